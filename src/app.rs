@@ -5,7 +5,7 @@ use crate::browser::{
     util::{self, window, ClosureNew},
     NextTick, Url,
 };
-use crate::virtual_dom::{patch, El, Mailbox, Node, Tag, View};
+use crate::virtual_dom::{patch, El, Mailbox, Tag, View};
 use builder::{
     init::{Init, InitFn},
     IntoAfterMount, MountPointInitInitAPI, UndefinedInitAPI, UndefinedMountPoint,
@@ -27,6 +27,7 @@ pub mod builder;
 pub mod cfg;
 pub mod data;
 pub mod effects;
+pub mod error_boundary;
 pub mod message_mapper;
 pub mod orders;
 pub mod render_timestamp_delta;
@@ -38,6 +39,7 @@ pub use builder::{
 pub use cfg::{AppCfg, AppInitCfg};
 pub use data::AppData;
 pub use effects::Effect;
+pub use error_boundary::{RenderPanic, RenderPhase};
 pub use message_mapper::MessageMapper;
 pub use orders::{Orders, OrdersContainer, OrdersProxy};
 pub use render_timestamp_delta::RenderTimestampDelta;
@@ -200,6 +202,7 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
         mount_point: Element,
         routes: Option<RoutesFn<Ms>>,
         window_events: Option<WindowEventsFn<Ms, Mdl>>,
+        error_boundary: Option<ErrorBoundaryFn<Ms>>,
         init_cfg: OptDynInitCfg<Ms, Mdl, ElC, GMs>,
     ) -> Self {
         let window = util::window();
@@ -214,6 +217,7 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
                 sink,
                 view,
                 window_events,
+                error_boundary,
             }),
             data: Rc::new(AppData {
                 model: RefCell::new(None),
@@ -273,17 +277,9 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
             // Attach all top-level elements to the mount point if present. This means that we have
             // effectively taken full control of everything within the mounting element.
             for child in &mut new.children {
-                match child {
-                    Node::Element(child_el) => {
-                        virtual_dom_bridge::attach_el_and_children(child_el, &self.cfg.mount_point);
-                        patch::attach_listeners(child_el, &self.mailbox());
-                    }
-                    Node::Text(top_child_text) => {
-                        virtual_dom_bridge::attach_text_node(top_child_text, &self.cfg.mount_point);
-                    }
-                    Node::Empty => (),
-                }
+                virtual_dom_bridge::attach_node(child, &self.cfg.mount_point);
             }
+            patch::attach_nodes_listeners(&mut new.children, &self.mailbox());
         }
 
         new
@@ -385,6 +381,10 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
     fn rerender_vdom(&self) {
         let new_render_timestamp = window().performance().expect("get `Performance`").now();
 
+        if let Some(error_boundary) = self.cfg.error_boundary {
+            error_boundary::start_render(&self.cfg.mount_point, error_boundary);
+        }
+
         // Create a new vdom: The top element, and all its children. Does not yet
         // have associated web_sys elements.
         let mut new = El::empty(Tag::Placeholder);
@@ -401,15 +401,25 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
         // We'll get a runtime panic if any are left un-removed.
         patch::detach_listeners(&mut old);
 
+        // Patching may recreate the focused element (eg if its parent's tag changes),
+        // so remember it to focus the same element or its replacement again.
+        let focus = virtual_dom_bridge::capture_focus(&self.cfg.mount_point);
+
+        error_boundary::start_patch();
         patch::patch_els(
             &self.cfg.document,
             &self.mailbox(),
             &self.clone(),
             &self.cfg.mount_point,
-            old.children.into_iter(),
-            new.children.iter_mut(),
+            old.children,
+            &mut new.children,
         );
 
+        if let Some(focus) = focus {
+            virtual_dom_bridge::restore_focus(&self.cfg.mount_point, focus);
+        }
+        error_boundary::end_render();
+
         // Now that we've re-rendered, replace our stored El with the new one;
         // it will be used as the old El next time.
         self.data.main_el_vdom.borrow_mut().replace(new);
@@ -521,7 +531,11 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
                 }),
                 routes,
             );
-            routing::setup_link_listener(enclose!((self => s) move |msg| s.update(msg)), routes);
+            routing::setup_link_listener(
+                enclose!((self => s) move |msg| s.update(msg)),
+                routes,
+                &self.cfg.mount_point,
+            );
         }
 
         self.process_cmd_and_msg_queue(orders.effects);
@@ -115,6 +115,7 @@ impl<
             mount_point.element_getter()(),
             builder.routes,
             builder.window_events,
+            builder.error_boundary,
             None,
         );
 
@@ -158,6 +159,7 @@ impl<
             mount_point_getter(),
             builder.routes,
             builder.window_events,
+            builder.error_boundary,
             Some(AppInitCfg {
                 mount_type,
                 into_after_mount: Box::new(into_after_mount),
@@ -179,6 +181,7 @@ impl<Ms: 'static, Mdl: 'static + Default, ElC: 'static + View<Ms>, GMs: 'static>
             routes: builder.routes,
             window_events: builder.window_events,
             sink: builder.sink,
+            error_boundary: builder.error_boundary,
 
             init_api: BeforeAfterInitAPI::default(),
         })
@@ -347,6 +350,7 @@ pub struct Builder<Ms: 'static, Mdl: 'static, ElC: View<Ms>, GMs, InitAPIType> {
     routes: Option<RoutesFn<Ms>>,
     window_events: Option<WindowEventsFn<Ms, Mdl>>,
     sink: Option<SinkFn<Ms, Mdl, ElC, GMs>>,
+    error_boundary: Option<ErrorBoundaryFn<Ms>>,
 
     // TODO: Remove when removing legacy init fields.
     init_api: InitAPIType,
@@ -362,6 +366,7 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> Builder<Ms, Mdl, ElC, GMs,
             routes: None,
             window_events: None,
             sink: None,
+            error_boundary: None,
 
             init_api: UndefinedInitAPI,
         }
@@ -394,6 +399,7 @@ impl<
             routes: self.routes,
             window_events: self.window_events,
             sink: self.sink,
+            error_boundary: self.error_boundary,
 
             init_api: self.init_api.init(new_init),
         }
@@ -431,6 +437,7 @@ impl<
             routes: self.routes,
             window_events: self.window_events,
             sink: self.sink,
+            error_boundary: self.error_boundary,
 
             init_api: self.init_api.mount(new_mount_point),
         }
@@ -460,6 +467,7 @@ impl<
             routes: self.routes,
             window_events: self.window_events,
             sink: self.sink,
+            error_boundary: self.error_boundary,
 
             init_api: self.init_api.before_mount(Box::new(before_mount)),
         }
@@ -488,6 +496,7 @@ impl<
             routes: self.routes,
             window_events: self.window_events,
             sink: self.sink,
+            error_boundary: self.error_boundary,
 
             init_api: self.init_api.after_mount(after_mount),
         }
@@ -545,6 +554,27 @@ impl<
         self.sink = Some(sink);
         self
     }
+
+    /// Registers a function which renders the fallback when the app panics while rendering.
+    ///
+    /// A panic aborts the app - it can't be caught - but with an error boundary, the mount
+    /// point's content is replaced with the fallback (rendered as static HTML, without listeners)
+    /// and the panic is logged together with the elements being patched, so view bugs are easier
+    /// to find. Tracking the elements adds a small overhead to each render.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///# use seed::{prelude::*, *};
+    ///# enum Msg {}
+    ///fn error_boundary(render_panic: &RenderPanic) -> Node<Msg> {
+    ///    div!["Something went wrong, please reload the page."]
+    ///}
+    /// ```
+    pub fn error_boundary(mut self, error_boundary: ErrorBoundaryFn<Ms>) -> Self {
+        self.error_boundary = Some(error_boundary);
+        self
+    }
 }
 
 impl<
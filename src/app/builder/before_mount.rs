@@ -75,6 +75,8 @@ impl BeforeMount {
 
     /// Choose the element where the application will be mounted.
     /// The default one is the element with `id` = "app".
+    /// More apps can coexist on one page (eg widgets in a server-rendered page),
+    /// each mounted into its own element. Links are routed only inside of the app's element.
     ///
     /// # Examples
     ///
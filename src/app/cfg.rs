@@ -28,4 +28,5 @@ where
     pub sink: Option<SinkFn<Ms, Mdl, ElC, GMs>>,
     pub view: ViewFn<Mdl, ElC>,
     pub window_events: Option<WindowEventsFn<Ms, Mdl>>,
+    pub error_boundary: Option<ErrorBoundaryFn<Ms>>,
 }
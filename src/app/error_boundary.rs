@@ -0,0 +1,165 @@
+//! This module contains the error boundary of rendering; see `AppBuilder::error_boundary`.
+//!
+//! Panics abort Wasm apps, so they can't be caught and the app can't continue. However,
+//! the panic hook runs before the abort - it logs where in the vdom the app panicked
+//! and replaces the mount point's content with the fallback.
+
+use crate::virtual_dom::{html, Node, Tag};
+use std::{cell::RefCell, fmt, panic, sync::Once};
+
+/// What the app was doing when it panicked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderPhase {
+    /// Calling the `view` function.
+    View,
+    /// Patching the DOM with the new vdom.
+    Patch,
+}
+
+/// Description of a panic during rendering, passed to the fallback.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderPanic {
+    /// The panic message with its location.
+    pub message: String,
+    pub phase: RenderPhase,
+    /// Tags of the elements being patched, from the top-level one; empty if the app
+    /// didn't panic during patching.
+    pub path: Vec<String>,
+}
+
+impl fmt::Display for RenderPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.phase {
+            RenderPhase::View => write!(f, "The view panicked: {}", self.message),
+            RenderPhase::Patch if self.path.is_empty() => {
+                write!(f, "Patching panicked: {}", self.message)
+            }
+            RenderPhase::Patch => write!(
+                f,
+                "Patching `{}` panicked: {}",
+                self.path.join(" > "),
+                self.message
+            ),
+        }
+    }
+}
+
+struct ActiveRender {
+    mount_point: web_sys::Element,
+    fallback_html: Box<dyn Fn(&RenderPanic) -> String>,
+    phase: RenderPhase,
+    /// Only the tags are kept, so entering an element is cheap; they're formatted on panic.
+    path: Vec<Tag>,
+}
+
+thread_local! {
+    static ACTIVE_RENDER: RefCell<Option<ActiveRender>> = RefCell::default();
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Guard the render of the app mounted into the `mount_point` until `end_render`.
+pub(crate) fn start_render<Ms: 'static>(
+    mount_point: &web_sys::Element,
+    fallback: fn(&RenderPanic) -> Node<Ms>,
+) {
+    INSTALL_HOOK.call_once(|| {
+        // Keep the previous hook - eg `console_error_panic_hook` - so it still reports the panic.
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            show_fallback(&info.to_string());
+            previous_hook(info);
+        }));
+    });
+    ACTIVE_RENDER.with(|active| {
+        active.replace(Some(ActiveRender {
+            mount_point: mount_point.clone(),
+            fallback_html: Box::new(move |panic| html::node_to_html(&fallback(panic))),
+            phase: RenderPhase::View,
+            path: Vec::new(),
+        }))
+    });
+}
+
+pub(crate) fn start_patch() {
+    with_active_render(|render| render.phase = RenderPhase::Patch);
+}
+
+pub(crate) fn end_render() {
+    ACTIVE_RENDER.with(|active| active.replace(None));
+}
+
+/// Pops the element from the path when dropped.
+pub(crate) struct PathGuard;
+
+impl Drop for PathGuard {
+    fn drop(&mut self) {
+        with_active_render(|render| {
+            render.path.pop();
+        });
+    }
+}
+
+/// Push the element to the path of the guarded render (if any) while it's being patched.
+pub(crate) fn enter_node<Ms>(node: &Node<Ms>) -> Option<PathGuard> {
+    let el = match node {
+        Node::Element(el) => el,
+        _ => return None,
+    };
+    with_active_render(|render| render.path.push(el.tag.clone())).map(|_| PathGuard)
+}
+
+fn with_active_render<T>(f: impl FnOnce(&mut ActiveRender) -> T) -> Option<T> {
+    ACTIVE_RENDER.with(|active| active.borrow_mut().as_mut().map(f))
+}
+
+fn show_fallback(message: &str) {
+    ACTIVE_RENDER.with(|active| {
+        // The panic may come from the code borrowing it.
+        let active = match active.try_borrow() {
+            Ok(active) => active,
+            Err(_) => return,
+        };
+        if let Some(render) = active.as_ref() {
+            let render_panic = RenderPanic {
+                message: message.to_owned(),
+                phase: render.phase,
+                path: render
+                    .path
+                    .iter()
+                    .map(|tag| tag.name().into_owned())
+                    .collect(),
+            };
+            crate::error(&render_panic.to_string());
+            render
+                .mount_point
+                .set_inner_html(&(render.fallback_html)(&render_panic));
+        }
+    });
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn render_panic_display() {
+        let mut render_panic = RenderPanic {
+            message: "index out of bounds".into(),
+            phase: RenderPhase::Patch,
+            path: vec!["div".into(), "ul".into(), "li".into()],
+        };
+        assert_eq!(
+            render_panic.to_string(),
+            "Patching `div > ul > li` panicked: index out of bounds"
+        );
+
+        render_panic.phase = RenderPhase::View;
+        render_panic.path.clear();
+        assert_eq!(
+            render_panic.to_string(),
+            "The view panicked: index out of bounds"
+        );
+    }
+}
@@ -1,10 +1,11 @@
-use super::OrdersContainer;
+use super::{OrdersContainer, RenderPanic};
 use crate::browser::Url;
-use crate::virtual_dom::Listener;
+use crate::virtual_dom::{Listener, Node};
 
 pub type UpdateFn<Ms, Mdl, ElC, GMs> = fn(Ms, &mut Mdl, &mut OrdersContainer<Ms, Mdl, ElC, GMs>);
 pub type SinkFn<Ms, Mdl, ElC, GMs> = fn(GMs, &mut Mdl, &mut OrdersContainer<Ms, Mdl, ElC, GMs>);
 pub type ViewFn<Mdl, ElC> = fn(&Mdl) -> ElC;
 pub type RoutesFn<Ms> = fn(Url) -> Option<Ms>;
 pub type WindowEventsFn<Ms, Mdl> = fn(&Mdl) -> Vec<Listener<Ms>>;
+pub type ErrorBoundaryFn<Ms> = fn(&RenderPanic) -> Node<Ms>;
 pub type MsgListeners<Ms> = Vec<Box<dyn Fn(&Ms)>>;
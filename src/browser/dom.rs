@@ -3,9 +3,14 @@
 
 pub mod cast;
 pub mod css_units;
+pub mod el_ref;
 pub mod event_handler;
+pub mod key_combo;
 pub mod lifecycle_hooks;
 pub mod namespace;
+pub mod observers;
+pub mod scoped_styles;
+pub mod svg_path;
 pub mod virtual_dom_bridge;
 
 pub use lifecycle_hooks::LifecycleHooks;
@@ -217,6 +222,1120 @@ pub mod tests {
         assert_eq!(expected, get_node_html(&node));
     }
 
+    /// Tests that methods `append_html` and `prepend_html` add parsed nodes around existing children
+    #[wasm_bindgen_test]
+    pub fn append_and_prepend_html() {
+        let expected = "<div><b>first</b>middle<i>last</i></div>";
+
+        let mut e = El::empty(seed::virtual_dom::Tag::Div);
+        e.add_text("middle");
+        e.append_html("<i>last</i>");
+        e.prepend_html("<b>first</b>");
+        let node = el_to_websys(Node::Element(e));
+
+        assert_eq!(expected, get_node_html(&node));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn transition() {
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Div);
+        e.transition(
+            &[St::Opacity, St::Transform],
+            std::time::Duration::from_millis(250),
+            seed::virtual_dom::Easing::EaseInOut,
+        );
+        assert_eq!(
+            e.style.vals[&St::Transition],
+            CSSValue::Some("opacity 250ms ease-in-out, transform 250ms ease-in-out".into())
+        );
+
+        e.transition(
+            &[],
+            std::time::Duration::from_secs(1),
+            seed::virtual_dom::Easing::CubicBezier(0.1, 0.7, 1., 0.1),
+        );
+        assert_eq!(
+            e.style.vals[&St::Transition],
+            CSSValue::Some("all 1000ms cubic-bezier(0.1, 0.7, 1, 0.1)".into())
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn labelled_input() {
+        let mut input = El::empty(seed::virtual_dom::Tag::Input);
+        input.add_attr("id", "name");
+        let field: El<Msg> = El::labelled_input("Name", Some("Your full name"), input);
+        let node = el_to_websys(Node::Element(field));
+
+        assert_eq!(
+            get_node_html(&node),
+            "<div><label for=\"name\">Name</label>\
+             <input id=\"name\" aria-describedby=\"name-description\">\
+             <p id=\"name-description\">Your full name</p></div>"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn labelled_input_generated_id() {
+        let input = El::empty(seed::virtual_dom::Tag::Input);
+        let field: El<Msg> = El::labelled_input("Name", None, input);
+
+        let id = match &field.children[1] {
+            Node::Element(input) => input.attrs.vals[&At::Id].clone(),
+            _ => panic!("the input should be the second child"),
+        };
+        match &field.children[0] {
+            Node::Element(label) => assert_eq!(label.attrs.vals[&At::For], id),
+            _ => panic!("the label should be the first child"),
+        }
+        assert_eq!(field.children.len(), 2);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn live_region() {
+        use seed::virtual_dom::Politeness;
+
+        let mut region: El<Msg> = El::live_region(Politeness::Assertive);
+        region.add_text("Invalid email");
+        let node = el_to_websys(Node::Element(region));
+
+        assert_eq!(
+            get_node_html(&node),
+            "<div role=\"alert\" aria-atomic=\"true\" aria-live=\"assertive\">Invalid email</div>"
+        );
+
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Div);
+        e.aria_live(Politeness::Polite);
+        assert_eq!(
+            e.attrs.vals[&At::AriaLive],
+            seed::virtual_dom::AtValue::Some("polite".into())
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn custom_event_detail() {
+        use crate::dom_entity_names::Ev;
+        use std::{cell::RefCell, rc::Rc};
+
+        assert_eq!(Ev::custom("click"), Ev::Click);
+        assert_eq!(Ev::custom("color-picked").as_str(), "color-picked");
+
+        let received: Rc<RefCell<Option<String>>> = Default::default();
+        let mailbox = Mailbox::new({
+            let received = received.clone();
+            move |detail: Option<String>| *received.borrow_mut() = detail
+        });
+        let mut listener = seed::prelude::custom_ev("color-picked", |detail| detail.as_string());
+        let el_ws = util::document().create_element("div").unwrap();
+        listener.attach(&el_ws, mailbox);
+
+        let mut init = web_sys::CustomEventInit::new();
+        init.detail(&JsValue::from_str("red"));
+        let event = web_sys::CustomEvent::new_with_event_init_dict("color-picked", &init).unwrap();
+        el_ws.dispatch_event(&event).unwrap();
+
+        assert_eq!(*received.borrow(), Some("red".to_string()));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn animation_end_filtered() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let received: Rc<RefCell<Vec<&str>>> = Default::default();
+        let mailbox = Mailbox::new({
+            let received = received.clone();
+            move |msg: &'static str| received.borrow_mut().push(msg)
+        });
+        let mut e: El<&str> = El::empty(seed::virtual_dom::Tag::Div);
+        e.on_animation_end("fade-out", || "faded");
+        let el_ws = util::document().create_element("div").unwrap();
+        e.listeners[0].attach(&el_ws, mailbox);
+        (e.hooks.did_mount.unwrap().actions)(&el_ws);
+
+        for name in &["pulse", "fade-out"] {
+            let mut init = web_sys::AnimationEventInit::new();
+            init.animation_name(name);
+            let event =
+                web_sys::AnimationEvent::new_with_event_init_dict("animationend", &init).unwrap();
+            el_ws.dispatch_event(&event).unwrap();
+        }
+
+        assert_eq!(*received.borrow(), vec!["faded"]);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn autofocus_attr_and_focus_on_insert() {
+        use seed::virtual_dom::{AtValue, Tag};
+
+        let mut e: El<Msg> = El::empty(Tag::Input);
+        e.autofocus_attr();
+        assert_eq!(e.attrs.vals.get(&At::AutoFocus), Some(&AtValue::None));
+
+        let document = util::document();
+        let mut e: El<Msg> = El::empty(Tag::Input);
+        e.focus_on_insert();
+        assert!(e.attrs.vals.get(&At::AutoFocus).is_none());
+        let input = document.create_element("input").unwrap();
+        util::body().append_child(&input).unwrap();
+        (e.hooks.did_mount.unwrap().actions)(&input);
+        assert!(document
+            .active_element()
+            .unwrap()
+            .is_same_node(Some(&input)));
+        input.remove();
+    }
+
+    #[wasm_bindgen_test]
+    pub fn paste_plain_text() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let received: Rc<RefCell<Vec<String>>> = Default::default();
+        let mailbox = Mailbox::new({
+            let received = received.clone();
+            move |msg: String| received.borrow_mut().push(msg)
+        });
+        let mut e: El<String> = El::empty(seed::virtual_dom::Tag::Div);
+        e.on_paste(|text| text);
+        let el_ws = util::document().create_element("div").unwrap();
+        e.listeners[0].attach(&el_ws, mailbox);
+
+        let mut init = web_sys::EventInit::new();
+        init.cancelable(true);
+        let event = web_sys::Event::new_with_event_init_dict("paste", &init).unwrap();
+        let data = web_sys::DataTransfer::new().unwrap();
+        data.set_data("text/html", "<b>bold</b>").unwrap();
+        data.set_data("text/plain", "bold").unwrap();
+        js_sys::Reflect::set(&event, &JsValue::from_str("clipboardData"), &data).unwrap();
+        el_ws.dispatch_event(&event).unwrap();
+
+        assert_eq!(*received.borrow(), vec!["bold".to_owned()]);
+        assert!(event.default_prevented());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn detach_ws() {
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Div);
+        assert!(e.detach_ws().is_none());
+
+        e.add_text("text");
+        let node = el_to_websys(Node::Element(e.clone()));
+        let parent = node.parent_node().unwrap();
+        e.node_ws = Some(node.clone());
+
+        let detached = e.detach_ws().unwrap();
+        assert!(detached.is_same_node(Some(&node)));
+        assert!(parent.first_child().is_none());
+        assert_eq!(e.children.len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn clone_is_unmounted() {
+        let document = crate::util::document();
+        let mut item: El<Msg> = El::empty(seed::virtual_dom::Tag::Li);
+        item.add_text("a");
+        item.node_ws = Some(document.create_element("li").unwrap().into());
+        if let Node::Text(text) = &mut item.children[0] {
+            text.node_ws = Some(document.create_text_node("a").into());
+        }
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Ul);
+        e.add_child(Node::Element(item));
+        e.node_ws = Some(document.create_element("ul").unwrap().into());
+
+        let clone = e.clone();
+        assert!(clone.node_ws.is_none());
+        match &clone.children[0] {
+            Node::Element(item) => {
+                assert!(item.node_ws.is_none());
+                assert!(item.children[0].text().unwrap().node_ws.is_none());
+            }
+            _ => panic!("not an El node"),
+        }
+
+        let kept = e.clone_keeping_ws();
+        assert!(kept
+            .node_ws
+            .as_ref()
+            .unwrap()
+            .is_same_node(e.node_ws.as_ref()));
+        match &kept.children[0] {
+            Node::Element(item) => {
+                assert!(item.node_ws.is_some());
+                assert!(item.children[0].text().unwrap().node_ws.is_some());
+            }
+            _ => panic!("not an El node"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    pub fn nodes_inserted_next_to_self() {
+        let document = crate::util::document();
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Div);
+        let node = document.create_element("hr").unwrap();
+        assert!(!e.insert_before_self(&node));
+
+        let node_ws = el_to_websys(Node::Element(e.clone()));
+        let parent = node_ws.parent_node().unwrap();
+        e.node_ws = Some(node_ws);
+
+        assert!(e.insert_before_self(&document.create_element("span").unwrap()));
+        assert!(e.insert_after_self(&document.create_element("p").unwrap()));
+        assert!(e.insert_after_self(&document.create_element("b").unwrap()));
+        assert_eq!(
+            parent.dyn_ref::<web_sys::Element>().unwrap().inner_html(),
+            "<span></span><div></div><b></b><p></p>"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn set_inner_text() {
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Div);
+        e.add_child(Node::Element(El::empty(seed::virtual_dom::Tag::Span)))
+            .add_text("old")
+            .set_inner_text("<b>not bold</b>");
+        let node = el_to_websys(Node::Element(e));
+
+        assert_eq!(
+            get_node_html(&node),
+            "<div>&lt;b&gt;not bold&lt;/b&gt;</div>"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn hook_builders() {
+        use seed::browser::dom::lifecycle_hooks::{did_mount, LifecycleHooks};
+        use std::{cell::RefCell, rc::Rc};
+
+        let calls: Rc<RefCell<Vec<&str>>> = Default::default();
+        let record = |name: &'static str| {
+            let calls = calls.clone();
+            move |_: &web_sys::Node| calls.borrow_mut().push(name)
+        };
+        let node: web_sys::Node = util::document().create_element("div").unwrap().into();
+
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Div);
+        e.on_insert(record("insert 1"))
+            .on_insert(record("insert 2"))
+            .on_update(record("update"))
+            .on_remove(record("remove"));
+        (e.hooks.did_mount.as_mut().unwrap().actions)(&node);
+        (e.hooks.did_update.as_mut().unwrap().actions)(&node);
+        (e.hooks.will_unmount.as_mut().unwrap().actions)(&node);
+        assert_eq!(
+            *calls.borrow(),
+            vec!["insert 1", "insert 2", "update", "remove"]
+        );
+
+        let mut hooks = LifecycleHooks::new();
+        hooks.did_mount = Some(did_mount(record("prebuilt")));
+        e.set_hooks(hooks);
+        assert!(e.hooks.did_update.is_none());
+        assert!(e.hooks.will_unmount.is_none());
+        calls.borrow_mut().clear();
+        (e.hooks.did_mount.as_mut().unwrap().actions)(&node);
+        assert_eq!(*calls.borrow(), vec!["prebuilt"]);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn on_resize_sets_hooks() {
+        let mut e = El::empty(seed::virtual_dom::Tag::Div);
+        e.on_resize(|rect| rect.width);
+
+        assert_eq!(e.listeners.len(), 1);
+        assert!(e.hooks.did_mount.is_some());
+        assert!(e.hooks.will_unmount.is_some());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn on_intersect_sets_hooks() {
+        let mut e = El::empty(seed::virtual_dom::Tag::Div);
+        let options = seed::prelude::IntersectOptions {
+            root_margin: Some("100px".into()),
+            thresholds: vec![0., 0.5],
+        };
+        e.on_intersect(options, |info| info.is_intersecting);
+
+        assert_eq!(e.listeners.len(), 1);
+        assert!(e.hooks.did_mount.is_some());
+        assert!(e.hooks.will_unmount.is_some());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn inner_html() {
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Div);
+        e.add_text("vdom");
+        assert_eq!(e.inner_html(), Some("vdom".to_string()));
+
+        let node = el_to_websys(Node::Element(e.clone()));
+        node.dyn_ref::<Element>()
+            .unwrap()
+            .set_inner_html("<i>live</i>");
+        e.node_ws = Some(node);
+        assert_eq!(e.inner_html(), Some("<i>live</i>".to_string()));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn set_and_merge_style_and_attrs() {
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Div);
+        e.add_style(St::Color, "red").add_attr("id", "old");
+
+        e.merge_style(style![St::Display => "flex"])
+            .merge_attrs(attrs! {At::Title => "title"});
+        assert_eq!(e.style.vals.len(), 2);
+        assert_eq!(e.attrs.vals.len(), 2);
+
+        e.set_style(style![St::Color => "blue"])
+            .set_attrs(attrs! {At::Id => "new"});
+        assert_eq!(e.style, style![St::Color => "blue"]);
+        assert_eq!(e.attrs, attrs! {At::Id => "new"});
+    }
+
+    #[wasm_bindgen_test]
+    pub fn input_ev_number_parses_value() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let received: Rc<RefCell<Vec<Option<f64>>>> = Default::default();
+        let mailbox = Mailbox::new({
+            let received = received.clone();
+            move |value: Option<f64>| received.borrow_mut().push(value)
+        });
+        let mut listener = seed::prelude::input_ev_number("input", |value| value);
+        let input = util::document()
+            .create_element("input")
+            .unwrap()
+            .dyn_into::<web_sys::HtmlInputElement>()
+            .unwrap();
+        listener.attach(&input, mailbox);
+
+        for value in &["4.5", "", "abc", "inf", "NaN"] {
+            input.set_value(value);
+            input
+                .dispatch_event(&web_sys::Event::new("input").unwrap())
+                .unwrap();
+        }
+        assert_eq!(*received.borrow(), vec![Some(4.5), None, None, None, None]);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn form_control_attrs() {
+        use seed::virtual_dom::{AutoCapitalize, Autocomplete, EnterKeyHint, InputMode};
+
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Input);
+        e.input_mode(InputMode::Decimal)
+            .enter_key_hint(EnterKeyHint::Next)
+            .autocomplete(Autocomplete::OneTimeCode)
+            .autocapitalize(AutoCapitalize::Off)
+            .autocorrect(false)
+            .spellcheck(false);
+        let node = el_to_websys(Node::Element(e));
+
+        assert_eq!(
+            get_node_html(&node),
+            "<input inputmode=\"decimal\" enterkeyhint=\"next\" autocomplete=\"one-time-code\" \
+             autocapitalize=\"off\" autocorrect=\"off\" spellcheck=\"false\">"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn map_children() {
+        use seed::virtual_dom::Tag;
+
+        let wrap = |node: Node<Msg>| {
+            let mut wrapper = El::empty(Tag::Span);
+            wrapper.add_child(node);
+            Node::Element(wrapper)
+        };
+        let mut item = El::empty(Tag::Li);
+        item.add_text("a");
+        let mut e: El<Msg> = El::empty(Tag::Ul);
+        e.add_child(Node::Element(item)).add_text("b");
+
+        let mut shallow = e.clone();
+        shallow.map_children(wrap);
+        assert_eq!(
+            get_node_html(&el_to_websys(Node::Element(shallow))),
+            "<ul><span><li>a</li></span><span>b</span></ul>"
+        );
+
+        e.map_children_deep(|node| match node {
+            Node::Text(_) => wrap(node),
+            node => node,
+        });
+        assert_eq!(
+            get_node_html(&el_to_websys(Node::Element(e))),
+            "<ul><li><span>a</span></li><span>b</span></ul>"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn style_units() {
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Div);
+        e.add_style_px(St::Width, 12.5)
+            .add_style_rem(St::Margin, 1.)
+            .add_style_percent(St::Height, 50.);
+
+        assert_eq!(e.style.to_string(), "width:12.5px;margin:1rem;height:50%");
+
+        e.add_style_px(St::Width, f64::INFINITY)
+            .add_style_rem(St::Margin, f64::NAN)
+            .add_style_percent(St::Height, f64::NEG_INFINITY);
+        assert_eq!(e.style.to_string(), "");
+    }
+
+    #[wasm_bindgen_test]
+    pub fn collapse_redundant_wrappers() {
+        use seed::virtual_dom::Tag;
+
+        let wrap = |tag: Tag, child: El<Msg>| {
+            let mut wrapper = El::empty(tag);
+            wrapper.add_child(Node::Element(child));
+            wrapper
+        };
+        let mut item = El::empty(Tag::P);
+        item.add_text("a");
+        let mut styled = wrap(Tag::Div, item.clone());
+        styled.add_style(St::Color, "red");
+        let mut text_wrapper = El::empty(Tag::Span);
+        text_wrapper.add_text("b");
+
+        let mut e: El<Msg> = El::empty(Tag::Section);
+        e.add_child(Node::Element(wrap(Tag::Div, wrap(Tag::Span, item))))
+            .add_child(Node::Element(styled))
+            .add_child(Node::Element(text_wrapper));
+        let node = el_to_websys(Node::Element(e.collapse_redundant_wrappers()));
+
+        assert_eq!(
+            get_node_html(&node),
+            "<section><p>a</p><div style=\"color:red\"><p>a</p></div>b</section>"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn from_markdown_blocks() {
+        use seed::virtual_dom::html::children_to_html;
+
+        let markdown = "# Title\n\nSome *text*.\n\n- a\n- b\n\n<div>\nhtml\n</div>\n\n---\n";
+
+        let mut blocks = El::<Msg>::from_markdown_blocks(markdown);
+        match blocks.next() {
+            Some(Node::Element(heading)) => assert_eq!(heading.tag, seed::virtual_dom::Tag::H1),
+            _ => panic!("the first node should be the heading"),
+        }
+
+        let blocks: Vec<Node<Msg>> = El::from_markdown_blocks(markdown).collect();
+        assert_eq!(
+            children_to_html(&blocks),
+            children_to_html(&El::<Msg>::from_markdown(markdown))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn tag_names() {
+        use seed::virtual_dom::Tag;
+
+        let e: El<Msg> = El::empty(Tag::Custom("DIV".into()));
+        assert!(e.is_tag(&Tag::Div));
+        assert!(!e.is_tag(&Tag::Span));
+
+        assert_eq!(Tag::Script.name(), "script");
+        assert_eq!(Tag::LinearGradient.name(), "linearGradient");
+        assert_eq!(Tag::Custom("My-Element".into()).name(), "my-element");
+    }
+
+    #[wasm_bindgen_test]
+    pub fn wrap() {
+        use seed::virtual_dom::Tag;
+
+        let mut container = El::empty(Tag::Div);
+        container.add_class("container").add_text("Title");
+        let e: El<Msg> = El::empty(Tag::P).wrap(container);
+
+        assert_eq!(
+            get_node_html(&el_to_websys(Node::Element(e))),
+            "<div class=\"container\">Title<p></p></div>"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn intersperse() {
+        let links = vec![
+            Node::new_text("a"),
+            Node::Empty,
+            Node::new_text("b"),
+            Node::new_text("c"),
+        ];
+        let nodes: Vec<Node<Msg>> = seed::virtual_dom::intersperse(links, || Node::new_text(" / "));
+
+        assert_eq!(
+            nodes.iter().map(Node::get_text).collect::<String>(),
+            "a / b / c"
+        );
+        assert_eq!(nodes.len(), 5);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn with_children_capacity() {
+        use seed::virtual_dom::Tag;
+
+        let mut e: El<Msg> = El::with_children_capacity(Tag::Ul, 1000);
+        assert_eq!(e.tag, Tag::Ul);
+        assert!(e.children.is_empty());
+        assert!(e.children.capacity() >= 1000);
+
+        for _ in 0..1000 {
+            e.add_child(Node::Element(El::empty(Tag::Li)));
+        }
+        assert_eq!(e.children.len(), 1000);
+        assert!(get_node_html(&el_to_websys(Node::Element(e))).starts_with("<ul><li></li>"));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn empty_and_text_length() {
+        use seed::virtual_dom::Tag;
+
+        let mut e: El<Msg> = El::empty(Tag::Div);
+        assert!(e.is_empty());
+        assert!(!e.has_children());
+
+        e.add_child(Node::Empty).add_text(" \n ");
+        assert!(e.is_empty());
+        assert!(e.has_children());
+        assert_eq!(e.text_content_length(), 3);
+
+        let mut item = El::empty(Tag::Span);
+        item.add_text("čaj");
+        e.add_child(Node::Element(item));
+        assert!(!e.is_empty());
+        assert_eq!(e.text_content_length(), 6);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn clone_with_rebuilt_listeners() {
+        use seed::{
+            dom_entity_names::Ev,
+            prelude::raw_ev,
+            virtual_dom::{Listener, Tag},
+        };
+
+        fn on_click(el: &El<Msg>) -> Vec<Listener<Msg>> {
+            if el.is_tag(&Tag::Button) {
+                vec![raw_ev(Ev::Click, |_| -> Msg { unreachable!() })]
+            } else {
+                Vec::new()
+            }
+        }
+
+        let mut form: El<Msg> = El::empty(Tag::Form);
+        form.add_child(Node::Element(El::empty(Tag::Button)))
+            .add_text("text");
+        // As if the form has been rendered.
+        crate::browser::dom::virtual_dom_bridge::assign_ws_nodes_to_el(
+            &util::document(),
+            &mut form,
+        );
+        form.auto_memo();
+        form.memo.as_mut().unwrap().content_hash = Some(form.content_hash());
+        let cloned = form.clone_with(on_click);
+
+        assert!(cloned.listeners.is_empty());
+        assert!(cloned.node_ws.is_none());
+        assert_eq!(cloned.memo_hash(), None);
+        match &cloned.children[..] {
+            [Node::Element(button), Node::Text(text)] => {
+                assert_eq!(button.listeners.len(), 1);
+                assert_eq!(button.listeners[0].trigger, Ev::Click);
+                assert!(button.node_ws.is_none());
+                assert_eq!(text.text, "text");
+                assert!(text.node_ws.is_none());
+            }
+            children => panic!("Unexpected children: {:?}", children),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    pub fn visually_hidden() {
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Span);
+        e.add_text("Close").visually_hidden();
+        let node = el_to_websys(Node::Element(e));
+
+        let style = node.dyn_ref::<web_sys::HtmlElement>().unwrap().style();
+        assert_eq!(style.get_property_value("position").unwrap(), "absolute");
+        assert_eq!(style.get_property_value("width").unwrap(), "1px");
+        assert_eq!(style.get_property_value("overflow").unwrap(), "hidden");
+        assert_eq!(style.get_property_value("white-space").unwrap(), "nowrap");
+        // Unlike `display: none`, it keeps the text in the accessibility tree.
+        assert_eq!(style.get_property_value("display").unwrap(), "");
+        assert_eq!(node.text_content(), Some("Close".into()));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn landmarks() {
+        use seed::virtual_dom::Role;
+
+        let html = |el: El<Msg>| get_node_html(&el_to_websys(Node::Element(el)));
+        assert_eq!(html(El::nav_landmark()), "<nav></nav>");
+        assert_eq!(
+            html(El::banner_landmark()),
+            r#"<header role="banner"></header>"#
+        );
+        assert_eq!(
+            html(El::landmark(Role::Search)),
+            r#"<div role="search"></div>"#
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn svg_string_imported() {
+        use seed::browser::dom::Namespace;
+        use seed::virtual_dom::{At, AtValue, Tag};
+        use wasm_bindgen::JsCast;
+
+        let nodes: Vec<Node<Msg>> = El::from_svg_string(
+            r#"<defs><linearGradient id="fade"><stop offset="0" stop-color="red"/></linearGradient></defs><rect fill="url(#fade)" width="10" height="10"/>"#,
+        );
+        let (defs, rect) = match &nodes[..] {
+            [Node::Element(defs), Node::Element(rect)] => (defs, rect),
+            nodes => panic!("Unexpected nodes: {:?}", nodes),
+        };
+        assert_eq!(defs.tag, Tag::Defs);
+        assert_eq!(rect.namespace, Some(Namespace::Svg));
+        assert_eq!(
+            rect.attrs.vals.get(&At::from("fill")),
+            Some(&AtValue::Some("url(#fade)".into()))
+        );
+        let gradient = match &defs.children[..] {
+            [Node::Element(gradient)] => gradient,
+            children => panic!("Unexpected children: {:?}", children),
+        };
+        assert_eq!(gradient.tag, Tag::LinearGradient);
+        assert_eq!(gradient.namespace, Some(Namespace::Svg));
+
+        let mut svg = El::empty_svg(Tag::Svg);
+        svg.children = nodes;
+        let svg_ws = el_to_websys(Node::Element(svg));
+        let gradient_ws = svg_ws
+            .dyn_ref::<Element>()
+            .unwrap()
+            .query_selector("linearGradient")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            gradient_ws.namespace_uri(),
+            Some(Namespace::Svg.as_str().to_string())
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn spread_props() {
+        use seed::{
+            browser::dom::event_handler::raw_ev,
+            virtual_dom::{AtValue, Ev, Props, Tag},
+        };
+
+        let mut props: Props<Msg> = Props::default();
+        props.attrs.add(At::Title, "Save");
+        props.attrs.add(At::Class, "primary");
+        props.style.add(St::Color, "red");
+        props.classes.push("large".into());
+        props
+            .listeners
+            .push(raw_ev(Ev::Click, |_| -> Msg { unreachable!() }));
+
+        let mut e: El<Msg> = El::empty(Tag::Button);
+        e.add_class("btn")
+            .add_style(St::Color, "blue")
+            .spread(props);
+
+        assert_eq!(e.classes(), vec!["btn", "large", "primary"]);
+        assert_eq!(
+            e.attrs.vals.get(&At::Title),
+            Some(&AtValue::Some("Save".into()))
+        );
+        assert_eq!(e.style.vals.get(&St::Color), Some(&"red".into()));
+        assert_eq!(e.listeners.len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn conditional_and_extended_children() {
+        use seed::virtual_dom::Tag;
+
+        let mut e: El<Msg> = El::empty(Tag::Ul);
+        e.add_child_if(false, Node::new_text("hidden"))
+            .add_child_if(true, Node::new_text("a"))
+            .extend_children(vec!["b", "c"].into_iter().map(Node::new_text));
+        assert_eq!(
+            get_node_html(&el_to_websys(Node::Element(e))),
+            "<ul>abc</ul>"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn listeners_for_trigger() {
+        use seed::{
+            browser::dom::event_handler::simple_ev,
+            virtual_dom::{Ev, Tag},
+        };
+
+        let mut e: El<()> = El::empty(Tag::Button);
+        e.add_listener(simple_ev(Ev::Click, ()))
+            .add_listener(simple_ev(Ev::Focus, ()))
+            .add_listener(simple_ev(Ev::Click, ()));
+        assert_eq!(e.listeners_for(Ev::Click).len(), 2);
+        assert_eq!(e.listeners_for(Ev::Blur).len(), 0);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn listener_triggers() {
+        use seed::{
+            browser::dom::event_handler::simple_ev,
+            virtual_dom::{Ev, Tag},
+        };
+
+        let mut e: El<()> = El::empty(Tag::Input);
+        assert!(e.listener_triggers().is_empty());
+
+        e.add_listener(simple_ev(Ev::Input, ()))
+            .add_listener(simple_ev(Ev::Blur, ()))
+            .add_listener(simple_ev(Ev::Input, ()));
+        assert_eq!(e.listener_triggers(), vec![Ev::Input, Ev::Blur, Ev::Input]);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn merge_listeners_from() {
+        use seed::{
+            browser::dom::event_handler::simple_ev,
+            virtual_dom::{Ev, Tag},
+        };
+
+        let mut e: El<()> = El::empty(Tag::Button);
+        e.add_listener(simple_ev(Ev::Click, ()));
+        let mut other = El::empty(Tag::Button);
+        other
+            .add_listener(simple_ev(Ev::Focus, ()))
+            .add_listener(simple_ev(Ev::Click, ()));
+
+        e.merge_listeners_from(&mut other);
+        assert_eq!(e.listener_triggers(), vec![Ev::Click, Ev::Focus, Ev::Click]);
+        assert!(other.listeners.is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn hover_style_injected() {
+        let mut style = Style::empty();
+        style.add(St::Color, "red");
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Button);
+        e.hover_style(style);
+        let class = e.classes().join(" ");
+        assert!(class.starts_with("seed-"));
+
+        let node = el_to_websys(Node::Element(e));
+        assert_eq!(node.dyn_ref::<Element>().unwrap().class_name(), class);
+        let rule = util::document()
+            .head()
+            .unwrap()
+            .last_element_child()
+            .unwrap()
+            .text_content();
+        assert_eq!(rule, Some(format!(".{}:hover{{color:red}}", class)));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn media_style_injected() {
+        let mut style = Style::empty();
+        style.add(St::Display, "none");
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Aside);
+        e.add_class("sidebar")
+            .media_style("(max-width: 600px)", style);
+        let classes = e
+            .classes()
+            .iter()
+            .map(|class| class.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(classes[0], "sidebar");
+
+        el_to_websys(Node::Element(e));
+        let rule = util::document()
+            .head()
+            .unwrap()
+            .last_element_child()
+            .unwrap()
+            .text_content();
+        assert_eq!(
+            rule,
+            Some(format!(
+                "@media (max-width: 600px){{.{}{{display:none}}}}",
+                classes[1]
+            ))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn debounced_listener() {
+        use seed::{
+            browser::dom::event_handler::{debounce, simple_ev},
+            virtual_dom::Ev,
+        };
+        use std::time::Duration;
+
+        let delay = Duration::from_millis(300);
+        let wrapped = debounce(simple_ev(Ev::Input, ()), delay);
+        let built = simple_ev(Ev::Input, ()).debounce(delay);
+
+        assert_eq!(wrapped.options.debounce, Some(delay));
+        assert_eq!(wrapped, built);
+        assert_ne!(wrapped, simple_ev(Ev::Input, ()));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn form_association() {
+        use seed::virtual_dom::{FormMethod, Tag};
+
+        let mut e: El<Msg> = El::empty(Tag::Button);
+        e.form_id("checkout")
+            .form_action("/drafts")
+            .form_method(FormMethod::Post);
+        assert_eq!(
+            get_node_html(&el_to_websys(Node::Element(e))),
+            r#"<button form="checkout" formaction="/drafts" formmethod="post"></button>"#
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn language() {
+        use seed::virtual_dom::Tag;
+
+        let mut e: El<Msg> = El::empty(Tag::Q);
+        e.lang("fr-CA");
+        assert_eq!(
+            get_node_html(&el_to_websys(Node::Element(e))),
+            r#"<q lang="fr-CA"></q>"#
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn text_direction() {
+        use seed::virtual_dom::{Dir, Tag};
+
+        let mut e: El<Msg> = El::empty(Tag::P);
+        e.dir(Dir::Auto);
+        assert_eq!(
+            get_node_html(&el_to_websys(Node::Element(e))),
+            r#"<p dir="auto"></p>"#
+        );
+
+        util::set_document_dir(Dir::Rtl);
+        let html = util::document().document_element().unwrap();
+        assert_eq!(html.get_attribute("dir"), Some("rtl".into()));
+        html.remove_attribute("dir").unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    pub fn content_hash() {
+        use seed::{
+            browser::dom::event_handler::simple_ev,
+            virtual_dom::{Ev, Tag},
+        };
+
+        let list = |text: &str| {
+            let mut item = El::empty(Tag::Li);
+            item.add_text(text.to_owned());
+            let mut list: El<()> = El::empty(Tag::Ul);
+            list.add_class("items").add_child(Node::Element(item));
+            list
+        };
+        let mut with_listener = list("a");
+        with_listener.add_listener(simple_ev(Ev::Click, ()));
+
+        assert_eq!(list("a").content_hash(), list("a").content_hash());
+        assert_eq!(list("a").content_hash(), with_listener.content_hash());
+        assert_ne!(list("a").content_hash(), list("b").content_hash());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn strip_listeners() {
+        use seed::{
+            browser::dom::{event_handler::simple_ev, lifecycle_hooks::did_mount},
+            virtual_dom::{Ev, Tag},
+        };
+
+        let mut button = El::empty(Tag::Button);
+        button.add_listener(simple_ev(Ev::Click, ()));
+        let mut hook = did_mount(|_| ());
+        hook.message = Some(());
+        button.hooks.did_mount = Some(hook);
+        let mut e: El<()> = El::empty(Tag::Div);
+        e.add_listener(simple_ev(Ev::Input, ()))
+            .add_child(Node::Portal {
+                target: "#modals".into(),
+                children: vec![Node::Element(button)],
+            })
+            .strip_listeners();
+
+        assert!(e.listeners.is_empty());
+        match &e.children[0] {
+            Node::Portal { children, .. } => match &children[0] {
+                Node::Element(button) => {
+                    assert!(button.listeners.is_empty());
+                    let hook = button.hooks.did_mount.as_ref().expect("hook is kept");
+                    assert!(hook.message.is_none());
+                }
+                _ => panic!("Not an element"),
+            },
+            _ => panic!("Not a portal"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    pub fn map_attrs() {
+        use seed::virtual_dom::{At, AtValue, Tag};
+
+        let mut img = El::empty(Tag::Img);
+        img.add_attr("src", "/cat.png").add_attr("alt", "cat");
+        let mut link = El::empty(Tag::A);
+        link.add_attr("href", "https://example.com/")
+            .add_child(Node::Element(img));
+        let mut e: El<Msg> = El::empty(Tag::Div);
+        e.add_attr("id", "/root")
+            .add_child(Node::Element(link))
+            .map_attrs(|key, value| match (key, value) {
+                (At::Src, AtValue::Some(url)) | (At::Href, AtValue::Some(url))
+                    if url.starts_with('/') =>
+                {
+                    url.insert_str(0, "https://example.com");
+                }
+                _ => (),
+            });
+
+        assert_eq!(
+            seed::virtual_dom::html::el_to_html(&e),
+            "<div id=\"/root\"><a href=\"https://example.com/\">\
+             <img src=\"https://example.com/cat.png\" alt=\"cat\"></a></div>"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn normalize() {
+        use seed::virtual_dom::Tag;
+
+        let mut e: El<Msg> = El::empty(Tag::P);
+        e.add_text("a")
+            .add_child(Node::Empty)
+            .add_text("b")
+            .add_text("")
+            .add_text("c")
+            .add_child(Node::Element(El::empty(Tag::Br)))
+            .add_text("d")
+            .normalize();
+
+        assert_eq!(e.children.len(), 3);
+        assert_eq!(e.children[0].text().unwrap().text, "abc");
+        assert_eq!(e.children[2].text().unwrap().text, "d");
+    }
+
+    #[wasm_bindgen_test]
+    pub fn disclosure_button() {
+        let mut e: El<Msg> = El::disclosure_button("details", false);
+        e.add_text("Details");
+        assert_eq!(
+            get_node_html(&el_to_websys(Node::Element(e))),
+            r#"<button type="button" aria-expanded="false" aria-controls="details">Details</button>"#
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn sized_canvas() {
+        use seed::virtual_dom::Tag;
+
+        let mut e: El<Msg> = El::empty(Tag::Canvas);
+        e.size(300, 150);
+        assert_eq!(
+            get_node_html(&el_to_websys(Node::Element(e))),
+            r#"<canvas width="300" height="150"></canvas>"#
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn lazy_image() {
+        use seed::virtual_dom::{Decoding, Tag};
+
+        let mut e: El<Msg> = El::empty(Tag::Img);
+        e.lazy_load().decoding(Decoding::Async);
+        assert_eq!(
+            get_node_html(&el_to_websys(Node::Element(e))),
+            r#"<img loading="lazy" decoding="async">"#
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn test_id() {
+        use seed::virtual_dom::Tag;
+
+        let mut e: El<Msg> = El::empty(Tag::Button);
+        e.test_id("submit");
+        let expected = if cfg!(any(debug_assertions, feature = "testing")) {
+            r#"<button data-testid="submit"></button>"#
+        } else {
+            "<button></button>"
+        };
+        assert_eq!(get_node_html(&el_to_websys(Node::Element(e))), expected);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn roving_tab_index() {
+        use seed::virtual_dom::{At, AtValue, Tag};
+
+        let mut toolbar: El<Msg> = El::empty(Tag::Div);
+        for _ in 0..3 {
+            toolbar
+                .add_child(Node::Element(El::empty(Tag::Button)))
+                .add_text(" ");
+        }
+        toolbar.roving_tab_index(1);
+
+        let tab_indexes = toolbar
+            .children
+            .iter()
+            .filter_map(|child| match child {
+                Node::Element(el) => el.attrs.vals.get(&At::TabIndex).cloned(),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tab_indexes,
+            vec![
+                AtValue::Some("-1".into()),
+                AtValue::Some("0".into()),
+                AtValue::Some("-1".into()),
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn classes() {
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Div);
+        assert!(e.classes().is_empty());
+        assert_eq!(e.class_count(), 0);
+
+        e.add_attr("class", " first  second");
+        e.add_class("third");
+        assert_eq!(e.classes(), vec!["first", "second", "third"]);
+        assert_eq!(e.class_count(), 3);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn attr_and_style_counts() {
+        let mut e: El<Msg> = El::empty(seed::virtual_dom::Tag::Div);
+        assert_eq!(e.attr_count(), 0);
+        assert_eq!(e.style_count(), 0);
+
+        e.add_attr("data-a", "1")
+            .add_attr("data-b", "2")
+            .add_class("first")
+            .add_class("second")
+            .add_style(St::Color, "red");
+        assert_eq!(e.attr_count(), 3);
+        assert_eq!(e.style_count(), 1);
+    }
+
     /// Test that `style!` macro accept types that have `to_css_value()` function
     #[wasm_bindgen_test]
     pub fn to_css_value_in_style() {
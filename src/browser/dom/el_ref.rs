@@ -0,0 +1,40 @@
+//! This module contains `ElRef` - a handle to the live DOM node of a rendered element;
+//! see `El::el_ref`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+
+/// A shareable cell with the element's `web_sys` node. It's filled when the element
+/// is inserted into the DOM and cleared when it's removed, so read it after render;
+/// eg in `update` or in `Orders::after_next_render`. Clones point to the same cell.
+#[derive(Clone, Debug, Default)]
+pub struct ElRef(Rc<RefCell<Option<web_sys::Node>>>);
+
+impl ElRef {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The node, if the element is in the DOM.
+    pub fn get(&self) -> Option<web_sys::Node> {
+        self.0.borrow().clone()
+    }
+
+    /// The node cast to the given type; eg `el_ref.get_as::<web_sys::HtmlVideoElement>()`.
+    /// Returns `None` if the element isn't in the DOM or has a different type.
+    pub fn get_as<T: JsCast>(&self) -> Option<T> {
+        self.get().and_then(|node| node.dyn_into::<T>().ok())
+    }
+
+    /// The element's position and size relative to the viewport (`getBoundingClientRect()`);
+    /// eg to position a tooltip. Returns `None` if the element isn't in the DOM.
+    pub fn bounding_rect(&self) -> Option<web_sys::DomRect> {
+        self.get_as::<web_sys::Element>()
+            .map(|element| element.get_bounding_client_rect())
+    }
+
+    pub(crate) fn set(&self, node: Option<web_sys::Node>) {
+        self.0.replace(node);
+    }
+}
@@ -2,9 +2,10 @@
 //! `web_sys::Event`
 
 use super::super::util;
-use crate::virtual_dom::{Category, Listener};
+use crate::virtual_dom::{Category, Ev, Listener};
 use serde::de::DeserializeOwned;
-use wasm_bindgen::JsCast;
+use std::{collections::HashMap, time::Duration};
+use wasm_bindgen::{JsCast, JsValue};
 
 /// Create an event that passes a String of field text, for fast input handling.
 pub fn input_ev<Ms, T: ToString + Copy>(
@@ -31,6 +32,32 @@ pub fn input_ev<Ms, T: ToString + Copy>(
     )
 }
 
+/// Create an event that passes the field's value parsed to a number; eg for `input[type=number]`.
+/// The value is `None` if the field is empty or it isn't a finite number (eg `inf` or `NaN`).
+pub fn input_ev_number<Ms, T: ToString + Copy>(
+    trigger: T,
+    handler: impl FnOnce(Option<f64>) -> Ms + 'static + Clone,
+) -> Listener<Ms> {
+    input_ev(trigger, move |value| {
+        (handler.clone())(parse_value(&value).filter(|number: &f64| number.is_finite()))
+    })
+}
+
+/// See `input_ev_number`; decimal numbers are `None` too.
+pub fn input_ev_int<Ms, T: ToString + Copy>(
+    trigger: T,
+    handler: impl FnOnce(Option<i64>) -> Ms + 'static + Clone,
+) -> Listener<Ms> {
+    input_ev(trigger, move |value| (handler.clone())(parse_value(&value)))
+}
+
+fn parse_value<N: std::str::FromStr>(value: &str) -> Option<N> {
+    match value.trim() {
+        "" => None,
+        value => value.parse().ok(),
+    }
+}
+
 /// Create an event that passes a `web_sys::KeyboardEvent`, allowing easy access
 /// to items like `key_code`() and key().
 pub fn keyboard_ev<Ms, T: ToString + Copy>(
@@ -80,6 +107,236 @@ pub fn pointer_ev<Ms, T: ToString + Copy>(
     )
 }
 
+/// Create an event that passes the `relatedTarget` of a `web_sys::FocusEvent`; ie the element
+/// losing focus for `focus`/`focusin`, or the element receiving focus for `blur`/`focusout`.
+/// `relatedTarget` is `None` when focus comes from or goes to outside of the page.
+///
+/// _Note:_ `focus` and `blur` don't bubble, so they only fire on the element itself.
+/// Use `focusin` and `focusout` when listening on a container, eg to detect that focus
+/// has left a dropdown.
+pub fn focus_ev<Ms, T: ToString + Copy>(
+    trigger: T,
+    handler: impl FnOnce(Option<web_sys::EventTarget>) -> Ms + 'static + Clone,
+) -> Listener<Ms> {
+    let closure = move |event: web_sys::Event| {
+        (handler.clone())(
+            event
+                .dyn_ref::<web_sys::FocusEvent>()
+                .unwrap()
+                .related_target(),
+        )
+    };
+    Listener::new(
+        &trigger.to_string(),
+        Some(closure),
+        Some(Category::Focus),
+        None,
+    )
+}
+
+/// See `focus_ev`
+pub fn blur_ev<Ms, T: ToString + Copy>(
+    trigger: T,
+    handler: impl FnOnce(Option<web_sys::EventTarget>) -> Ms + 'static + Clone,
+) -> Listener<Ms> {
+    focus_ev(trigger, handler)
+}
+
+/// Create a `slotchange` event handler that gets the nodes currently assigned to the `slot`
+/// (including the ones of nested slots); see `El::assigned_nodes`.
+pub fn slotchange_ev<Ms>(
+    handler: impl FnOnce(Vec<web_sys::Node>) -> Ms + 'static + Clone,
+) -> Listener<Ms> {
+    let closure = move |event: web_sys::Event| {
+        let nodes = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::HtmlSlotElement>().ok())
+            .map(|slot| assigned_nodes(&slot))
+            .unwrap_or_default();
+        (handler.clone())(nodes)
+    };
+    Listener::new(
+        &Ev::SlotChange.to_string(),
+        Some(closure),
+        Some(Category::Raw),
+        None,
+    )
+}
+
+/// Create an `animationend` event handler that gets the animation's name
+/// (`AnimationEvent.animationName`). Animations of descendants bubble to the element too;
+/// see `El::on_animation_end` to handle only one animation.
+pub fn animationend_ev<Ms>(handler: impl FnOnce(String) -> Ms + 'static + Clone) -> Listener<Ms> {
+    let closure = move |event: web_sys::Event| (handler.clone())(animation_name(&event));
+    Listener::new(
+        &Ev::AnimationEnd.to_string(),
+        Some(closure),
+        Some(Category::Raw),
+        None,
+    )
+}
+
+/// Create a `transitionend` event handler that gets the transitioned CSS property
+/// (`TransitionEvent.propertyName`; eg `"opacity"`). Transitions of descendants bubble
+/// to the element too; see `El::on_transition_end` to handle only one property.
+pub fn transitionend_ev<Ms>(handler: impl FnOnce(String) -> Ms + 'static + Clone) -> Listener<Ms> {
+    let closure = move |event: web_sys::Event| (handler.clone())(transition_property(&event));
+    Listener::new(
+        &Ev::TransitionEnd.to_string(),
+        Some(closure),
+        Some(Category::Raw),
+        None,
+    )
+}
+
+/// Create a `paste` event handler that gets the `web_sys::Event` (a JS `ClipboardEvent`);
+/// its `clipboardData` holds the pasted content (see `clipboard_data` and `clipboard_text`).
+/// Call `prevent_default` on the event to insert the content yourself - eg without formatting;
+/// see `El::on_paste`.
+pub fn paste_ev<Ms>(handler: impl FnOnce(web_sys::Event) -> Ms + 'static + Clone) -> Listener<Ms> {
+    clipboard_ev(Ev::Paste, handler)
+}
+
+/// Create a `copy` event handler that gets the `web_sys::Event` (a JS `ClipboardEvent`);
+/// replace the copied content with `set_clipboard_text`.
+pub fn copy_ev<Ms>(handler: impl FnOnce(web_sys::Event) -> Ms + 'static + Clone) -> Listener<Ms> {
+    clipboard_ev(Ev::Copy, handler)
+}
+
+/// See `copy_ev`; `prevent_default` also keeps the selection in the document.
+pub fn cut_ev<Ms>(handler: impl FnOnce(web_sys::Event) -> Ms + 'static + Clone) -> Listener<Ms> {
+    clipboard_ev(Ev::Cut, handler)
+}
+
+fn clipboard_ev<Ms>(
+    trigger: Ev,
+    handler: impl FnOnce(web_sys::Event) -> Ms + 'static + Clone,
+) -> Listener<Ms> {
+    let closure = move |event: web_sys::Event| (handler.clone())(event);
+    Listener::new(
+        &trigger.to_string(),
+        Some(closure),
+        Some(Category::Raw),
+        None,
+    )
+}
+
+/// `ClipboardEvent.clipboardData`; `None` if it isn't a `ClipboardEvent`.
+/// (`web_sys::ClipboardEvent` is available only with the unstable web-sys APIs.)
+pub fn clipboard_data(event: &web_sys::Event) -> Option<web_sys::DataTransfer> {
+    js_sys::Reflect::get(event, &JsValue::from_str("clipboardData"))
+        .ok()
+        .and_then(|data| data.dyn_into::<web_sys::DataTransfer>().ok())
+}
+
+/// The plain text of the clipboard event's data; empty if there isn't any.
+pub fn clipboard_text(event: &web_sys::Event) -> String {
+    clipboard_data(event)
+        .and_then(|data| data.get_data("text/plain").ok())
+        .unwrap_or_default()
+}
+
+/// Put the plain `text` to the clipboard instead of the copied (or cut) selection;
+/// use in `copy_ev` or `cut_ev` handlers. It calls `prevent_default`, because the browser
+/// would replace the data with the selection otherwise.
+pub fn set_clipboard_text(event: &web_sys::Event, text: &str) {
+    event.prevent_default();
+    let is_set =
+        clipboard_data(event).is_some_and(|data| data.set_data("text/plain", text).is_ok());
+    if !is_set {
+        crate::error("Problem setting the clipboard data");
+    }
+}
+
+/// `AnimationEvent.animationName`; empty if it isn't an `AnimationEvent`.
+pub(crate) fn animation_name(event: &web_sys::Event) -> String {
+    event
+        .dyn_ref::<web_sys::AnimationEvent>()
+        .map(web_sys::AnimationEvent::animation_name)
+        .unwrap_or_default()
+}
+
+/// `TransitionEvent.propertyName`; empty if it isn't a `TransitionEvent`.
+pub(crate) fn transition_property(event: &web_sys::Event) -> String {
+    event
+        .dyn_ref::<web_sys::TransitionEvent>()
+        .map(web_sys::TransitionEvent::property_name)
+        .unwrap_or_default()
+}
+
+/// The nodes assigned to the slot, with nested slots replaced by their assigned nodes.
+pub(crate) fn assigned_nodes(slot: &web_sys::HtmlSlotElement) -> Vec<web_sys::Node> {
+    let mut options = web_sys::AssignedNodesOptions::new();
+    options.flatten(true);
+    slot.assigned_nodes_with_options(&options)
+        .iter()
+        .filter_map(|node| node.dyn_into::<web_sys::Node>().ok())
+        .collect()
+}
+
+/// Create a `submit` event handler that gets the form's fields as a map of names to values,
+/// collected by `FormData`, so only named and enabled fields are included. The default
+/// submission (page reload) is prevented. Values of fields with the same name (eg checkboxes
+/// or multi-selects) are joined by `,`; use `form_submit_ev_multi` to get them separately.
+/// Selected files are represented by their names.
+pub fn form_submit_ev<Ms>(
+    handler: impl FnOnce(HashMap<String, String>) -> Ms + 'static + Clone,
+) -> Listener<Ms> {
+    form_submit_ev_multi(move |values| {
+        (handler.clone())(
+            values
+                .into_iter()
+                .map(|(name, values)| (name, values.join(",")))
+                .collect(),
+        )
+    })
+}
+
+/// See `form_submit_ev`; each field name has all its values in the document order.
+pub fn form_submit_ev_multi<Ms>(
+    handler: impl FnOnce(HashMap<String, Vec<String>>) -> Ms + 'static + Clone,
+) -> Listener<Ms> {
+    let closure = move |event: web_sys::Event| {
+        event.prevent_default();
+        let values = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::HtmlFormElement>().ok())
+            .map(|form| form_values(&form))
+            .unwrap_or_default();
+        (handler.clone())(values)
+    };
+    Listener::new(
+        &Ev::Submit.to_string(),
+        Some(closure),
+        Some(Category::Raw),
+        None,
+    )
+}
+
+fn form_values(form: &web_sys::HtmlFormElement) -> HashMap<String, Vec<String>> {
+    let mut values = HashMap::<String, Vec<String>>::new();
+    let entries = web_sys::FormData::new_with_form(form)
+        .ok()
+        .and_then(|form_data| js_sys::try_iter(&form_data).ok().flatten());
+    for entry in entries.into_iter().flatten().filter_map(Result::ok) {
+        let entry = js_sys::Array::from(&entry);
+        let name = match entry.get(0).as_string() {
+            Some(name) => name,
+            None => continue,
+        };
+        let value = entry.get(1);
+        let value = match value.as_string() {
+            Some(value) => value,
+            None => match value.dyn_ref::<web_sys::File>() {
+                Some(file) => file.name(),
+                None => continue,
+            },
+        };
+        values.entry(name).or_default().push(value);
+    }
+    values
+}
+
 /// Create an event that accepts a closure, and passes a `web_sys::Event`, allowing full control of
 /// event-handling
 pub fn raw_ev<Ms, T: ToString + Copy>(
@@ -95,6 +352,27 @@ pub fn raw_ev<Ms, T: ToString + Copy>(
     )
 }
 
+/// Create an event handler for an event with any name; eg a `CustomEvent` dispatched
+/// by a web component. The handler gets the event's `detail`
+/// (`JsValue::UNDEFINED` if it isn't a `CustomEvent`).
+pub fn custom_ev<Ms>(
+    name: &str,
+    handler: impl FnOnce(JsValue) -> Ms + 'static + Clone,
+) -> Listener<Ms> {
+    let closure = move |event: web_sys::Event| {
+        let detail = event
+            .dyn_ref::<web_sys::CustomEvent>()
+            .map_or(JsValue::UNDEFINED, web_sys::CustomEvent::detail);
+        (handler.clone())(detail)
+    };
+    Listener::new_ev(
+        Ev::custom(name),
+        Some(closure),
+        Some(Category::Custom),
+        None,
+    )
+}
+
 /// Create an event that passes no data, other than it occurred. Foregoes using a closure,
 /// in favor of pointing to a message directly.
 pub fn simple_ev<Ms: Clone, T>(trigger: T, message: Ms) -> Listener<Ms>
@@ -113,6 +391,22 @@ where
     )
 }
 
+/// Debounce the listener; eg one created by a macro or returned from a component.
+/// Equivalent to `listener.debounce(delay)`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///# use seed::prelude::*;
+///# use std::time::Duration;
+///# #[derive(Clone)]
+///# enum Msg { Search(String) }
+/// let listener = debounce(input_ev(Ev::Input, Msg::Search), Duration::from_millis(300));
+/// ```
+pub fn debounce<Ms>(listener: Listener<Ms>, delay: Duration) -> Listener<Ms> {
+    listener.debounce(delay)
+}
+
 #[deprecated]
 pub const UPDATE_TRIGGER_EVENT_ID: &str = "triggerupdate";
 
@@ -0,0 +1,164 @@
+//! This module contains `KeyCombo` - a keyboard shortcut like `Ctrl+K`;
+//! see `El::on_key` and `El::on_key_combo`.
+//!
+//! Seed listeners send a message for each event, so the `keydown` listener that checks
+//! the key is added to the DOM node when it's inserted and it passes only the matching
+//! events to the app as custom events (see `observers::pass_matching_events`).
+
+use super::{event_handler::custom_ev, observers};
+use crate::virtual_dom::Listener;
+use std::{fmt, rc::Rc};
+use wasm_bindgen::JsCast;
+
+/// A key with modifiers; eg `KeyCombo::parse("Ctrl+Shift+K")`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyCombo {
+    /// [KeyboardEvent.key](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/key);
+    /// single characters are compared case-insensitively.
+    pub key: String,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    /// The `⌘` key on Mac and the `⊞` key on Windows.
+    pub meta: bool,
+}
+
+impl KeyCombo {
+    /// Parse modifiers (`Ctrl`, `Shift`, `Alt` and `Meta` or `Cmd`; case-insensitive)
+    /// and a key separated by `+`; eg `"Ctrl+K"`, `"Alt+ArrowUp"` or `"Ctrl++"`.
+    pub fn parse(combo: &str) -> Self {
+        let (modifiers, key) = if combo == "+" || combo.ends_with("++") {
+            (combo.trim_end_matches('+'), "+")
+        } else {
+            match combo.rfind('+') {
+                Some(index) => (&combo[..index], &combo[index + 1..]),
+                None => ("", combo),
+            }
+        };
+
+        let mut key_combo = Self {
+            key: key.to_owned(),
+            ..Self::default()
+        };
+        for modifier in modifiers.split('+').filter(|modifier| !modifier.is_empty()) {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => key_combo.ctrl = true,
+                "shift" => key_combo.shift = true,
+                "alt" | "option" => key_combo.alt = true,
+                "meta" | "cmd" | "command" => key_combo.meta = true,
+                _ => {
+                    crate::error(&format!("Unknown key modifier: {}", modifier));
+                }
+            }
+        }
+        key_combo
+    }
+
+    /// The event has the same key and exactly the same modifiers.
+    pub fn matches(&self, event: &web_sys::KeyboardEvent) -> bool {
+        self.matches_parts(
+            &event.key(),
+            event.ctrl_key(),
+            event.shift_key(),
+            event.alt_key(),
+            event.meta_key(),
+        )
+    }
+
+    fn matches_parts(&self, key: &str, ctrl: bool, shift: bool, alt: bool, meta: bool) -> bool {
+        let key_matches = if self.key.chars().count() == 1 {
+            self.key.to_lowercase() == key.to_lowercase()
+        } else {
+            self.key == key
+        };
+        key_matches
+            && self.ctrl == ctrl
+            && self.shift == shift
+            && self.alt == alt
+            && self.meta == meta
+    }
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [
+            (self.ctrl, "Ctrl+"),
+            (self.shift, "Shift+"),
+            (self.alt, "Alt+"),
+            (self.meta, "Meta+"),
+        ];
+        for (_, name) in modifiers.iter().filter(|(pressed, _)| *pressed) {
+            write!(f, "{}", name)?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+/// Pass matching `keydown` events to the `event_name` listener and prevent their default
+/// action; use as a `did_mount` action. The listener is dropped together with the node.
+pub(crate) fn listen_keydown(
+    node: &web_sys::Node,
+    event_name: String,
+    matches: impl Fn(&web_sys::KeyboardEvent) -> bool + 'static,
+) {
+    observers::pass_matching_events(node, "keydown", event_name, move |event| {
+        match event.dyn_ref::<web_sys::KeyboardEvent>() {
+            Some(event) if matches(event) => {
+                event.prevent_default();
+                true
+            }
+            _ => false,
+        }
+    });
+}
+
+/// Listen to the events passed by `listen_keydown`.
+pub(crate) fn key_listener<Ms>(
+    event_name: &str,
+    msg_factory: impl Fn() -> Ms + 'static,
+) -> Listener<Ms> {
+    let msg_factory = Rc::new(msg_factory);
+    custom_ev(event_name, move |_| msg_factory())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn parse_combos() {
+        assert_eq!(
+            KeyCombo::parse("Ctrl+Shift+K"),
+            KeyCombo {
+                key: "K".into(),
+                ctrl: true,
+                shift: true,
+                ..KeyCombo::default()
+            }
+        );
+        assert_eq!(
+            KeyCombo::parse("cmd+alt++"),
+            KeyCombo {
+                key: "+".into(),
+                alt: true,
+                meta: true,
+                ..KeyCombo::default()
+            }
+        );
+        assert_eq!(KeyCombo::parse("Escape").to_string(), "Escape");
+        assert_eq!(KeyCombo::parse("shift+ctrl+k").to_string(), "Ctrl+Shift+k");
+    }
+
+    #[wasm_bindgen_test]
+    fn match_combos() {
+        let combo = KeyCombo::parse("Ctrl+K");
+        assert!(combo.matches_parts("k", true, false, false, false));
+        assert!(!combo.matches_parts("k", false, false, false, false));
+        assert!(!combo.matches_parts("k", true, true, false, false));
+
+        let combo = KeyCombo::parse("Enter");
+        assert!(combo.matches_parts("Enter", false, false, false, false));
+        assert!(!combo.matches_parts("enter", false, false, false, false));
+    }
+}
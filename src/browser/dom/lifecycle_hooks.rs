@@ -2,6 +2,7 @@
 
 use crate::app::MessageMapper;
 use std::fmt;
+use std::time::Duration;
 
 type _HookFn = Box<dyn FnMut(&web_sys::Node)>; // todo
 
@@ -16,6 +17,11 @@ pub struct LifecycleHooks<Ms> {
     pub did_mount: Option<DidMount<Ms>>,
     pub did_update: Option<DidUpdate<Ms>>,
     pub will_unmount: Option<WillUnmount<Ms>>,
+    pub leave_animation: Option<LeaveAnimation>,
+    /// Animate moves of the keyed element among its siblings; see `El::animate_move`.
+    pub move_animation: Option<MoveAnimation>,
+    /// Keep the element's scroll position when it's patched or replaced; see `El::preserve_scroll`.
+    pub preserve_scroll: bool,
 }
 
 impl<Ms> LifecycleHooks<Ms> {
@@ -24,6 +30,9 @@ impl<Ms> LifecycleHooks<Ms> {
             did_mount: None,
             did_update: None,
             will_unmount: None,
+            leave_animation: None,
+            move_animation: None,
+            preserve_scroll: false,
         }
     }
 }
@@ -32,10 +41,13 @@ impl<Ms> fmt::Debug for LifecycleHooks<Ms> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "LifecycleHooks {{ did_mount:{:?}, did_update:{:?}, will_unmount:{} }}",
+            "LifecycleHooks {{ did_mount:{:?}, did_update:{:?}, will_unmount:{}, leave_animation:{:?}, move_animation:{:?}, preserve_scroll:{} }}",
             fmt_hook_fn(&self.did_mount),
             fmt_hook_fn(&self.did_update),
-            fmt_hook_fn(&self.will_unmount)
+            fmt_hook_fn(&self.will_unmount),
+            self.leave_animation,
+            self.move_animation,
+            self.preserve_scroll
         )
     }
 }
@@ -56,10 +68,25 @@ impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for LifecycleHook
                 actions: d.actions,
                 message: d.message.map(f),
             }),
+            leave_animation: self.leave_animation,
+            move_animation: self.move_animation,
+            preserve_scroll: self.preserve_scroll,
         }
     }
 }
 
+/// Extend `actions`, so that `next` is called after the already stored actions.
+pub(crate) fn append_actions(
+    actions: &mut Box<dyn FnMut(&web_sys::Node)>,
+    mut next: impl FnMut(&web_sys::Node) + 'static,
+) {
+    let mut previous = std::mem::replace(actions, Box::new(|_| ()));
+    *actions = Box::new(move |node| {
+        previous(node);
+        next(node);
+    });
+}
+
 pub struct DidMount<Ms> {
     pub actions: Box<dyn FnMut(&web_sys::Node)>,
     pub message: Option<Ms>,
@@ -75,6 +102,22 @@ pub struct WillUnmount<Ms> {
     pub message: Option<Ms>,
 }
 
+/// The class added to an element removed from the DOM, and the longest delay before
+/// its DOM node is actually removed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LeaveAnimation {
+    pub class: String,
+    pub duration: Duration,
+}
+
+/// The class (with a `transition` of `transform`) added to an element while it moves
+/// from its old position, and the duration of the transition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveAnimation {
+    pub class: String,
+    pub duration: Duration,
+}
+
 /// A constructor for `DidMount`, to be used in the API
 pub fn did_mount<Ms>(mut actions: impl FnMut(&web_sys::Node) + 'static) -> DidMount<Ms> {
     let closure = move |el: &web_sys::Node| actions(el);
@@ -0,0 +1,259 @@
+//! This module contains helpers for observers (`ResizeObserver`, `IntersectionObserver`
+//! and a document-level click listener), used in `El::on_resize`, `El::on_intersect`
+//! and `El::on_click_outside`.
+//!
+//! Observers are stored on their DOM nodes (not in the vdom), since the vdom is recreated
+//! in each render - the hook that removes them belongs to a different `El` than the hook
+//! that created them. Observations are passed to the app as custom events.
+
+use super::event_handler::custom_ev;
+use crate::{browser::util, virtual_dom::Listener};
+use std::rc::Rc;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+const RESIZE_EVENT: &str = "seedresize";
+pub(crate) const RESIZE_OBSERVER_KEY: &str = "seedResizeObserver";
+const INTERSECT_EVENT: &str = "seedintersect";
+pub(crate) const INTERSECTION_OBSERVER_KEY: &str = "seedIntersectionObserver";
+const CLICK_OUTSIDE_EVENT: &str = "seedclickoutside";
+const CLICK_OUTSIDE_LISTENER_KEY: &str = "seedClickOutsideListener";
+
+/// The element's content box; see `El::on_resize`.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/ResizeObserverEntry/contentRect)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ContentRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl From<&web_sys::DomRectReadOnly> for ContentRect {
+    fn from(rect: &web_sys::DomRectReadOnly) -> Self {
+        Self {
+            x: rect.x(),
+            y: rect.y(),
+            width: rect.width(),
+            height: rect.height(),
+        }
+    }
+}
+
+/// Options of `El::on_intersect`.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/IntersectionObserver/IntersectionObserver)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IntersectOptions {
+    /// Grow (or shrink, if negative) the viewport; eg `"200px"` to start loading
+    /// the next page before the end of the list is visible.
+    pub root_margin: Option<String>,
+    /// Ratios of the visible area that trigger the message; `[0.]` if empty.
+    pub thresholds: Vec<f64>,
+}
+
+/// The element's intersection with the viewport; see `El::on_intersect`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct IntersectionInfo {
+    pub is_intersecting: bool,
+    /// Ratio of the visible area; from `0.` to `1.`.
+    pub intersection_ratio: f64,
+}
+
+impl From<&web_sys::IntersectionObserverEntry> for IntersectionInfo {
+    fn from(entry: &web_sys::IntersectionObserverEntry) -> Self {
+        Self {
+            is_intersecting: entry.is_intersecting(),
+            intersection_ratio: entry.intersection_ratio(),
+        }
+    }
+}
+
+/// Start observing the element's size; use as a `did_mount` action.
+pub(crate) fn observe_resize(node: &web_sys::Node) {
+    let element = match node.dyn_ref::<web_sys::Element>() {
+        Some(element) => element,
+        None => return,
+    };
+    let callback = Closure::wrap(Box::new(|entries: js_sys::Array| {
+        for entry in entries.iter() {
+            let entry = entry.unchecked_into::<web_sys::ResizeObserverEntry>();
+            dispatch(&entry.target(), RESIZE_EVENT, &entry.content_rect());
+        }
+    }) as Box<dyn FnMut(js_sys::Array)>);
+
+    let observer = web_sys::ResizeObserver::new(callback.into_js_value().unchecked_ref())
+        .expect("Problem creating ResizeObserver");
+    observer.observe(element);
+    store(node, RESIZE_OBSERVER_KEY, &observer);
+}
+
+/// Stop observing the element's size; use as a `will_unmount` action.
+pub(crate) fn unobserve_resize(node: &web_sys::Node) {
+    if let Some(observer) = take(node, RESIZE_OBSERVER_KEY) {
+        observer
+            .unchecked_into::<web_sys::ResizeObserver>()
+            .disconnect();
+    }
+}
+
+/// Start observing the element's intersection with the viewport; use as a `did_mount` action.
+pub(crate) fn observe_intersection(node: &web_sys::Node, options: &IntersectOptions) {
+    let element = match node.dyn_ref::<web_sys::Element>() {
+        Some(element) => element,
+        None => return,
+    };
+    let callback = Closure::wrap(Box::new(|entries: js_sys::Array| {
+        for entry in entries.iter() {
+            let entry = entry.unchecked_into::<web_sys::IntersectionObserverEntry>();
+            dispatch(&entry.target(), INTERSECT_EVENT, &entry);
+        }
+    }) as Box<dyn FnMut(js_sys::Array)>);
+
+    let mut init = web_sys::IntersectionObserverInit::new();
+    if let Some(root_margin) = &options.root_margin {
+        init.root_margin(root_margin);
+    }
+    if !options.thresholds.is_empty() {
+        let thresholds = options
+            .thresholds
+            .iter()
+            .map(|threshold| JsValue::from_f64(*threshold))
+            .collect::<js_sys::Array>();
+        init.threshold(&thresholds);
+    }
+
+    let observer = web_sys::IntersectionObserver::new_with_options(
+        callback.into_js_value().unchecked_ref(),
+        &init,
+    )
+    .expect("Problem creating IntersectionObserver");
+    observer.observe(element);
+    store(node, INTERSECTION_OBSERVER_KEY, &observer);
+}
+
+/// Stop observing the element's intersection; use as a `will_unmount` action.
+pub(crate) fn unobserve_intersection(node: &web_sys::Node) {
+    if let Some(observer) = take(node, INTERSECTION_OBSERVER_KEY) {
+        observer
+            .unchecked_into::<web_sys::IntersectionObserver>()
+            .disconnect();
+    }
+}
+
+/// Start listening to clicks outside the element; use as a `did_mount` action.
+/// The listener is in the capture phase, so it fires even if a handler inside
+/// the clicked element stops the propagation.
+pub(crate) fn observe_click_outside(node: &web_sys::Node) {
+    let observed = node.clone();
+    let callback = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let target = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Node>().ok());
+        if let Some(target) = target {
+            if !observed.contains(Some(&target)) {
+                dispatch(&observed, CLICK_OUTSIDE_EVENT, &JsValue::UNDEFINED);
+            }
+        }
+    }) as Box<dyn FnMut(web_sys::Event)>)
+    .into_js_value();
+
+    if util::document()
+        .add_event_listener_with_callback_and_bool("click", callback.unchecked_ref(), true)
+        .is_err()
+    {
+        crate::error("Problem adding click outside listener");
+    }
+    store(node, CLICK_OUTSIDE_LISTENER_KEY, &callback);
+}
+
+/// Stop listening to clicks outside the element; use as a `will_unmount` action.
+pub(crate) fn unobserve_click_outside(node: &web_sys::Node) {
+    if let Some(callback) = take(node, CLICK_OUTSIDE_LISTENER_KEY) {
+        if util::document()
+            .remove_event_listener_with_callback_and_bool("click", callback.unchecked_ref(), true)
+            .is_err()
+        {
+            crate::error("Problem removing click outside listener");
+        }
+    }
+}
+
+/// Listen to the clicks dispatched by `observe_click_outside`.
+pub(crate) fn click_outside_listener<Ms>(msg_factory: impl Fn() -> Ms + 'static) -> Listener<Ms> {
+    let msg_factory = Rc::new(msg_factory);
+    custom_ev(CLICK_OUTSIDE_EVENT, move |_| msg_factory())
+}
+
+/// Listen to the observations dispatched by `observe_intersection`.
+pub(crate) fn intersection_listener<Ms>(
+    handler: impl FnOnce(IntersectionInfo) -> Ms + 'static + Clone,
+) -> Listener<Ms> {
+    custom_ev(INTERSECT_EVENT, move |detail| {
+        (handler.clone())(IntersectionInfo::from(
+            detail.unchecked_ref::<web_sys::IntersectionObserverEntry>(),
+        ))
+    })
+}
+
+/// Listen to the observations dispatched by `observe_resize`.
+pub(crate) fn resize_listener<Ms>(
+    handler: impl FnOnce(ContentRect) -> Ms + 'static + Clone,
+) -> Listener<Ms> {
+    custom_ev(RESIZE_EVENT, move |detail| {
+        (handler.clone())(ContentRect::from(
+            detail.unchecked_ref::<web_sys::DomRectReadOnly>(),
+        ))
+    })
+}
+
+/// Dispatch the `event_name` custom event for each `trigger` event on the node that `matches`;
+/// use as a `did_mount` action. Seed listeners send a message for each event, so this is how
+/// they get only some events (eg of one key or animation). The listener is dropped together
+/// with the node.
+pub(crate) fn pass_matching_events(
+    node: &web_sys::Node,
+    trigger: &str,
+    event_name: String,
+    matches: impl Fn(&web_sys::Event) -> bool + 'static,
+) {
+    let target = node.clone();
+    let callback = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        if matches(&event) {
+            dispatch(&target, &event_name, &JsValue::UNDEFINED);
+        }
+    }) as Box<dyn FnMut(web_sys::Event)>)
+    .into_js_value();
+
+    if node
+        .add_event_listener_with_callback(trigger, callback.unchecked_ref())
+        .is_err()
+    {
+        crate::error(&format!("Problem adding {} listener", trigger));
+    }
+}
+
+/// Dispatch a non-bubbling custom event, so observations don't reach ancestors' listeners.
+pub(crate) fn dispatch(target: &web_sys::EventTarget, name: &str, detail: &JsValue) {
+    let mut init = web_sys::CustomEventInit::new();
+    init.detail(detail);
+    let event = web_sys::CustomEvent::new_with_event_init_dict(name, &init)
+        .expect("Problem creating observer event");
+    if target.dispatch_event(&event).is_err() {
+        crate::error("Problem dispatching observer event");
+    }
+}
+
+fn store(node: &web_sys::Node, key: &str, observer: &JsValue) {
+    if js_sys::Reflect::set(node, &JsValue::from_str(key), observer).is_err() {
+        crate::error("Problem storing observer");
+    }
+}
+
+fn take(node: &web_sys::Node, key: &str) -> Option<JsValue> {
+    let key = JsValue::from_str(key);
+    let observer = js_sys::Reflect::get(node, &key).ok()?;
+    if observer.is_undefined() {
+        return None;
+    }
+    js_sys::Reflect::delete_property(node.unchecked_ref(), &key).ok()?;
+    Some(observer)
+}
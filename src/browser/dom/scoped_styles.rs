@@ -0,0 +1,230 @@
+//! This module contains styles that can't be inline (eg `:hover` or media query ones),
+//! injected as CSS rules for generated classes; see `El::hover_style` and `El::media_style`.
+//!
+//! Each rule is a `<style>` element in the document head. The class is derived from the rule's
+//! content, so it's the same in each render, and elements with the same rule share it.
+//! The rule is removed when the last element using it is removed. In the same way as observers,
+//! the class whose rule the element uses is stored on its DOM node - the vdom is recreated
+//! in each render.
+
+use crate::{browser::util, virtual_dom::Style};
+use std::{
+    cell::RefCell,
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+use wasm_bindgen::JsValue;
+
+/// One rule of an element; eg its `:hover` style.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ScopedRule {
+    /// The element's generated class.
+    pub class: String,
+    /// The whole CSS rule; eg `.seed-1f2e3d:hover{color:red}`. Empty if the style is invalid;
+    /// see `checked_css`.
+    pub css: String,
+    /// Identifies the kind of the rule on the DOM node (eg `:hover`); elements have
+    /// at most one rule of each kind.
+    scope: String,
+}
+
+struct InjectedRule {
+    style_el: web_sys::Element,
+    users: usize,
+}
+
+thread_local! {
+    static INJECTED_RULES: RefCell<HashMap<String, InjectedRule>> = RefCell::default();
+}
+
+impl ScopedRule {
+    /// `.{class}{pseudo_class}{style}`; eg `pseudo_class(":hover", style)`.
+    pub fn pseudo_class(pseudo_class: &str, style: &Style) -> Self {
+        let declarations = style.to_string();
+        let class = generated_class(pseudo_class, &declarations);
+        Self {
+            css: checked_css(
+                format!(".{}{}{{{}}}", class, pseudo_class, declarations),
+                &[&declarations],
+            ),
+            class,
+            scope: pseudo_class.to_owned(),
+        }
+    }
+
+    /// `@media {query}{.{class}{style}}`; eg `media("(max-width: 600px)", style)`.
+    pub fn media(query: &str, style: &Style) -> Self {
+        let declarations = style.to_string();
+        let scope = format!("@media {}", query);
+        let class = generated_class(&scope, &declarations);
+        Self {
+            css: checked_css(
+                format!("{}{{.{}{{{}}}}}", scope, class, declarations),
+                &[query, &declarations],
+            ),
+            class,
+            scope,
+        }
+    }
+
+    /// Inject the rule for the inserted element; use as a `did_mount` action.
+    pub fn insert(&self, node: &web_sys::Node) {
+        acquire(&self.class, &self.css);
+        self.store_class(node);
+    }
+
+    /// Use the rule instead of the element's previous one of the same kind, if it changed;
+    /// use as a `did_update` action.
+    pub fn update(&self, node: &web_sys::Node) {
+        match self.stored_class(node) {
+            Some(class) if class == self.class => (),
+            previous => {
+                acquire(&self.class, &self.css);
+                self.store_class(node);
+                if let Some(previous) = previous {
+                    release(&previous);
+                }
+            }
+        }
+    }
+
+    /// Remove the rule of the removed element, unless other elements use it;
+    /// use as a `will_unmount` action.
+    pub fn remove(&self, node: &web_sys::Node) {
+        if let Some(class) = self.stored_class(node) {
+            release(&class);
+        }
+    }
+
+    fn node_key(&self) -> JsValue {
+        JsValue::from_str(&format!("seedScopedStyle {}", self.scope))
+    }
+
+    fn store_class(&self, node: &web_sys::Node) {
+        if js_sys::Reflect::set(node, &self.node_key(), &JsValue::from_str(&self.class)).is_err() {
+            crate::error("Problem storing the scoped style");
+        }
+    }
+
+    fn stored_class(&self, node: &web_sys::Node) -> Option<String> {
+        js_sys::Reflect::get(node, &self.node_key())
+            .ok()
+            .and_then(|class| class.as_string())
+    }
+}
+
+/// Return the `css`, or an empty one if the `texts` put into it contain braces - they would
+/// end the rule and start others.
+fn checked_css(css: String, texts: &[&str]) -> String {
+    if texts.iter().any(|text| text.contains(['{', '}'])) {
+        crate::error(format!("Scoped style can't contain braces: {}", css));
+        return String::new();
+    }
+    css
+}
+
+/// `seed-{hash}` of the rule without the class.
+fn generated_class(scope: &str, declarations: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    scope.hash(&mut hasher);
+    declarations.hash(&mut hasher);
+    format!("seed-{:x}", hasher.finish())
+}
+
+fn acquire(class: &str, css: &str) {
+    INJECTED_RULES.with(|rules| {
+        let mut rules = rules.borrow_mut();
+        if let Some(rule) = rules.get_mut(class) {
+            rule.users += 1;
+            return;
+        }
+        let document = util::document();
+        let style_el = document
+            .create_element("style")
+            .expect("Problem creating style element");
+        style_el.set_text_content(Some(css));
+        let head = match document.head() {
+            Some(head) => head,
+            None => {
+                crate::error("Can't find the document's head for scoped styles");
+                return;
+            }
+        };
+        if head.append_child(&style_el).is_err() {
+            crate::error("Problem injecting the scoped style");
+            return;
+        }
+        rules.insert(class.to_owned(), InjectedRule { style_el, users: 1 });
+    });
+}
+
+fn release(class: &str) {
+    INJECTED_RULES.with(|rules| {
+        let mut rules = rules.borrow_mut();
+        let is_unused = match rules.get_mut(class) {
+            Some(rule) => {
+                rule.users -= 1;
+                rule.users == 0
+            }
+            None => false,
+        };
+        if is_unused {
+            if let Some(rule) = rules.remove(class) {
+                rule.style_el.remove();
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::virtual_dom::St;
+    use wasm_bindgen_test::*;
+
+    fn style(color: &str) -> Style {
+        let mut style = Style::empty();
+        style.add(St::Color, color);
+        style.add(St::Cursor, "pointer");
+        style
+    }
+
+    #[wasm_bindgen_test]
+    fn rules() {
+        let hover = ScopedRule::pseudo_class(":hover", &style("red"));
+        assert!(hover.class.starts_with("seed-"));
+        assert_eq!(
+            hover.css,
+            format!(".{}:hover{{color:red;cursor:pointer}}", hover.class)
+        );
+        assert_eq!(hover, ScopedRule::pseudo_class(":hover", &style("red")));
+        assert_ne!(
+            hover.class,
+            ScopedRule::pseudo_class(":focus", &style("red")).class
+        );
+        assert_ne!(
+            hover.class,
+            ScopedRule::pseudo_class(":hover", &style("blue")).class
+        );
+
+        let media = ScopedRule::media("(max-width: 600px)", &style("red"));
+        assert_eq!(
+            media.css,
+            format!(
+                "@media (max-width: 600px){{.{}{{color:red;cursor:pointer}}}}",
+                media.class
+            )
+        );
+        assert_ne!(media.class, hover.class);
+    }
+
+    #[wasm_bindgen_test]
+    fn rules_with_braces_ignored() {
+        let hover = ScopedRule::pseudo_class(":hover", &style("red}body{display:none"));
+        assert!(hover.class.starts_with("seed-"));
+        assert_eq!(hover.css, "");
+
+        let media = ScopedRule::media("all{body{display:none}}", &style("red"));
+        assert_eq!(media.css, "");
+    }
+}
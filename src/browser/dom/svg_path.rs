@@ -0,0 +1,106 @@
+//! [MDN web docs](https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute/d)
+
+use std::fmt;
+
+/// Builder for the `d` attribute of the SVG `path` element.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use seed::{prelude::*, *};
+/// # type Msg = ();
+/// let triangle: Node<Msg> = path![attrs! {
+///     At::D => PathBuilder::new().move_to(0, 0).line_to(10, 10).line_to(0, 10).close().build()
+/// }];
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathBuilder {
+    commands: Vec<String>,
+}
+
+impl PathBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new subpath at the given point (`M`).
+    pub fn move_to(self, x: impl fmt::Display, y: impl fmt::Display) -> Self {
+        self.command(format!("M{} {}", x, y))
+    }
+
+    /// Draw a straight line to the given point (`L`).
+    pub fn line_to(self, x: impl fmt::Display, y: impl fmt::Display) -> Self {
+        self.command(format!("L{} {}", x, y))
+    }
+
+    /// Draw a cubic Bézier curve to the point `(x, y)`,
+    /// with the control points `(x1, y1)` and `(x2, y2)` (`C`).
+    #[allow(clippy::many_single_char_names)]
+    pub fn curve_to(
+        self,
+        x1: impl fmt::Display,
+        y1: impl fmt::Display,
+        x2: impl fmt::Display,
+        y2: impl fmt::Display,
+        x: impl fmt::Display,
+        y: impl fmt::Display,
+    ) -> Self {
+        self.command(format!("C{} {} {} {} {} {}", x1, y1, x2, y2, x, y))
+    }
+
+    /// Draw a quadratic Bézier curve to the point `(x, y)`,
+    /// with the control point `(x1, y1)` (`Q`).
+    pub fn quad_to(
+        self,
+        x1: impl fmt::Display,
+        y1: impl fmt::Display,
+        x: impl fmt::Display,
+        y: impl fmt::Display,
+    ) -> Self {
+        self.command(format!("Q{} {} {} {}", x1, y1, x, y))
+    }
+
+    /// Draw a straight line back to the start of the current subpath (`Z`).
+    pub fn close(self) -> Self {
+        self.command("Z".to_owned())
+    }
+
+    /// Get the `d` attribute value.
+    pub fn build(&self) -> String {
+        self.commands.join(" ")
+    }
+
+    fn command(mut self, command: String) -> Self {
+        self.commands.push(command);
+        self
+    }
+}
+
+impl fmt::Display for PathBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.build())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn empty() {
+        assert_eq!(PathBuilder::new().build(), "");
+    }
+
+    #[wasm_bindgen_test]
+    fn all_commands() {
+        let d = PathBuilder::new()
+            .move_to(0, 0)
+            .line_to(10, 10.5)
+            .curve_to(1, 2, 3, 4, 5, 6)
+            .quad_to(-1, -2, 0, 0)
+            .close()
+            .build();
+        assert_eq!(d, "M0 0 L10 10.5 C1 2 3 4 5 6 Q-1 -2 0 0 Z");
+    }
+}
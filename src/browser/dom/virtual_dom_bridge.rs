@@ -1,10 +1,19 @@
 //! This file contains interactions with `web_sys`.
 
-use super::Namespace;
-use crate::virtual_dom::{At, AtValue, Attrs, El, Node, Style, Text};
-use wasm_bindgen::JsCast;
+use super::{
+    lifecycle_hooks::{LeaveAnimation, MoveAnimation},
+    Namespace,
+};
+use crate::virtual_dom::{diff, At, AtValue, Attrs, DialogState, El, Node, Patch, Style, Text};
+use gloo_timers::callback::Timeout;
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
 use web_sys::Document;
 
+/// Marks the DOM nodes of removed elements that are still running their leave animations.
+const LEAVING_KEY: &str = "seedLeaving";
+const LEAVE_END_EVENTS: [&str; 2] = ["animationend", "transitionend"];
+
 /// Convenience function to reduce repetition
 fn set_style(el_ws: &web_sys::Node, style: &Style) {
     el_ws
@@ -36,6 +45,11 @@ where
             );
         }
         Node::Empty => (),
+        Node::Portal { children, .. } => {
+            for child in children {
+                assign_ws_nodes(document, child);
+            }
+        }
     }
 }
 
@@ -126,6 +140,9 @@ pub(crate) fn make_websys_el<Ms>(
         set_style(&el_ws, &el_vdom.style)
     }
 
+    #[cfg(feature = "profiling")]
+    profiling::record_created(el_vdom);
+
     el_ws.into()
 }
 
@@ -147,15 +164,43 @@ pub fn attach_children<Ms>(el_vdom: &mut El<Ms>) {
         .expect("Missing websys el in attach_children");
     // appending the its children to the el_ws
     for child in &mut el_vdom.children {
-        match child {
-            // Raise the active level once per recursion.
-            Node::Element(child_el) => attach_el_and_children(child_el, el_ws),
-            Node::Text(child_text) => attach_text_node(child_text, el_ws),
-            Node::Empty => (),
+        attach_node(child, el_ws);
+    }
+}
+
+/// Attach the node (with `attach_el_and_children` or `attach_text_node`);
+/// portals' children are attached to their targets instead of `parent`.
+pub fn attach_node<Ms>(node: &mut Node<Ms>, parent: &web_sys::Node) {
+    match node {
+        // Raise the active level once per recursion.
+        Node::Element(el) => attach_el_and_children(el, parent),
+        Node::Text(text) => attach_text_node(text, parent),
+        Node::Empty => (),
+        Node::Portal { target, children } => attach_portal(target, children),
+    }
+}
+
+/// Attach the portal's children to the element matching the `target` selector.
+pub fn attach_portal<Ms>(target: &str, children: &mut [Node<Ms>]) {
+    if let Some(target_ws) = portal_target(target) {
+        for child in children {
+            attach_node(child, &target_ws);
         }
     }
 }
 
+/// Find the DOM element that a portal renders its children into.
+pub(crate) fn portal_target(target: &str) -> Option<web_sys::Node> {
+    let target_ws = crate::util::document()
+        .query_selector(target)
+        .ok()
+        .and_then(std::convert::identity);
+    if target_ws.is_none() {
+        crate::error(&format!("Can't find the portal target: {}", target));
+    }
+    target_ws.map(Into::into)
+}
+
 /// Attaches the element, and all children, recursively. Only run this when creating a fresh vdom node, since
 /// it performs a rerender of the el and all children; eg a potentially-expensive op.
 /// This is where rendering occurs.
@@ -176,12 +221,7 @@ pub fn attach_el_and_children<Ms>(el_vdom: &mut El<Ms>, parent: &web_sys::Node)
 
     // appending the its children to the el_ws
     for child in &mut el_vdom.children {
-        match child {
-            // Raise the active level once per recursion.
-            Node::Element(child_el) => attach_el_and_children(child_el, el_ws),
-            Node::Text(child_text) => attach_text_node(child_text, el_ws),
-            Node::Empty => (),
-        }
+        attach_node(child, el_ws);
     }
 
     // Note: Call `set_default_element_state` after child appending,
@@ -231,25 +271,20 @@ pub fn _remove_children(el: &web_sys::Node) {
 /// process children, and assumes the tag is the same. Assume we've identfied
 /// the most-correct pairing between new and old.
 pub fn patch_el_details<Ms>(old: &mut El<Ms>, new: &mut El<Ms>, old_el_ws: &web_sys::Node) {
+    #[cfg(feature = "profiling")]
+    profiling::record_patched(new);
+
     // Perform side-effects specified for updating
     if let Some(update_actions) = &mut new.hooks.did_update {
         (update_actions.actions)(old_el_ws) // todo
     }
 
+    for patch in diff::diff_details(old, new, &[]) {
+        apply_patch(old_el_ws, &patch);
+    }
+
     if old.attrs != new.attrs {
         for (key, new_val) in &new.attrs.vals {
-            match old.attrs.vals.get(key) {
-                Some(old_val) => {
-                    // The value's different
-                    if old_val != new_val {
-                        set_attr_value(old_el_ws, key, new_val);
-                    }
-                }
-                None => {
-                    set_attr_value(old_el_ws, key, new_val);
-                }
-            }
-
             // We handle value in the vdom using attributes, but the DOM needs
             // to use set_value or set_checked.
             match key {
@@ -267,26 +302,34 @@ pub fn patch_el_details<Ms>(old: &mut El<Ms>, new: &mut El<Ms>, old_el_ws: &web_
                 crate::error(err);
             })
         }
-        // Remove attributes that aren't in the new vdom.
-        for name in old.attrs.vals.keys() {
-            if new.attrs.vals.get(name).is_none() {
-                // todo get to the bottom of this
-                match old_el_ws.dyn_ref::<web_sys::Element>() {
-                    Some(el) => el
-                        .remove_attribute(name.as_str())
-                        .expect("Removing an attribute"),
-                    None => {
-                        crate::error("Minor error on html element (setting attrs)");
-                    }
+    }
+}
+
+/// Apply a `Patch` that changes only the given node; the node has to be found by the caller.
+/// Structural patches (`ReplaceNode`, `InsertChild`, `RemoveChild` and `MoveChild`) are carried
+/// out by `patch::patch_els`, because they also attach listeners and run lifecycle hooks.
+pub fn apply_patch(node_ws: &web_sys::Node, patch: &Patch) {
+    match patch {
+        Patch::SetAttr { key, val, .. } => set_attr_value(node_ws, key, val),
+        Patch::RemoveAttr { key, .. } => {
+            // todo get to the bottom of this
+            match node_ws.dyn_ref::<web_sys::Element>() {
+                Some(el) => el
+                    .remove_attribute(key.as_str())
+                    .expect("Removing an attribute"),
+                None => {
+                    crate::error("Minor error on html element (setting attrs)");
                 }
             }
         }
-    }
-
-    // Patch style.
-    if old.style != new.style {
-        // We can't patch each part of style; rewrite the whole attribute.
-        set_style(old_el_ws, &new.style)
+        Patch::SetStyle { style, .. } => set_style(node_ws, style),
+        Patch::SetText { text, .. } => node_ws.set_text_content(Some(text)),
+        Patch::ReplaceNode { .. }
+        | Patch::InsertChild { .. }
+        | Patch::RemoveChild { .. }
+        | Patch::MoveChild { .. } => {
+            crate::error("Structural patches can't be applied to a single node");
+        }
     }
 }
 
@@ -296,11 +339,13 @@ impl<Ms> From<&web_sys::Element> for El<Ms> {
     /// and markdown strings. Includes children, recursively added.
     #[allow(clippy::too_many_lines)]
     fn from(ws_el: &web_sys::Element) -> Self {
-        // Result of tag_name is all caps, but tag From<String> expects lower.
+        // Result of tag_name is all caps for HTML elements, but tag From<String> expects lower.
+        // Local names are lowercase for HTML elements and keep the case of SVG ones
+        // (eg `linearGradient`).
         // Probably is more pure to match by xlmns attribute instead.
-        let mut el = match ws_el.tag_name().to_lowercase().as_ref() {
-            "svg" => El::empty_svg(ws_el.tag_name().to_lowercase().into()),
-            _ => El::empty(ws_el.tag_name().to_lowercase().into()),
+        let mut el = match ws_el.local_name().as_ref() {
+            "svg" => El::empty_svg(ws_el.local_name().into()),
+            _ => El::empty(ws_el.local_name().into()),
         };
 
         // Populate attributes
@@ -454,6 +499,28 @@ pub fn node_from_ws<Ms>(node: &web_sys::Node) -> Option<Node<Ms>> {
     }
 }
 
+/// Assign the DOM node and its descendants to the vdom element created from it by `node_from_ws`.
+pub(crate) fn adopt_ws_nodes<Ms>(el: &mut El<Ms>, node: &web_sys::Node) {
+    el.node_ws = Some(node.clone());
+    // `node_from_ws` imports only element and text nodes.
+    let child_nodes = node.child_nodes();
+    let children_ws = (0..child_nodes.length())
+        .filter_map(|i| child_nodes.get(i))
+        .filter(|child| {
+            matches!(
+                child.node_type(),
+                web_sys::Node::ELEMENT_NODE | web_sys::Node::TEXT_NODE
+            )
+        });
+    for (child, child_ws) in el.children.iter_mut().zip(children_ws) {
+        match child {
+            Node::Element(child_el) => adopt_ws_nodes(child_el, &child_ws),
+            Node::Text(text) => text.node_ws = Some(child_ws),
+            Node::Empty | Node::Portal { .. } => (),
+        }
+    }
+}
+
 /// Insert a new node into the specified part of the DOM tree.
 pub(crate) fn insert_node(
     node: &web_sys::Node,
@@ -478,8 +545,423 @@ pub(crate) fn remove_node(node: &web_sys::Node, parent: &web_sys::Node) {
         .expect("Problem removing old el_ws when updating to empty");
 }
 
+/// Add the animation's class to the node, and remove the node once its animation
+/// or transition ends - or after the animation's duration at the latest.
+/// Until then, patching skips the node; see `next_patched_node`.
+pub(crate) fn remove_node_animated(
+    node: &web_sys::Node,
+    parent: &web_sys::Node,
+    animation: &LeaveAnimation,
+) {
+    let el_ws = match node.dyn_ref::<web_sys::Element>() {
+        Some(el_ws) => el_ws,
+        None => return remove_node(node, parent),
+    };
+    if el_ws.class_list().add_1(&animation.class).is_err() {
+        crate::error("Problem adding the leave animation class");
+    }
+    if js_sys::Reflect::set(node, &JsValue::from_str(LEAVING_KEY), &JsValue::TRUE).is_err() {
+        crate::error("Problem marking the leaving node");
+    }
+
+    // The end listener and the fallback timeout; the first of them to fire takes both.
+    let pending = Rc::new(RefCell::new(
+        None::<(Closure<dyn FnMut(web_sys::Event)>, Timeout)>,
+    ));
+    let remove = {
+        let (node, parent, pending) = (node.clone(), parent.clone(), Rc::clone(&pending));
+        Rc::new(move || {
+            // Dropping the timeout cancels it.
+            let (end_listener, _timeout) = match pending.borrow_mut().take() {
+                Some(pending) => pending,
+                None => return,
+            };
+            for event in &LEAVE_END_EVENTS {
+                let _ = node.remove_event_listener_with_callback(
+                    event,
+                    end_listener.as_ref().unchecked_ref(),
+                );
+            }
+            // The parent may have been removed or replaced in the meantime.
+            if node.parent_node().as_ref() == Some(&parent) {
+                remove_node(&node, &parent);
+            }
+        })
+    };
+
+    let end_listener = {
+        let (node, remove) = (node.clone(), Rc::clone(&remove));
+        Closure::new(move |event: web_sys::Event| {
+            // Animations of the descendants don't count.
+            let is_own = event
+                .target()
+                .is_some_and(|target| node.is_same_node(Some(target.unchecked_ref())));
+            if is_own {
+                remove();
+            }
+        })
+    };
+    for event in &LEAVE_END_EVENTS {
+        if node
+            .add_event_listener_with_callback(event, end_listener.as_ref().unchecked_ref())
+            .is_err()
+        {
+            crate::error("Problem adding the leave animation listener");
+        }
+    }
+    let timeout = Timeout::new(animation.duration.as_millis() as u32, move || remove());
+    pending.replace(Some((end_listener, timeout)));
+}
+
+/// The node after the `previous` one (or the first child of the `parent`), skipping the nodes
+/// of removed elements that are still running their leave animations - they aren't
+/// in the vdom.
+pub(crate) fn next_patched_node(
+    parent: &web_sys::Node,
+    previous: Option<&web_sys::Node>,
+) -> Option<web_sys::Node> {
+    let key = JsValue::from_str(LEAVING_KEY);
+    let mut next = match previous {
+        Some(previous) => previous.next_sibling(),
+        None => parent.first_child(),
+    };
+    while let Some(node) = &next {
+        let is_leaving = js_sys::Reflect::get(node, &key).is_ok_and(|leaving| leaving.is_truthy());
+        if !is_leaving {
+            break;
+        }
+        next = node.next_sibling();
+    }
+    next
+}
+
+/// Add the class to the inserted node, and remove it in the next frame;
+/// so the element transitions from the styles of the class to its own ones.
+pub(crate) fn animate_enter(node: &web_sys::Node, class: &str) {
+    let el_ws = match node.dyn_ref::<web_sys::Element>() {
+        Some(el_ws) => el_ws.clone(),
+        None => return,
+    };
+    if el_ws.class_list().add_1(class).is_err() {
+        crate::error("Problem adding the enter animation class");
+    }
+
+    let class = class.to_owned();
+    // The first frame renders the element with the class, so remove it in the second one.
+    let next_frame = Closure::once_into_js(move || {
+        let remove_class = Closure::once_into_js(move || {
+            if el_ws.class_list().remove_1(&class).is_err() {
+                crate::error("Problem removing the enter animation class");
+            }
+        });
+        crate::util::window()
+            .request_animation_frame(remove_class.unchecked_ref())
+            .expect("Problem requesting animation frame");
+    });
+    crate::util::window()
+        .request_animation_frame(next_frame.unchecked_ref())
+        .expect("Problem requesting animation frame");
+}
+
+/// Play the move of the element from the `old_rect` to its current position (FLIP):
+/// render it at the old position with `transform`, then add the class and remove the transform,
+/// so it transitions to the current position. The class is removed after the `duration`.
+pub(crate) fn animate_move(
+    node: &web_sys::Node,
+    old_rect: &web_sys::DomRect,
+    animation: &MoveAnimation,
+) {
+    let el_ws = match node.dyn_ref::<web_sys::HtmlElement>() {
+        Some(el_ws) => el_ws.clone(),
+        None => return,
+    };
+    let new_rect = el_ws.get_bounding_client_rect();
+    let (dx, dy) = (
+        old_rect.left() - new_rect.left(),
+        old_rect.top() - new_rect.top(),
+    );
+    if dx.abs() < 0.5 && dy.abs() < 0.5 {
+        return;
+    }
+
+    let style = el_ws.style();
+    style.set_property("transition", "none").ok();
+    style
+        .set_property("transform", &format!("translate({}px, {}px)", dx, dy))
+        .ok();
+    // Read the layout, so the old position is rendered before the transition starts.
+    el_ws.offset_height();
+    if el_ws.class_list().add_1(&animation.class).is_err() {
+        crate::error("Problem adding the move animation class");
+    }
+    style.remove_property("transition").ok();
+    style.remove_property("transform").ok();
+
+    let class = animation.class.clone();
+    Timeout::new(animation.duration.as_millis() as u32, move || {
+        if el_ws.class_list().remove_1(&class).is_err() {
+            crate::error("Problem removing the move animation class");
+        }
+    })
+    .forget();
+}
+
+/// Name of the node's property with the hash of HTML set by `sync_inner_html`.
+const INNER_HTML_HASH_KEY: &str = "seedInnerHtmlHash";
+
+/// Set `innerHTML` of the element, unless the HTML with the same `hash` has been set already.
+pub(crate) fn sync_inner_html(node: &web_sys::Node, html: &str, hash: &str) {
+    let element = match node.dyn_ref::<web_sys::Element>() {
+        Some(element) => element,
+        None => return,
+    };
+    let key = JsValue::from_str(INNER_HTML_HASH_KEY);
+    let current_hash = js_sys::Reflect::get(node, &key)
+        .ok()
+        .and_then(|hash| hash.as_string());
+    if current_hash.as_deref() == Some(hash) {
+        return;
+    }
+    element.set_inner_html(html);
+    if js_sys::Reflect::set(node, &key, &JsValue::from_str(hash)).is_err() {
+        crate::error("Problem storing HTML hash");
+    }
+}
+
+/// Call `show()`, `showModal()` or `close()` if the `dialog`'s state differs from the `state`.
+/// An open dialog can't switch between modal and non-modal, so it's closed and shown again.
+pub(crate) fn sync_dialog(node: &web_sys::Node, state: DialogState) {
+    let dialog = match node.dyn_ref::<web_sys::HtmlDialogElement>() {
+        Some(dialog) => dialog,
+        None => return,
+    };
+    let show_modal = || {
+        dialog.show_modal().unwrap_or_else(|err| {
+            crate::error(err);
+        })
+    };
+    // Browsers without `:modal` keep the dialog as it is.
+    let is_modal = || dialog.matches(":modal").ok();
+    match state {
+        DialogState::Closed if dialog.open() => dialog.close(),
+        DialogState::Open if !dialog.open() => dialog.show(),
+        DialogState::Open if is_modal() == Some(true) => {
+            dialog.close();
+            dialog.show();
+        }
+        DialogState::Modal if !dialog.open() => show_modal(),
+        DialogState::Modal if is_modal() == Some(false) => {
+            dialog.close();
+            show_modal();
+        }
+        _ => (),
+    }
+}
+
+/// Get `scrollTop` and `scrollLeft` of the node; `None` if it isn't an element.
+pub(crate) fn scroll_position(node: &web_sys::Node) -> Option<(i32, i32)> {
+    node.dyn_ref::<web_sys::Element>()
+        .map(|el| (el.scroll_top(), el.scroll_left()))
+}
+
+pub(crate) fn set_scroll_position(node: &web_sys::Node, (top, left): (i32, i32)) {
+    if let Some(el) = node.dyn_ref::<web_sys::Element>() {
+        el.set_scroll_top(top);
+        el.set_scroll_left(left);
+    }
+}
+
+/// The focused element and its text selection, captured before patching;
+/// see `capture_focus` and `restore_focus`.
+pub(crate) struct FocusSnapshot {
+    element: web_sys::Element,
+    tag_name: String,
+    /// Indexes of child nodes leading from the root to the element.
+    path: Vec<u32>,
+    selection: Option<(u32, u32)>,
+}
+
+fn selection_range(element: &web_sys::Element) -> Option<(u32, u32)> {
+    // Some input types (eg `number`) don't support selection; the getters fail or return `None`.
+    let (start, end) = if let Some(input) = element.dyn_ref::<web_sys::HtmlInputElement>() {
+        (input.selection_start(), input.selection_end())
+    } else if let Some(textarea) = element.dyn_ref::<web_sys::HtmlTextAreaElement>() {
+        (textarea.selection_start(), textarea.selection_end())
+    } else {
+        return None;
+    };
+    Some((start.ok()??, end.ok()??))
+}
+
+fn set_selection_range(element: &web_sys::Element, (start, end): (u32, u32)) {
+    // Errors are ignored - the element may not support selection.
+    if let Some(input) = element.dyn_ref::<web_sys::HtmlInputElement>() {
+        input.set_selection_range(start, end).ok();
+    } else if let Some(textarea) = element.dyn_ref::<web_sys::HtmlTextAreaElement>() {
+        textarea.set_selection_range(start, end).ok();
+    }
+}
+
+/// Remember the focused element, if it's inside the `root`, so `restore_focus` can
+/// focus it again if it's lost during patching.
+pub(crate) fn capture_focus(root: &web_sys::Node) -> Option<FocusSnapshot> {
+    let element = crate::util::document().active_element()?;
+    if root.is_same_node(Some(&element)) || !root.contains(Some(&element)) {
+        return None;
+    }
+
+    let mut path = Vec::new();
+    let mut node: web_sys::Node = element.clone().into();
+    while !root.is_same_node(Some(&node)) {
+        let parent = node.parent_node()?;
+        let children = parent.child_nodes();
+        let index = (0..children.length())
+            .find(|index| node.is_same_node(children.item(*index).as_ref()))?;
+        path.push(index);
+        node = parent;
+    }
+    path.reverse();
+
+    Some(FocusSnapshot {
+        tag_name: element.tag_name(),
+        selection: selection_range(&element),
+        element,
+        path,
+    })
+}
+
+/// Focus the element captured by `capture_focus` if it has lost focus, and restore its selection.
+/// If the element has been recreated, its replacement is found by its position in the `root`
+/// and its tag.
+pub(crate) fn restore_focus(root: &web_sys::Node, focus: FocusSnapshot) {
+    if let Some(active) = crate::util::document().active_element() {
+        if active.is_same_node(Some(&focus.element)) {
+            return;
+        }
+    }
+
+    let element = if root.contains(Some(&focus.element)) {
+        focus.element
+    } else {
+        let replacement = focus
+            .path
+            .iter()
+            .try_fold(root.clone(), |node, index| node.child_nodes().item(*index));
+        match replacement.and_then(|node| node.dyn_into::<web_sys::Element>().ok()) {
+            Some(element) if element.tag_name() == focus.tag_name => element,
+            _ => return,
+        }
+    };
+
+    if let Some(html_element) = element.dyn_ref::<web_sys::HtmlElement>() {
+        if html_element.focus().is_err() {
+            crate::error("Problem restoring focus");
+        }
+    }
+    if let Some(selection) = focus.selection {
+        set_selection_range(&element, selection);
+    }
+}
+
 pub(crate) fn replace_child(new: &web_sys::Node, old: &web_sys::Node, parent: &web_sys::Node) {
     parent
         .replace_child(new, old)
         .expect("Problem replacing element");
 }
+
+/// Counts of created and patched elements, collected only with the `profiling` feature;
+/// eg to find components that are rendered too often.
+#[cfg(feature = "profiling")]
+pub mod profiling {
+    use crate::virtual_dom::{At, AtValue, El};
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    thread_local! {
+        static RENDER_STATS: RefCell<RenderStats> = RefCell::new(RenderStats::default());
+    }
+
+    /// Counts of elements by their identity - `tag[key=..]` for keyed elements (see `El::key`),
+    /// `tag#id` for elements with an `id`, otherwise `tag`.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct RenderStats {
+        /// Elements created in the DOM; including the ones that replaced old elements.
+        pub created: BTreeMap<String, usize>,
+        /// Elements whose attributes or style have been patched. Elements are patched
+        /// only if they differ from the old ones; children aren't compared.
+        pub patched: BTreeMap<String, usize>,
+    }
+
+    /// Counts collected since the app started or since the last `reset_render_stats`.
+    pub fn render_stats() -> RenderStats {
+        RENDER_STATS.with(|stats| stats.borrow().clone())
+    }
+
+    pub fn reset_render_stats() {
+        RENDER_STATS.with(|stats| *stats.borrow_mut() = RenderStats::default());
+    }
+
+    fn identity<Ms>(el: &El<Ms>) -> String {
+        if let Some(key) = &el.key {
+            return format!("{}[key={}]", el.tag.name(), key);
+        }
+        match el.attrs.vals.get(&At::Id) {
+            Some(AtValue::Some(id)) => format!("{}#{}", el.tag.name(), id),
+            _ => el.tag.name().into_owned(),
+        }
+    }
+
+    pub(crate) fn record_created<Ms>(el: &El<Ms>) {
+        RENDER_STATS
+            .with(|stats| *stats.borrow_mut().created.entry(identity(el)).or_insert(0) += 1);
+    }
+
+    pub(crate) fn record_patched<Ms>(el: &El<Ms>) {
+        RENDER_STATS
+            .with(|stats| *stats.borrow_mut().patched.entry(identity(el)).or_insert(0) += 1);
+    }
+
+    #[cfg(test)]
+    pub mod tests {
+        use super::*;
+        use crate::virtual_dom::Tag;
+        use wasm_bindgen_test::*;
+
+        #[derive(Clone, Debug)]
+        enum Msg {}
+
+        #[wasm_bindgen_test]
+        fn stats_by_identity() {
+            let mut list: El<Msg> = El::empty(Tag::Ul);
+            list.add_attr("id", "todos");
+            let item: El<Msg> = El::empty(Tag::Li);
+
+            reset_render_stats();
+            record_created(&list);
+            record_patched(&item);
+            record_patched(&item);
+
+            let stats = render_stats();
+            assert_eq!(stats.created.get("ul#todos"), Some(&1));
+            assert_eq!(stats.patched.get("li"), Some(&2));
+            assert_eq!(stats.patched.get("ul#todos"), None);
+
+            reset_render_stats();
+            let mut first_item: El<Msg> = El::empty(Tag::Li);
+            first_item.key(1).add_attr("id", "first");
+            let mut second_item: El<Msg> = El::empty(Tag::Li);
+            second_item.key(2);
+            record_patched(&first_item);
+            record_patched(&second_item);
+            record_patched(&second_item);
+
+            let stats = render_stats();
+            assert_eq!(stats.patched.get("li[key=1]"), Some(&1));
+            assert_eq!(stats.patched.get("li[key=2]"), Some(&2));
+            assert_eq!(stats.patched.get("li"), None);
+
+            reset_render_stats();
+            assert_eq!(render_stats(), RenderStats::default());
+        }
+    }
+}
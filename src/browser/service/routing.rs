@@ -105,12 +105,19 @@ pub fn setup_hashchange_listener<Ms>(
 
 /// Set up a listener that intercepts clicks on elements containing an Href attribute,
 /// so we can prevent page refresh for internal links, and route internally.  Run this on load.
+///
+/// The listener is added to the app's `mount_point`, so only links inside of the app are
+/// intercepted and more apps can be mounted on the same page. If the mount points are nested,
+/// each app whose `routes` accept the link gets its message, but the route is pushed only once.
 #[allow(clippy::option_map_unit_fn)]
-pub fn setup_link_listener<Ms>(update: impl Fn(Ms) + 'static, routes: fn(Url) -> Option<Ms>)
-where
+pub fn setup_link_listener<Ms>(
+    update: impl Fn(Ms) + 'static,
+    routes: fn(Url) -> Option<Ms>,
+    mount_point: &web_sys::Element,
+) where
     Ms: 'static,
 {
-    let closure = Closure::new(move |event: web_sys::Event| {
+    let closure: Closure<dyn FnMut(web_sys::Event)> = Closure::new(move |event: web_sys::Event| {
         event.target()
             .and_then(|et| et.dyn_into::<web_sys::Element>().ok())
             .and_then(|el| el.closest("[href]").ok())
@@ -140,8 +147,13 @@ where
                     // Only update when requested for an update by the user.
                     let url = Url::try_from(href).expect("cast link href to `Url`");
                     if let Some(redirect_msg) = routes(url.clone()) {
-                        // Route internally, overriding the default history
-                        push_route(url);
+                        // Route internally, overriding the default history.
+                        // Apps with nested mount points get the same event - only the first
+                        // one pushes the route, so the history entry isn't duplicated.
+                        if !is_routed(&event) {
+                            push_route(url);
+                            set_routed(&event);
+                        }
                         event.prevent_default(); // Prevent page refresh
                         update(redirect_msg);
                     }
@@ -149,13 +161,28 @@ where
             });
     });
 
-    (util::document().as_ref() as &web_sys::EventTarget)
+    (mount_point.as_ref() as &web_sys::EventTarget)
         .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
         .expect("Problem setting up link interceptor");
 
     closure.forget(); // todo: Can we store the closure somewhere to avoid using forget?
 }
 
+/// Name of the property marking a click event whose route has already been pushed.
+const ROUTED_EVENT_KEY: &str = "seedRouted";
+
+fn is_routed(event: &web_sys::Event) -> bool {
+    js_sys::Reflect::get(event, &JsValue::from_str(ROUTED_EVENT_KEY))
+        .map(|routed| routed.is_truthy())
+        .unwrap_or(false)
+}
+
+fn set_routed(event: &web_sys::Event) {
+    if js_sys::Reflect::set(event, &JsValue::from_str(ROUTED_EVENT_KEY), &JsValue::TRUE).is_err() {
+        crate::error("Problem marking routed event");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use wasm_bindgen_test::*;
@@ -3,6 +3,8 @@
 
 // @TODO refactor (ideally once `Unsized` and `Specialization` are stable)
 
+use crate::virtual_dom::Dir;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 use web_sys;
@@ -45,6 +47,16 @@ pub fn history() -> web_sys::History {
     window().history().expect("Can't find history")
 }
 
+/// Set the direction of the page's text (`dir` of the `html` element); eg `Dir::Rtl`
+/// for a right-to-left locale. See `El::dir` for parts of the page.
+pub fn set_document_dir(dir: Dir) {
+    if let Some(html) = document().document_element() {
+        if html.set_attribute("dir", dir.as_str()).is_err() {
+            error("Problem setting the document direction");
+        }
+    }
+}
+
 /// Convenience function to access the `web_sys::HtmlDocument`.
 pub fn html_document() -> web_sys::HtmlDocument {
     wasm_bindgen::JsValue::from(document()).unchecked_into::<web_sys::HtmlDocument>()
@@ -86,6 +98,14 @@ pub fn cookies() -> Option<cookie::CookieJar> {
     }
 }
 
+/// Generate an id that is unique within the app; eg to associate a `label` with its input.
+/// Generate it once (eg in `init`) and store it; a new id in each `view` call would
+/// be patched into the DOM on each render.
+pub fn unique_id(prefix: &str) -> String {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    format!("{}-{}", prefix, NEXT_ID.fetch_add(1, Ordering::Relaxed))
+}
+
 /// Request the animation frame.
 pub fn request_animation_frame(
     f: Closure<dyn FnMut(RequestAnimationFrameTime)>,
@@ -2,15 +2,15 @@
 make_attrs! {
     // Missing data-*
     Accept => "accept", AcceptCharset => "accept-charset", AccessKey => "accesskey", Action => "action",
-    Alt => "alt", Async => "async", AutoComplete => "autocomplete", AutoFocus => "autofocus",
+    Alt => "alt", Async => "async", AutoCapitalize => "autocapitalize", AutoComplete => "autocomplete", AutoCorrect => "autocorrect", AutoFocus => "autofocus",
     AutoPlay => "autoplay", Charset => "charset", Checked => "checked", Cite => "cite", Class => "class",
     Color => "color", Cols => "cols", ColSpan => "colspan", Content => "content", ContentEditable => "contenteditable",
-    Controls => "controls", Coords => "coords", Data => "data", DateTime => "datetime", Default => "default",
+    Controls => "controls", Coords => "coords", Data => "data", DateTime => "datetime", Decoding => "decoding", Default => "default",
     Defer => "defer", Dir => "dir", DirName => "dirname", Disabled => "disabled", Download => "download",
-    Draggable => "draggable", DropZone => "dropzone", EncType => "enctype", For => "for", Form => "form",
-    FormAction => "formaction", Headers => "headers", Height => "height", Hidden => "hidden", High => "high",
-    Href => "href", HrefLang => "hreflang", HttpEquiv => "http-equiv", Id => "id", IsMap => "ismap",
-    Kind => "kind", Label => "label", Lang => "lang", List => "list", Loop => "loop", Low => "low",
+    Draggable => "draggable", DropZone => "dropzone", EncType => "enctype", EnterKeyHint => "enterkeyhint", For => "for", Form => "form",
+    FormAction => "formaction", FormMethod => "formmethod", Headers => "headers", Height => "height", Hidden => "hidden", High => "high",
+    Href => "href", HrefLang => "hreflang", HttpEquiv => "http-equiv", Id => "id", InputMode => "inputmode", IsMap => "ismap",
+    Kind => "kind", Label => "label", Lang => "lang", List => "list", Loading => "loading", Loop => "loop", Low => "low",
     Max => "max", MaxLength => "maxlength", Media => "media", Method => "method", Min => "min", MinLength => "minlength",
     Multiple => "multiple", Muted => "muted", Name => "name", NoValidate => "novalidate", OnAbort => "onabort",
     OnAfterPrint => "onafterprint", OnBeforePrint => "onbeforeprint", OnBeforeUnload => "onbeforeunload",
@@ -32,6 +32,7 @@ make_attrs! {
     OnStorage => "onstorage", OnSubmit => "onsubmit", OnSuspend => "onsuspend", OnTimeUpdate => "ontimeupdate",
     OnToggle => "ontoggle", OnUnload => "onunload", OnVolumeChange => "onvolumechange", OnWaiting => "onwaiting",
     OnWheel => "onwheel", Open => "open", Optimum => "optimum", Pattern => "pattern", Placeholder => "placeholder",
+    Popover => "popover", PopoverTarget => "popovertarget", PopoverTargetAction => "popovertargetaction",
     Poster => "poster", Preload => "preload", ReadOnly => "readonly", Rel => "rel", Required => "required",
     Reversed => "reversed", Rows => "rows", RowSpan => "rowspan", Sandbox => "sandbox", Scope => "scope",
     Selected => "selected", Shape => "shape", Size => "size", Span => "span", SpellCheck => "spellcheck",
@@ -40,6 +41,13 @@ make_attrs! {
     Translate => "translate", Type => "type", UseMap => "usemap", Value => "value", Width => "width",
     Wrap => "wrap",
 
+    // ARIA
+    // https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA/Attributes
+    AriaAtomic => "aria-atomic", AriaBusy => "aria-busy", AriaControls => "aria-controls",
+    AriaDescribedBy => "aria-describedby", AriaExpanded => "aria-expanded",
+    AriaLabel => "aria-label", AriaLabelledBy => "aria-labelledby", AriaLive => "aria-live",
+    Role => "role",
+
     // SVG
     // https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute
     AccentHeight => "accent-height", Accumulate => "accumulate", Additive => "additive",
@@ -10,6 +10,8 @@ macro_rules! make_events {
             $(
                 $event_camel,
             )+
+            /// An event with any other name; eg dispatched by a web component. See `Ev::custom`.
+            Custom(&'static str),
         }
 
         impl Ev {
@@ -18,6 +20,18 @@ macro_rules! make_events {
                     $ (
                         Ev::$event_camel => $event,
                     ) +
+                    Ev::Custom(event) => event,
+                }
+            }
+
+            /// Create an `Ev` from an arbitrary name; known names get their own variants.
+            /// Names of `Ev::Custom` events are stored for the lifetime of the app.
+            pub fn custom(event: &str) -> Self {
+                match event {
+                    $ (
+                          $event => Ev::$event_camel,
+                    ) +
+                    _ => Ev::Custom(super::intern_event_name(event)),
                 }
             }
         }
@@ -56,6 +70,7 @@ macro_rules! make_events {
                     $ (
                         Ev::$ event_camel => $ event.into(),
                     ) +
+                    Ev::Custom(event) => (*event).into(),
 
                 }
             }
@@ -63,5 +78,23 @@ macro_rules! make_events {
     }
 }
 
+/// Leak each distinct name once, so `Ev` can stay `Copy`.
+fn intern_event_name(event: &str) -> &'static str {
+    thread_local! {
+        static NAMES: std::cell::RefCell<std::collections::HashSet<&'static str>> = Default::default();
+    }
+    NAMES.with(|names| {
+        let mut names = names.borrow_mut();
+        match names.get(event) {
+            Some(name) => name,
+            None => {
+                let name: &'static str = Box::leak(event.to_owned().into_boxed_str());
+                names.insert(name);
+                name
+            }
+        }
+    })
+}
+
 mod event_names;
 pub use event_names::Ev;
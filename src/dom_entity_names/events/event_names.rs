@@ -2,6 +2,7 @@
 make_events! {
     Cached => "cached", Error => "error", Abort => "abort", Load => "load", BeforeUnload => "beforeunload",
     Unload => "unload", Online => "online", Offline => "offline", Focus => "focus", Blur => "blur",
+    FocusIn => "focusin", FocusOut => "focusout",
     Open => "open", Message => "message", Close => "close", PageHide => "pagehide",
     PageShow => "pageshow", PopState => "popstate", AnimationStart => "animationstart", AnimationEnd => "animationend",
     AnimationIteration => "animationiteration", TransitionStart => "transtionstart", TransitionEnd => "transitionend",
@@ -40,6 +41,8 @@ make_events! {
 
     Input => "input",
 
+    SlotChange => "slotchange",
+
     // deprecated, @TODO: remove
     TriggerUpdate => "triggerupdate"
 }
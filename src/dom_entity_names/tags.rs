@@ -24,6 +24,17 @@ macro_rules! make_tags {
                     ) +
                 }
             }
+
+            /// The element name - lowercase for HTML and custom elements; SVG elements
+            /// keep their case (eg `linearGradient`), because SVG is case-sensitive.
+            pub fn name(&self) -> std::borrow::Cow<'_, str> {
+                match self {
+                    Tag::Custom(name) if name.chars().any(char::is_uppercase) => {
+                        name.to_lowercase().into()
+                    }
+                    tag => tag.as_str().into(),
+                }
+            }
         }
 
         impl<'a, T: Into<std::borrow::Cow<'a, str>>> From<T> for Tag {
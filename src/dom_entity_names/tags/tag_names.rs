@@ -26,7 +26,7 @@ make_tags! {
     Applet => "applet", Embed => "embed", Iframe => "iframe",
     NoEmbed => "noembed", Object => "object", Param => "param", Picture => "picture", Source => "source",
 
-    Canvas => "canvas", NoScript => "noscript", Script => "Script",
+    Canvas => "canvas", NoScript => "noscript", Script => "script",
 
     Del => "del", Ins => "ins",
 
@@ -21,7 +21,7 @@ pub use crate::{
     browser::url::Url,
     browser::util::{
         self, body, canvas, canvas_context_2d, cookies, document, error, history, html_document,
-        log, update, window,
+        log, set_document_dir, unique_id, update, window,
     },
     virtual_dom::{attrs::Attrs, listener::Listener, style::Style},
 };
@@ -32,6 +32,8 @@ pub mod shortcuts;
 pub mod app;
 pub mod browser;
 pub mod dom_entity_names;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod virtual_dom;
 
 /// Create an element flagged in a way that it will not be rendered. Useful
@@ -80,16 +82,27 @@ pub fn set_timeout(handler: Box<dyn Fn()>, timeout: i32) {
 /// for element-creation macros, input event constructors, and the `History` struct.
 /// Expose the `wasm_bindgen` prelude, and lifecycle hooks.
 pub mod prelude {
+    #[cfg(feature = "profiling")]
+    pub use crate::browser::dom::virtual_dom_bridge::profiling::{
+        render_stats, reset_render_stats, RenderStats,
+    };
     pub use crate::{
         app::{
             builder::init::Init, AfterMount, App, BeforeMount, MessageMapper, MountType, Orders,
-            RenderTimestampDelta, UrlHandling,
+            RenderPanic, RenderTimestampDelta, UrlHandling,
         },
         browser::dom::css_units::*,
+        browser::dom::el_ref::ElRef,
         browser::dom::event_handler::{
-            input_ev, keyboard_ev, mouse_ev, pointer_ev, raw_ev, simple_ev, trigger_update_handler,
+            animationend_ev, blur_ev, clipboard_data, clipboard_text, copy_ev, custom_ev, cut_ev,
+            debounce, focus_ev, form_submit_ev, form_submit_ev_multi, input_ev, input_ev_int,
+            input_ev_number, keyboard_ev, mouse_ev, paste_ev, pointer_ev, raw_ev,
+            set_clipboard_text, simple_ev, slotchange_ev, transitionend_ev, trigger_update_handler,
         },
+        browser::dom::key_combo::KeyCombo,
         browser::dom::lifecycle_hooks::{did_mount, did_update, will_unmount},
+        browser::dom::observers::{ContentRect, IntersectOptions, IntersectionInfo},
+        browser::dom::svg_path::PathBuilder,
         browser::util::{
             request_animation_frame, ClosureNew, RequestAnimationFrameHandle,
             RequestAnimationFrameTime,
@@ -99,7 +112,11 @@ pub mod prelude {
         // https://github.com/rust-lang-nursery/reference/blob/master/src/macros-by-example.md
         shortcuts::*,
         virtual_dom::{
-            AsAtValue, At, AtValue, CSSValue, El, Ev, Listener, Node, St, Tag, UpdateEl, View,
+            intersperse, keyed_list, transition_group, virtual_window, visible_range, AsAtValue,
+            At, AtValue, AutoCapitalize, Autocomplete, CSSValue, CSSValuePart, Decoding,
+            DialogState, Dir, Easing, El, EnterKeyHint, Ev, FormMethod, InputMode, LinkOptions,
+            Listener, ListenerOptions, MarkdownOptions, Node, Politeness, PopoverMode, Props, Role,
+            St, Tag, Template, Theme, TransitionGroup, UpdateEl, ValidationWarning, View,
         },
     };
     pub use indexmap::IndexMap; // for attrs and style to work.
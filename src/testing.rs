@@ -0,0 +1,148 @@
+//! This module contains helpers for testing views; enable the `testing` feature
+//! to use them in your tests.
+
+use crate::virtual_dom::{diff, El, Node, Patch};
+
+/// Assert that rendering the `new` element wouldn't change the DOM of the `old` one
+/// and that their listeners are the same, so they don't have to be reattached;
+/// eg to check that a view function gives the same result for the same model.
+///
+/// # Panics
+/// With a description of the first difference; see `El::diff`.
+pub fn assert_no_diff<Ms>(old: &El<Ms>, new: &El<Ms>) {
+    let patches = El::diff(old, new);
+    if let Some(patch) = patches.first() {
+        panic!(
+            "The elements differ ({} patches); the first difference: {}",
+            patches.len(),
+            describe(patch)
+        );
+    }
+    if let Some(path) = first_listener_difference(old, new, &mut Vec::new()) {
+        panic!("Listeners of the element at {:?} differ", path);
+    }
+}
+
+fn describe(patch: &Patch) -> String {
+    match patch {
+        Patch::ReplaceNode { path } => format!("the node at {:?} is replaced", path),
+        Patch::SetAttr { path, key, val } => format!(
+            "attribute `{}` of the element at {:?} is set to {:?}",
+            key.as_str(),
+            path,
+            val
+        ),
+        Patch::RemoveAttr { path, key } => format!(
+            "attribute `{}` of the element at {:?} is removed",
+            key.as_str(),
+            path
+        ),
+        Patch::SetStyle { path, style } => {
+            format!("style of the element at {:?} is set to `{}`", path, style)
+        }
+        Patch::SetText { path, text } => {
+            format!("text of the node at {:?} is set to {:?}", path, text)
+        }
+        Patch::InsertChild { path, index } => {
+            format!("child {} is inserted into the element at {:?}", index, path)
+        }
+        Patch::RemoveChild { path, index } => {
+            format!("child {} is removed from the element at {:?}", index, path)
+        }
+        Patch::MoveChild { path, from, to } => format!(
+            "child {} of the element at {:?} is moved to {}",
+            from, path, to
+        ),
+    }
+}
+
+/// The path of the first element whose listeners differ; the elements have no patches.
+fn first_listener_difference<Ms>(
+    old: &El<Ms>,
+    new: &El<Ms>,
+    path: &mut diff::NodePath,
+) -> Option<diff::NodePath> {
+    if old.listeners != new.listeners {
+        return Some(path.clone());
+    }
+    first_listener_difference_in_children(&old.children, &new.children, path)
+}
+
+fn first_listener_difference_in_children<Ms>(
+    old: &[Node<Ms>],
+    new: &[Node<Ms>],
+    path: &mut diff::NodePath,
+) -> Option<diff::NodePath> {
+    for (index, (old_child, new_child)) in old.iter().zip(new).enumerate() {
+        path.push(index);
+        let difference = match (old_child, new_child) {
+            (Node::Element(old_el), Node::Element(new_el)) => {
+                first_listener_difference(old_el, new_el, path)
+            }
+            (
+                Node::Portal {
+                    children: old_children,
+                    ..
+                },
+                Node::Portal {
+                    children: new_children,
+                    ..
+                },
+            ) => first_listener_difference_in_children(old_children, new_children, path),
+            _ => None,
+        };
+        path.pop();
+        if difference.is_some() {
+            return difference;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        browser::dom::event_handler::simple_ev,
+        virtual_dom::{Ev, Tag},
+    };
+    use wasm_bindgen_test::*;
+
+    #[derive(Clone, Debug)]
+    enum Msg {
+        Clicked,
+        Hovered,
+    }
+
+    fn view(title: &str, msg: Msg) -> El<Msg> {
+        let mut button = El::empty(Tag::Button);
+        button
+            .add_attr("title", title)
+            .add_listener(simple_ev(Ev::Click, msg));
+        let mut el = El::empty(Tag::Div);
+        el.add_child(Node::Element(button));
+        el
+    }
+
+    #[wasm_bindgen_test]
+    fn same_views() {
+        assert_no_diff(&view("a", Msg::Clicked), &view("a", Msg::Clicked));
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic(expected = "The elements differ (1 patches); the first difference: \
+                    attribute `title` of the element at [0] is set to Some(\"b\")")]
+    fn changed_attr() {
+        assert_no_diff(&view("a", Msg::Clicked), &view("b", Msg::Clicked));
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic(expected = "Listeners of the element at [0] differ")]
+    fn changed_listener() {
+        let mut new = view("a", Msg::Hovered);
+        if let Node::Element(button) = &mut new.children[0] {
+            button.listeners = vec![simple_ev(Ev::MouseOver, Msg::Hovered)];
+        }
+        assert_no_diff(&view("a", Msg::Clicked), &new);
+    }
+}
@@ -1,44 +1,76 @@
 pub mod attrs;
+pub mod debug_tree;
+pub mod diff;
+pub mod html;
+pub mod json;
+pub mod links;
 pub mod listener;
 pub mod mailbox;
+pub mod markdown;
 pub mod node;
 pub mod patch;
+pub mod props;
+pub mod snapshot;
 pub mod style;
+pub mod template;
+pub mod transition_group;
 pub mod update_el;
+pub mod validate;
 pub mod values;
 pub mod view;
+pub mod virtual_window;
 
 pub use attrs::Attrs;
-pub use listener::{Category, Listener};
+pub use diff::Patch;
+pub use json::FromJsonError;
+pub use links::LinkOptions;
+pub use listener::{Category, Listener, ListenerOptions};
 pub use mailbox::Mailbox;
-pub use node::{El, Node, Text};
-pub use style::Style;
+pub use markdown::MarkdownOptions;
+pub use node::{intersperse, keyed_list, El, Node, Text};
+pub use props::Props;
+pub use style::{Style, Theme};
+pub use template::Template;
+pub use transition_group::{transition_group, TransitionGroup};
 pub use update_el::UpdateEl;
-pub use values::{AsAtValue, AtValue, CSSValue};
+pub use validate::ValidationWarning;
+pub use values::{
+    AsAtValue, AtValue, AutoCapitalize, Autocomplete, CSSValue, CSSValuePart, Decoding,
+    DialogState, Dir, Easing, EnterKeyHint, FormMethod, InputMode, Politeness, PopoverMode, Role,
+};
 pub use view::View;
+pub use virtual_window::{virtual_window, visible_range};
 
 pub use crate::dom_entity_names::{At, Ev, St, Tag};
 
 #[cfg(test)]
 pub mod tests {
     use futures::future;
-    use std::{cell::RefCell, rc::Rc};
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
     use wasm_bindgen::JsCast;
     use wasm_bindgen_test::*;
     use web_sys::{self, Element};
 
     use crate as seed;
     use crate::{
-        browser::{dom::virtual_dom_bridge, util},
+        browser::{
+            dom::{observers, virtual_dom_bridge},
+            util,
+        },
         class,
         prelude::*,
-        virtual_dom::{mailbox::Mailbox, patch},
+        virtual_dom::{mailbox::Mailbox, patch, Style},
     };
 
     wasm_bindgen_test_configure!(run_in_browser);
 
     #[derive(Clone, Debug)]
-    enum Msg {}
+    enum Msg {
+        Clicked,
+    }
 
     struct Model {}
 
@@ -80,7 +112,7 @@ pub mod tests {
         let mut vdom = Node::Element(El::empty(Tag::Div));
         virtual_dom_bridge::assign_ws_nodes(&doc, &mut vdom);
         // clone so we can keep using it after vdom is modified
-        if let Node::Element(vdom_el) = vdom.clone() {
+        if let Node::Element(vdom_el) = &vdom {
             let old_ws = vdom_el.node_ws.as_ref().unwrap().clone();
             parent.append_child(&old_ws).unwrap();
 
@@ -145,7 +177,7 @@ pub mod tests {
         let mut vdom = Node::Element(El::empty(Tag::Div));
         virtual_dom_bridge::assign_ws_nodes(&doc, &mut vdom);
         // clone so we can keep using it after vdom is modified
-        if let Node::Element(vdom_el) = vdom.clone() {
+        if let Node::Element(vdom_el) = &vdom {
             let old_ws = vdom_el.node_ws.as_ref().unwrap().clone();
             parent.append_child(&old_ws).unwrap();
 
@@ -184,7 +216,7 @@ pub mod tests {
         let mut vdom = Node::Element(El::empty(Tag::Div));
         virtual_dom_bridge::assign_ws_nodes(&doc, &mut vdom);
         // clone so we can keep using it after vdom is modified
-        if let Node::Element(el) = vdom.clone() {
+        if let Node::Element(el) = &vdom {
             let old_ws = el.node_ws.as_ref().unwrap().clone();
             parent.append_child(&old_ws).unwrap();
 
@@ -245,7 +277,7 @@ pub mod tests {
         let mut vdom = div![];
         virtual_dom_bridge::assign_ws_nodes(&doc, &mut vdom);
         // clone so we can keep using it after vdom is modified
-        if let Node::Element(el) = vdom.clone() {
+        if let Node::Element(el) = &vdom {
             let old_ws = el.node_ws.as_ref().unwrap().clone();
             parent.append_child(&old_ws).unwrap();
 
@@ -261,6 +293,43 @@ pub mod tests {
         }
     }
 
+    #[wasm_bindgen_test]
+    fn swapped_children_rerendered() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+
+        let mut vdom = div![];
+        virtual_dom_bridge::assign_ws_nodes(&doc, &mut vdom);
+        if let Node::Element(el) = &vdom {
+            let old_ws = el.node_ws.as_ref().unwrap().clone();
+            parent.append_child(&old_ws).unwrap();
+
+            vdom = call_patch(
+                &doc,
+                &parent,
+                &mailbox,
+                vdom,
+                div![li!["1"], "2", li!["3"]],
+                &app,
+            );
+            let html_result = old_ws.clone().dyn_into::<Element>().unwrap().inner_html();
+            assert_eq!(html_result, "<li>1</li>2<li>3</li>");
+
+            let mut new_vdom = div![li!["1"], "2", li!["3"]];
+            if let Node::Element(new_el) = &mut new_vdom {
+                new_el.swap_children(0, 2).swap_children(1, 2);
+            }
+            call_patch(&doc, &parent, &mailbox, vdom, new_vdom, &app);
+            let html_result = old_ws.dyn_into::<Element>().unwrap().inner_html();
+            assert_eq!(html_result, "<li>3</li><li>1</li>2");
+        } else {
+            panic!("Node not Element")
+        }
+    }
+
     /// Test if attribute `disabled` is correctly added and then removed.
     #[wasm_bindgen_test]
     fn attr_disabled() {
@@ -273,7 +342,7 @@ pub mod tests {
         let mut vdom = Node::Element(El::empty(Tag::Div));
         virtual_dom_bridge::assign_ws_nodes(&doc, &mut vdom);
         // clone so we can keep using it after vdom is modified
-        if let Node::Element(vdom_el) = vdom.clone() {
+        if let Node::Element(vdom_el) = &vdom {
             let old_ws = vdom_el.node_ws.as_ref().unwrap().clone();
             parent.append_child(&old_ws).unwrap();
 
@@ -355,7 +424,7 @@ pub mod tests {
         let mut vdom = Node::Element(El::empty(Tag::Div));
         virtual_dom_bridge::assign_ws_nodes(&doc, &mut vdom);
         // clone so we can keep using it after vdom is modified
-        if let Node::Element(vdom_el) = vdom.clone() {
+        if let Node::Element(vdom_el) = &vdom {
             let old_ws = vdom_el.node_ws.as_ref().unwrap().clone();
             parent.append_child(&old_ws).unwrap();
 
@@ -406,7 +475,7 @@ pub mod tests {
 
         let mut vdom = Node::Element(El::empty(Tag::Div));
         virtual_dom_bridge::assign_ws_nodes(&doc, &mut vdom);
-        if let Node::Element(vdom_el) = vdom.clone() {
+        if let Node::Element(vdom_el) = &vdom {
             // clone so we can keep using it after vdom is modified
             let old_ws = vdom_el.node_ws.as_ref().unwrap().clone();
             parent.append_child(&old_ws).unwrap();
@@ -446,166 +515,1219 @@ pub mod tests {
         }
     }
 
-    /// Test that if the old_el passed to patch was itself an empty, it is correctly patched to a non-empty.
+    /// Test that an optional child rendered as `Node::Empty` is inserted and removed correctly.
     #[wasm_bindgen_test]
-    fn root_empty_changed() {
+    fn optional_child_toggled() {
         let app = create_app();
         let mailbox = Mailbox::new(|_msg: Msg| {});
 
         let doc = util::document();
         let parent = doc.create_element("div").unwrap();
 
-        let mut vdom = seed::empty();
+        let view = |show: bool| {
+            let optional: Option<El<Msg>> = if show {
+                Some(El::empty(Tag::Span))
+            } else {
+                None
+            };
+            div!["a", Node::from(optional), "c"]
+        };
+        let html = |parent: &web_sys::Element| parent.first_element_child().unwrap().inner_html();
+
+        let mut vdom = call_patch(&doc, &parent, &mailbox, seed::empty(), view(false), &app);
+        assert_eq!(html(&parent), "ac");
+
+        vdom = call_patch(&doc, &parent, &mailbox, vdom, view(true), &app);
+        assert_eq!(html(&parent), "a<span></span>c");
+
+        call_patch(&doc, &parent, &mailbox, vdom, view(false), &app);
+        assert_eq!(html(&parent), "ac");
+    }
+
+    /// Test that `innerHTML` is rewritten only when the HTML changes.
+    #[wasm_bindgen_test]
+    fn inner_html_rewritten_only_when_changed() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let article = |html, title| {
+            let mut article = El::empty(Tag::Article);
+            article.set_inner_html(html).add_attr("title", title);
+            div![article]
+        };
+        let content = || {
+            parent
+                .query_selector("article")
+                .unwrap()
+                .unwrap()
+                .first_child()
+                .unwrap()
+        };
+
+        let mut vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            article("<p>Old</p>", "a"),
+            &app,
+        );
+        let old_content = content();
+        assert_eq!(old_content.text_content(), Some("Old".to_string()));
 
+        // The title changes, so the element is patched, but the HTML stays the same.
         vdom = call_patch(
             &doc,
             &parent,
             &mailbox,
             vdom,
-            div!["a", seed::empty(), "c"],
+            article("<p>Old</p>", "b"),
             &app,
         );
-        assert_eq!(parent.children().length(), 1);
-        if let Node::Element(vdom_el) = vdom {
-            let el_ws = vdom_el.node_ws.as_ref().expect("el_ws missing");
-            assert!(el_ws.is_same_node(parent.first_child().as_ref()));
-            assert_eq!(
-                iter_child_nodes(&el_ws)
-                    .map(|node| node.text_content().unwrap())
-                    .collect::<Vec<_>>(),
-                &["a", "c"],
+        assert!(old_content.is_same_node(Some(&content())));
+
+        call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            vdom,
+            article("<p>New</p>", "b"),
+            &app,
+        );
+        assert_eq!(content().text_content(), Some("New".to_string()));
+    }
+
+    /// Test that a bare text node can be mounted without a wrapping element,
+    /// like the app does with a view that returns only text.
+    #[wasm_bindgen_test]
+    fn bare_text_mounted() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let text = |text: &str| View::<Msg>::els(text.to_string()).remove(0);
+
+        let mut vdom = call_patch(&doc, &parent, &mailbox, seed::empty(), text("Hello"), &app);
+        assert_eq!(parent.inner_html(), "Hello");
+        let text_ws = parent.first_child().unwrap();
+
+        vdom = call_patch(&doc, &parent, &mailbox, vdom, text("Ahoj"), &app);
+        assert_eq!(parent.inner_html(), "Ahoj");
+        assert!(text_ws.is_same_node(parent.first_child().as_ref()));
+
+        vdom = call_patch(&doc, &parent, &mailbox, vdom, div!["Hi"], &app);
+        assert_eq!(parent.inner_html(), "<div>Hi</div>");
+
+        call_patch(&doc, &parent, &mailbox, vdom, text("Bye"), &app);
+        assert_eq!(parent.inner_html(), "Bye");
+    }
+
+    /// Test that listener options are applied.
+    #[wasm_bindgen_test]
+    fn listener_with_options() {
+        let app = create_app();
+        let clicks = Rc::new(Cell::new(0));
+        let mailbox = {
+            let clicks = Rc::clone(&clicks);
+            Mailbox::new(move |_msg: Msg| clicks.set(clicks.get() + 1))
+        };
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let view = || {
+            let mut link = El::empty(Tag::A);
+            link.add_attr("href", "#options").on_with_options(
+                Ev::Click,
+                ListenerOptions {
+                    once: true,
+                    prevent_default: true,
+                    ..ListenerOptions::default()
+                },
+                |_| Msg::Clicked,
             );
-        } else {
-            panic!("Node not Element type")
-        }
+            div![link]
+        };
+        let vdom = call_patch(&doc, &parent, &mailbox, seed::empty(), view(), &app);
+        let link_ws = parent.query_selector("a").unwrap().unwrap();
+        let click = || {
+            let mut init = web_sys::EventInit::new();
+            init.cancelable(true);
+            let event = web_sys::Event::new_with_event_init_dict("click", &init).unwrap();
+            link_ws.dispatch_event(&event).unwrap();
+            event.default_prevented()
+        };
+
+        assert!(click());
+        assert_eq!(clicks.get(), 1);
+        assert!(!click());
+        assert_eq!(clicks.get(), 1);
+
+        // The reattached `once` listener isn't called again.
+        call_patch(&doc, &parent, &mailbox, vdom, view(), &app);
+        assert!(!click());
+        assert_eq!(clicks.get(), 1);
     }
 
-    /// Test that an empty->empty transition is handled correctly.
+    /// Test that a debounced message isn't dropped when the element is re-rendered.
+    #[wasm_bindgen_test(async)]
+    async fn debounce_survives_patches() {
+        let app = create_app();
+        let inputs = Rc::new(Cell::new(0));
+        let mailbox = {
+            let inputs = Rc::clone(&inputs);
+            Mailbox::new(move |_msg: Msg| inputs.set(inputs.get() + 1))
+        };
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let view = || {
+            let mut input = El::empty(Tag::Input);
+            input.add_listener(
+                simple_ev(Ev::Input, Msg::Clicked).debounce(std::time::Duration::from_millis(20)),
+            );
+            div![input]
+        };
+        let vdom = call_patch(&doc, &parent, &mailbox, seed::empty(), view(), &app);
+        let input_ws = parent.query_selector("input").unwrap().unwrap();
+        input_ws
+            .dispatch_event(&web_sys::Event::new("input").unwrap())
+            .unwrap();
+
+        let vdom = call_patch(&doc, &parent, &mailbox, vdom, view(), &app);
+        call_patch(&doc, &parent, &mailbox, vdom, view(), &app);
+        assert_eq!(inputs.get(), 0);
+
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        gloo_timers::callback::Timeout::new(50, move || sender.send(()).unwrap()).forget();
+        receiver.await.unwrap();
+        assert_eq!(inputs.get(), 1);
+    }
+
+    /// Test that the button's `disabled` property follows `El::disabled_if`.
     #[wasm_bindgen_test]
-    fn root_empty_to_empty() {
+    fn disabled_if_toggles_property() {
         let app = create_app();
         let mailbox = Mailbox::new(|_msg: Msg| {});
 
         let doc = util::document();
         let parent = doc.create_element("div").unwrap();
+        let button = |disabled| {
+            let mut button = El::empty(Tag::Button);
+            button.disabled_if(disabled).aria_busy(disabled);
+            div![button]
+        };
+        let button_ws = || {
+            parent
+                .query_selector("button")
+                .unwrap()
+                .unwrap()
+                .dyn_into::<web_sys::HtmlButtonElement>()
+                .unwrap()
+        };
 
-        let old = seed::empty();
-        call_patch(&doc, &parent, &mailbox, old, seed::empty(), &app);
-        assert_eq!(parent.children().length(), 0);
+        let mut vdom = call_patch(&doc, &parent, &mailbox, seed::empty(), button(true), &app);
+        assert!(button_ws().disabled());
+        assert_eq!(
+            button_ws().get_attribute("aria-busy"),
+            Some("true".to_string())
+        );
+
+        vdom = call_patch(&doc, &parent, &mailbox, vdom, button(false), &app);
+        assert!(!button_ws().disabled());
+        assert!(!button_ws().has_attribute("disabled"));
+
+        call_patch(&doc, &parent, &mailbox, vdom, button(true), &app);
+        assert!(button_ws().disabled());
     }
 
-    /// Test that a text Node is correctly patched to an Element and vice versa.
+    /// Test that focus and selection move to the recreated input at the same position.
     #[wasm_bindgen_test]
-    fn text_to_element_to_text() {
+    fn focus_restored_in_recreated_input() {
         let app = create_app();
         let mailbox = Mailbox::new(|_msg: Msg| {});
 
         let doc = util::document();
         let parent = doc.create_element("div").unwrap();
+        util::body().append_child(&parent).unwrap();
+        let find_input = || {
+            parent
+                .query_selector("input")
+                .unwrap()
+                .unwrap()
+                .dyn_into::<web_sys::HtmlInputElement>()
+                .unwrap()
+        };
 
-        let mut vdom = seed::empty();
-        vdom = call_patch(&doc, &parent, &mailbox, vdom, Node::new_text("abc"), &app);
-        assert_eq!(parent.child_nodes().length(), 1);
-        let text = parent
-            .first_child()
-            .unwrap()
-            .dyn_ref::<web_sys::Text>()
-            .expect("not a Text node")
-            .clone();
-        assert_eq!(text.text_content().unwrap(), "abc");
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            div![input![attrs! {At::Value => "hello"}]],
+            &app,
+        );
+        let old_input = find_input();
+        old_input.focus().unwrap();
+        old_input.set_selection_range(1, 3).unwrap();
 
-        // change to a span (that contains a text node and styling).
-        // span was specifically chosen here because text Els are saved with the span tag.
-        // (or at least they were when the test was written.)
-        vdom = call_patch(
+        let focus = virtual_dom_bridge::capture_focus(&parent).expect("focus not captured");
+        call_patch(
             &doc,
             &parent,
             &mailbox,
             vdom,
-            span![style!["color" => "red"], "def"],
+            section![input![attrs! {At::Value => "hello"}]],
             &app,
         );
-        assert_eq!(parent.child_nodes().length(), 1);
-        let element = parent
-            .first_child()
-            .unwrap()
-            .dyn_ref::<Element>()
-            .expect("not an Element node")
-            .clone();
-        assert_eq!(&element.tag_name().to_lowercase(), "span");
+        virtual_dom_bridge::restore_focus(&parent, focus);
 
-        // change back to a text node
-        call_patch(&doc, &parent, &mailbox, vdom, Node::new_text("abc"), &app);
-        assert_eq!(parent.child_nodes().length(), 1);
-        let text = parent
-            .first_child()
-            .unwrap()
-            .dyn_ref::<web_sys::Text>()
-            .expect("not a Text node")
-            .clone();
-        assert_eq!(text.text_content().unwrap(), "abc");
+        let new_input = find_input();
+        assert!(!new_input.is_same_node(Some(&old_input)));
+        assert!(doc.active_element().unwrap().is_same_node(Some(&new_input)));
+        assert_eq!(new_input.selection_start(), Ok(Some(1)));
+        assert_eq!(new_input.selection_end(), Ok(Some(3)));
+
+        parent.remove();
     }
 
-    /// Test that the lifecycle hooks are called correctly.
+    /// Test that the scroll position is kept when the scrollable element is replaced.
     #[wasm_bindgen_test]
-    fn lifecycle_hooks() {
+    fn scroll_preserved_in_replaced_element() {
         let app = create_app();
-        use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        util::body().append_child(&parent).unwrap();
+        let panel = |tag: Tag| {
+            let mut panel = El::empty(tag);
+            panel
+                .add_style(St::Height, "50px")
+                .add_style(St::Overflow, "auto")
+                .preserve_scroll();
+            for i in 0..20 {
+                panel.add_child(p![i.to_string()]);
+            }
+            Node::Element(panel)
+        };
+
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            panel(Tag::Div),
+            &app,
+        );
+        parent.first_element_child().unwrap().set_scroll_top(100);
 
+        call_patch(&doc, &parent, &mailbox, vdom, panel(Tag::Section), &app);
+        let section = parent.first_element_child().unwrap();
+        assert_eq!(section.tag_name(), "SECTION");
+        assert_eq!(section.scroll_top(), 100);
+
+        parent.remove();
+    }
+
+    /// Test that `ElRef` contains the node only while the element is in the DOM.
+    #[wasm_bindgen_test]
+    fn el_ref_filled_and_cleared() {
+        let app = create_app();
         let mailbox = Mailbox::new(|_msg: Msg| {});
 
         let doc = util::document();
         let parent = doc.create_element("div").unwrap();
+        let el_ref = ElRef::new();
+        let canvas = || {
+            let mut canvas = El::empty(Tag::Canvas);
+            canvas.el_ref(&el_ref);
+            div![canvas]
+        };
 
-        let mut vdom = seed::empty();
+        let mut vdom = call_patch(&doc, &parent, &mailbox, seed::empty(), canvas(), &app);
+        let canvas_ws = parent.query_selector("canvas").unwrap().unwrap();
+        assert!(el_ref.get().unwrap().is_same_node(Some(&canvas_ws)));
+        assert!(el_ref.get_as::<web_sys::HtmlCanvasElement>().is_some());
+        assert!(el_ref.get_as::<web_sys::HtmlInputElement>().is_none());
 
-        let node_ref: Rc<RefCell<Option<web_sys::Node>>> = Default::default();
-        let mount_op_counter: Rc<AtomicUsize> = Default::default();
-        let update_counter: Rc<AtomicUsize> = Default::default();
+        vdom = call_patch(&doc, &parent, &mailbox, vdom, canvas(), &app);
+        assert!(el_ref.get().unwrap().is_same_node(Some(&canvas_ws)));
 
-        // A real view() function would recreate these closures on each call.
-        // We create the closures once and then clone them, which is hopefully close enough.
-        let did_mount_func = {
-            let node_ref = node_ref.clone();
-            let mount_op_counter = mount_op_counter.clone();
-            move |node: &web_sys::Node| {
-                node_ref.borrow_mut().replace(node.clone());
-                assert_eq!(
-                    mount_op_counter.fetch_add(1, SeqCst),
-                    0,
-                    "did_mount was called more than once"
-                );
-            }
+        call_patch(&doc, &parent, &mailbox, vdom, div![], &app);
+        assert!(el_ref.get().is_none());
+        assert!(el_ref.bounding_rect().is_none());
+    }
+
+    /// Test that the bounding rect is read from the rendered element.
+    #[wasm_bindgen_test]
+    fn bounding_rect_of_rendered_el() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        util::body().append_child(&parent).unwrap();
+        let mut block = El::empty(Tag::Div);
+        block
+            .add_style_px(St::Width, 40.)
+            .add_style_px(St::Height, 20.);
+        assert!(block.bounding_rect().is_none());
+
+        let vdom = call_patch(&doc, &parent, &mailbox, seed::empty(), div![block], &app);
+        let rect = match &vdom {
+            Node::Element(el) => match &el.children[0] {
+                Node::Element(block) => block.bounding_rect().unwrap(),
+                _ => panic!("Missing block"),
+            },
+            _ => panic!("Missing root"),
         };
-        let did_update_func = {
-            let update_counter = update_counter.clone();
-            move |_node: &web_sys::Node| {
-                update_counter.fetch_add(1, SeqCst);
-            }
+        assert_eq!((rect.width(), rect.height()), (40., 20.));
+        parent.remove();
+    }
+
+    /// Test that only clicks outside of the element send the message
+    /// and that the document listener is removed with the element.
+    #[wasm_bindgen_test]
+    fn click_outside_listened() {
+        let app = create_app();
+        let clicks = Rc::new(Cell::new(0));
+        let mailbox = {
+            let clicks = Rc::clone(&clicks);
+            Mailbox::new(move |_msg: Msg| clicks.set(clicks.get() + 1))
         };
-        let will_unmount_func = {
-            let node_ref = node_ref.clone();
-            move |_node: &web_sys::Node| {
-                node_ref.borrow_mut().take();
-                // If the counter wasn't 1, then either:
-                // * did_mount wasn't called - we already check this elsewhere
-                // * did_mount was called more than once - we already check this elsewhere
-                // * will_unmount was called more than once
-                assert_eq!(
-                    mount_op_counter.fetch_add(1, SeqCst),
-                    1,
-                    "will_unmount was called more than once"
-                );
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        util::body().append_child(&parent).unwrap();
+        let menu = || {
+            let mut menu = div![button!["Item"]];
+            if let Node::Element(el) = &mut menu {
+                el.on_click_outside(|| Msg::Clicked);
             }
+            div![menu, span!["Outside"]]
+        };
+        let click = |selector| {
+            parent
+                .query_selector(selector)
+                .unwrap()
+                .unwrap()
+                .unchecked_into::<web_sys::HtmlElement>()
+                .click()
         };
 
-        vdom = call_patch(
+        let vdom = call_patch(&doc, &parent, &mailbox, seed::empty(), menu(), &app);
+        click("button");
+        assert_eq!(clicks.get(), 0);
+        click("span");
+        assert_eq!(clicks.get(), 1);
+
+        let vdom = call_patch(&doc, &parent, &mailbox, vdom, div![span!["Outside"]], &app);
+        click("span");
+        assert_eq!(clicks.get(), 1);
+
+        // The listener is removed with the element's parent, too.
+        let vdom = call_patch(&doc, &parent, &mailbox, vdom, div![section![menu()]], &app);
+        click("span");
+        assert_eq!(clicks.get(), 2);
+        call_patch(&doc, &parent, &mailbox, vdom, div![span!["Outside"]], &app);
+        click("span");
+        assert_eq!(clicks.get(), 2);
+        parent.remove();
+    }
+
+    /// Test that the resize observer is disconnected when the element's parent is removed.
+    #[wasm_bindgen_test]
+    fn resize_observer_removed_with_parent() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let mut observed = El::empty(Tag::Div);
+        observed.on_resize(|_| Msg::Clicked);
+        let vdom = call_patch(
             &doc,
             &parent,
             &mailbox,
-            vdom,
-            div![
-                "a",
+            seed::empty(),
+            div![section![observed]],
+            &app,
+        );
+        let observed_ws = parent.query_selector("section > div").unwrap().unwrap();
+        let key = JsValue::from_str(observers::RESIZE_OBSERVER_KEY);
+        assert!(!js_sys::Reflect::get(&observed_ws, &key)
+            .unwrap()
+            .is_undefined());
+
+        call_patch(&doc, &parent, &mailbox, vdom, div![], &app);
+        assert!(js_sys::Reflect::get(&observed_ws, &key)
+            .unwrap()
+            .is_undefined());
+    }
+
+    /// Test that the intersection observer is disconnected when the element's parent is removed.
+    #[wasm_bindgen_test]
+    fn intersection_observer_removed_with_parent() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let mut observed = El::empty(Tag::Div);
+        observed.on_intersect(observers::IntersectOptions::default(), |_| Msg::Clicked);
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            div![section![observed]],
+            &app,
+        );
+        let observed_ws = parent.query_selector("section > div").unwrap().unwrap();
+        let key = JsValue::from_str(observers::INTERSECTION_OBSERVER_KEY);
+        assert!(!js_sys::Reflect::get(&observed_ws, &key)
+            .unwrap()
+            .is_undefined());
+
+        call_patch(&doc, &parent, &mailbox, vdom, div![], &app);
+        assert!(js_sys::Reflect::get(&observed_ws, &key)
+            .unwrap()
+            .is_undefined());
+    }
+
+    /// Test that the scoped styles' rules are removed when the element's parent is removed.
+    #[wasm_bindgen_test]
+    fn scoped_style_removed_with_parent() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let mut style = Style::empty();
+        style.add(St::Color, "olive");
+        let mut button = El::empty(Tag::Button);
+        button
+            .hover_style(style.clone())
+            .media_style("(min-width: 1px)", style);
+        let classes = button
+            .classes()
+            .iter()
+            .map(|class| class.to_string())
+            .collect::<Vec<_>>();
+        let is_injected = |class: &str| {
+            let rules = doc.head().unwrap().query_selector_all("style").unwrap();
+            (0..rules.length()).any(|index| {
+                rules
+                    .item(index)
+                    .and_then(|rule| rule.text_content())
+                    .is_some_and(|css| css.contains(class))
+            })
+        };
+
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            div![section![button]],
+            &app,
+        );
+        assert!(classes.iter().all(|class| is_injected(class)));
+
+        call_patch(&doc, &parent, &mailbox, vdom, div![], &app);
+        assert!(!classes.iter().any(|class| is_injected(class)));
+    }
+
+    /// Test that keyed elements keep their DOM nodes when they're reordered,
+    /// and that unkeyed siblings are still patched in order.
+    #[wasm_bindgen_test]
+    fn keyed_children_moved() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let list = |ids: &[u32]| {
+            let mut list = El::empty(Tag::Ul);
+            list.add_child(li!["header"]).extend_children(keyed_list(
+                ids,
+                |id| **id,
+                |id| {
+                    let mut item = El::empty(Tag::Li);
+                    item.add_text(id.to_string());
+                    item
+                },
+            ));
+            Node::Element(list)
+        };
+        let items = |parent: &Element| {
+            let items = parent.query_selector_all("li").unwrap();
+            (0..items.length())
+                .map(|index| items.item(index).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            list(&[1, 2, 3]),
+            &app,
+        );
+        let old_items = items(&parent);
+
+        call_patch(&doc, &parent, &mailbox, vdom, list(&[3, 4, 1]), &app);
+        let new_items = items(&parent);
+        assert_eq!(
+            new_items
+                .iter()
+                .map(|item| item.text_content().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["header", "3", "4", "1"]
+        );
+        assert!(new_items[0].is_same_node(Some(&old_items[0])));
+        assert!(new_items[1].is_same_node(Some(&old_items[3])));
+        assert!(new_items[3].is_same_node(Some(&old_items[1])));
+        assert!(!old_items[2].is_connected());
+    }
+
+    /// Test that keyed elements keep their DOM nodes when others are inserted or removed,
+    /// when they're reversed and when they're mixed with unkeyed siblings.
+    #[wasm_bindgen_test]
+    fn keyed_children_inserted_and_removed() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        // `None`s are unkeyed items; their text is `-`.
+        let list = |ids: &[Option<u32>]| {
+            let mut list = El::empty(Tag::Ul);
+            for id in ids {
+                let mut item = El::empty(Tag::Li);
+                match id {
+                    Some(id) => item.key(id).add_text(id.to_string()),
+                    None => item.add_text("-"),
+                };
+                list.add_child(Node::Element(item));
+            }
+            Node::Element(list)
+        };
+        let items = |parent: &Element| {
+            let items = parent.query_selector_all("li").unwrap();
+            (0..items.length())
+                .map(|index| items.item(index).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let texts = |items: &[web_sys::Node]| {
+            items
+                .iter()
+                .map(|item| item.text_content().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            list(&[Some(1), Some(2), Some(3)]),
+            &app,
+        );
+        let original = items(&parent);
+
+        // Insert at the start and in the middle.
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            vdom,
+            list(&[Some(0), Some(1), Some(2), Some(5), Some(3)]),
+            &app,
+        );
+        let inserted = items(&parent);
+        assert_eq!(texts(&inserted), vec!["0", "1", "2", "5", "3"]);
+        assert!(inserted[1].is_same_node(Some(&original[0])));
+        assert!(inserted[2].is_same_node(Some(&original[1])));
+        assert!(inserted[4].is_same_node(Some(&original[2])));
+
+        // Remove from the middle.
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            vdom,
+            list(&[Some(0), Some(2), Some(3)]),
+            &app,
+        );
+        let removed = items(&parent);
+        assert_eq!(texts(&removed), vec!["0", "2", "3"]);
+        assert!(removed[0].is_same_node(Some(&inserted[0])));
+        assert!(removed[1].is_same_node(Some(&inserted[2])));
+        assert!(removed[2].is_same_node(Some(&inserted[4])));
+        assert!(!inserted[1].is_connected());
+        assert!(!inserted[3].is_connected());
+
+        // Reverse.
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            vdom,
+            list(&[Some(3), Some(2), Some(0)]),
+            &app,
+        );
+        let reversed = items(&parent);
+        assert_eq!(texts(&reversed), vec!["3", "2", "0"]);
+        assert!(reversed[0].is_same_node(Some(&removed[2])));
+        assert!(reversed[2].is_same_node(Some(&removed[0])));
+
+        // Mix with unkeyed items; they're patched in order.
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            vdom,
+            list(&[None, Some(2), None, Some(3)]),
+            &app,
+        );
+        let mixed = items(&parent);
+        assert_eq!(texts(&mixed), vec!["-", "2", "-", "3"]);
+        assert!(mixed[1].is_same_node(Some(&reversed[1])));
+        assert!(mixed[3].is_same_node(Some(&reversed[0])));
+        assert!(!reversed[2].is_connected());
+
+        call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            vdom,
+            list(&[Some(3), None, Some(2)]),
+            &app,
+        );
+        let remixed = items(&parent);
+        assert_eq!(texts(&remixed), vec!["3", "-", "2"]);
+        assert!(remixed[0].is_same_node(Some(&mixed[3])));
+        assert!(remixed[1].is_same_node(Some(&mixed[0])));
+        assert!(remixed[2].is_same_node(Some(&mixed[1])));
+        assert!(!mixed[2].is_connected());
+    }
+
+    /// Test that the nodes projected into a rendered slot are read.
+    #[wasm_bindgen_test]
+    fn slot_assigned_nodes() {
+        let doc = util::document();
+        let host = doc.create_element("div").unwrap();
+        host.set_inner_html("<span>Projected</span>");
+        util::body().append_child(&host).unwrap();
+
+        let mut slot: Node<Msg> = Node::Element(El::empty(Tag::Slot));
+        virtual_dom_bridge::assign_ws_nodes(&doc, &mut slot);
+        let slot = match slot {
+            Node::Element(slot) => slot,
+            _ => unreachable!(),
+        };
+        assert!(slot.assigned_nodes().is_empty());
+
+        let shadow_root = host
+            .attach_shadow(&web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open))
+            .unwrap();
+        shadow_root
+            .append_child(slot.node_ws.as_ref().unwrap())
+            .unwrap();
+        let assigned = slot.assigned_nodes();
+        assert_eq!(assigned.len(), 1);
+        assert_eq!(assigned[0].text_content(), Some("Projected".into()));
+        host.remove();
+    }
+
+    /// Test that the popover attributes are created and patched as DOM attributes.
+    #[wasm_bindgen_test]
+    fn popover_attrs_patched() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let menu = |mode: Option<PopoverMode>| {
+            let mut button = El::empty(Tag::Button);
+            let mut menu = El::empty(Tag::Div);
+            menu.add_attr("id", "menu");
+            if let Some(mode) = mode {
+                button.popover_target("menu");
+                menu.popover(mode);
+            }
+            let mut wrapper = El::empty(Tag::Div);
+            wrapper
+                .add_child(Node::Element(button))
+                .add_child(Node::Element(menu));
+            Node::Element(wrapper)
+        };
+        let attr = |selector: &str, name: &str| {
+            parent
+                .query_selector(selector)
+                .unwrap()
+                .unwrap()
+                .get_attribute(name)
+        };
+
+        let mut vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            menu(Some(PopoverMode::Auto)),
+            &app,
+        );
+        assert_eq!(attr("button", "popovertarget"), Some("menu".into()));
+        assert_eq!(attr("#menu", "popover"), Some("auto".into()));
+
+        vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            vdom,
+            menu(Some(PopoverMode::Manual)),
+            &app,
+        );
+        assert_eq!(attr("#menu", "popover"), Some("manual".into()));
+
+        call_patch(&doc, &parent, &mailbox, vdom, menu(None), &app);
+        assert_eq!(attr("button", "popovertarget"), None);
+        assert_eq!(attr("#menu", "popover"), None);
+    }
+
+    /// Test that an `auto_memo` subtree isn't patched when its content doesn't change.
+    #[wasm_bindgen_test]
+    fn auto_memo_skips_unchanged() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let article = |text: &str| {
+            let mut paragraph = El::empty(Tag::P);
+            paragraph.add_text(text.to_owned());
+            let mut article = El::empty(Tag::Article);
+            article.add_child(Node::Element(paragraph)).auto_memo();
+            Node::Element(article)
+        };
+        let paragraph = || parent.query_selector("p").unwrap().unwrap();
+
+        let mut vdom = call_patch(&doc, &parent, &mailbox, seed::empty(), article("a"), &app);
+        // The hash of the rendered content is stored by the first patch.
+        vdom = call_patch(&doc, &parent, &mailbox, vdom, article("a"), &app);
+        // Changes made outside of the vdom show whether it was diffed.
+        paragraph().set_text_content(Some("outside"));
+
+        vdom = call_patch(&doc, &parent, &mailbox, vdom, article("a"), &app);
+        assert_eq!(paragraph().text_content(), Some("outside".into()));
+        if let Node::Element(article) = &vdom {
+            assert!(article.node_ws.is_some());
+            assert_eq!(article.memo_hash(), Some(article.content_hash()));
+        }
+
+        call_patch(&doc, &parent, &mailbox, vdom, article("b"), &app);
+        assert_eq!(paragraph().text_content(), Some("b".into()));
+    }
+
+    /// Test that `auto_memo` custom elements are still re-rendered (see Issue #325).
+    #[wasm_bindgen_test]
+    fn auto_memo_ignored_for_custom_elements() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let custom = || {
+            let mut el = El::empty(Tag::Custom("x-memo".into()));
+            el.add_text("a").auto_memo();
+            Node::Element(el)
+        };
+        let rendered = || parent.first_child().unwrap();
+
+        let mut vdom = call_patch(&doc, &parent, &mailbox, seed::empty(), custom(), &app);
+        let first = rendered();
+        vdom = call_patch(&doc, &parent, &mailbox, vdom, custom(), &app);
+        let second = rendered();
+        assert!(!first.is_same_node(Some(&second)));
+
+        call_patch(&doc, &parent, &mailbox, vdom, custom(), &app);
+        assert!(!second.is_same_node(Some(&rendered())));
+    }
+
+    /// Test that the moved items of a transition group get the move class.
+    #[wasm_bindgen_test]
+    fn transition_group_moves() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        util::body().append_child(&parent).unwrap();
+        let config = TransitionGroup {
+            move_class: Some("moving".into()),
+            move_duration: std::time::Duration::from_millis(300),
+            ..TransitionGroup::default()
+        };
+        let list = |ids: &[u32]| {
+            let mut list = El::empty(Tag::Ul);
+            list.extend_children(transition_group(
+                keyed_list(
+                    ids,
+                    |id| **id,
+                    |id| {
+                        let mut item = El::empty(Tag::Li);
+                        item.add_text(id.to_string());
+                        item
+                    },
+                ),
+                &config,
+            ));
+            Node::Element(list)
+        };
+        let item = |position: usize| {
+            parent
+                .query_selector(&format!("li:nth-child({})", position))
+                .unwrap()
+                .unwrap()
+        };
+
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            list(&[1, 2, 3]),
+            &app,
+        );
+        assert!(!item(1).class_list().contains("moving"));
+
+        call_patch(&doc, &parent, &mailbox, vdom, list(&[1, 3, 2]), &app);
+        assert_eq!(item(2).text_content(), Some("3".into()));
+        assert!(!item(1).class_list().contains("moving"));
+        assert!(item(2).class_list().contains("moving"));
+        assert!(item(3).class_list().contains("moving"));
+        parent.remove();
+    }
+
+    /// Test that the imported DOM nodes are reused when they're patched.
+    #[wasm_bindgen_test]
+    fn dom_node_adopted() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        parent.set_inner_html(r#"<p id="intro">Hello, <b>server</b></p>"#);
+        let p_ws = parent.first_child().unwrap();
+        let b_ws = p_ws.last_child().unwrap();
+
+        let adopted = El::from_dom_node(&p_ws).unwrap();
+        assert!(adopted.node_ws.as_ref().unwrap().is_same_node(Some(&p_ws)));
+        assert_eq!(
+            adopted.children[0].text().unwrap().node_ws.as_ref(),
+            p_ws.first_child().as_ref()
+        );
+        assert!(El::<Msg>::from_dom_node(&b_ws.first_child().unwrap()).is_none());
+
+        call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            Node::Element(adopted),
+            p![id!("intro"), "Hello, ", b!["client"]],
+            &app,
+        );
+        assert!(parent.first_child().unwrap().is_same_node(Some(&p_ws)));
+        assert!(p_ws.last_child().unwrap().is_same_node(Some(&b_ws)));
+        assert_eq!(
+            parent.inner_html(),
+            r#"<p id="intro">Hello, <b>client</b></p>"#
+        );
+    }
+
+    /// Test that a hidden element keeps its DOM node.
+    #[wasm_bindgen_test]
+    fn hidden_el_kept() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let panel = |visible| {
+            let mut panel = El::empty(Tag::Div);
+            panel.add_child(input![]).set_visible(visible);
+            div![panel]
+        };
+        let panel_ws = |parent: &Element| {
+            parent
+                .query_selector(":scope > div > div")
+                .unwrap()
+                .unwrap()
+        };
+
+        let vdom = call_patch(&doc, &parent, &mailbox, seed::empty(), panel(true), &app);
+        let old_ws = panel_ws(&parent);
+        assert!(!old_ws.has_attribute("hidden"));
+
+        let vdom = call_patch(&doc, &parent, &mailbox, vdom, panel(false), &app);
+        assert!(panel_ws(&parent).is_same_node(Some(&old_ws)));
+        assert!(old_ws.has_attribute("hidden"));
+
+        call_patch(&doc, &parent, &mailbox, vdom, panel(true), &app);
+        assert!(panel_ws(&parent).is_same_node(Some(&old_ws)));
+        assert!(!old_ws.has_attribute("hidden"));
+    }
+
+    /// Test that submitting the form sends its field values and doesn't reload the page.
+    #[wasm_bindgen_test]
+    fn form_values_submitted() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+        let values = Rc::new(RefCell::new(None));
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        util::body().append_child(&parent).unwrap();
+        let mut form = form![
+            input![attrs! {At::Name => "title"; At::Value => "Seed"}],
+            input![
+                attrs! {At::Name => "tag"; At::Type => "checkbox"; At::Checked => true.as_at_value(); At::Value => "rust"}
+            ],
+            input![
+                attrs! {At::Name => "tag"; At::Type => "checkbox"; At::Checked => true.as_at_value(); At::Value => "wasm"}
+            ],
+            input![attrs! {At::Name => "tag"; At::Type => "checkbox"; At::Value => "js"}],
+            input![attrs! {At::Value => "unnamed"}],
+        ];
+        if let Node::Element(el) = &mut form {
+            let values = Rc::clone(&values);
+            el.on_submit_form(move |form_values| {
+                values.replace(Some(form_values));
+                Msg::Clicked
+            });
+        }
+        call_patch(&doc, &parent, &mailbox, seed::empty(), form, &app);
+
+        let mut event_init = web_sys::EventInit::new();
+        event_init.cancelable(true);
+        let event = web_sys::Event::new_with_event_init_dict("submit", &event_init).unwrap();
+        parent
+            .first_child()
+            .unwrap()
+            .dispatch_event(&event)
+            .unwrap();
+
+        assert!(event.default_prevented());
+        let mut expected = std::collections::HashMap::new();
+        expected.insert("title".to_string(), "Seed".to_string());
+        expected.insert("tag".to_string(), "rust,wasm".to_string());
+        assert_eq!(values.borrow_mut().take(), Some(expected));
+        parent.remove();
+    }
+
+    /// Test that the dialog's methods are called on each change of its state.
+    #[wasm_bindgen_test]
+    fn dialog_state_synced() {
+        use DialogState::{Closed, Modal, Open};
+
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        util::body().append_child(&parent).unwrap();
+        let dialog = |state| Node::Element(El::dialog(state));
+        let dialog_state = |parent: &Element| {
+            let dialog = parent
+                .first_element_child()
+                .unwrap()
+                .dyn_into::<web_sys::HtmlDialogElement>()
+                .unwrap();
+            match (dialog.open(), dialog.matches(":modal").unwrap()) {
+                (false, _) => Closed,
+                (true, false) => Open,
+                (true, true) => Modal,
+            }
+        };
+
+        let mut vdom = call_patch(&doc, &parent, &mailbox, seed::empty(), dialog(Modal), &app);
+        assert_eq!(dialog_state(&parent), Modal);
+
+        // Each transition between the states.
+        for state in [Closed, Open, Modal, Open, Closed, Modal, Modal] {
+            vdom = call_patch(&doc, &parent, &mailbox, vdom, dialog(state), &app);
+            assert_eq!(dialog_state(&parent), state);
+        }
+
+        parent.remove();
+    }
+
+    /// Test that if the old_el passed to patch was itself an empty, it is correctly patched to a non-empty.
+    #[wasm_bindgen_test]
+    fn root_empty_changed() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+
+        let mut vdom = seed::empty();
+
+        vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            vdom,
+            div!["a", seed::empty(), "c"],
+            &app,
+        );
+        assert_eq!(parent.children().length(), 1);
+        if let Node::Element(vdom_el) = vdom {
+            let el_ws = vdom_el.node_ws.as_ref().expect("el_ws missing");
+            assert!(el_ws.is_same_node(parent.first_child().as_ref()));
+            assert_eq!(
+                iter_child_nodes(&el_ws)
+                    .map(|node| node.text_content().unwrap())
+                    .collect::<Vec<_>>(),
+                &["a", "c"],
+            );
+        } else {
+            panic!("Node not Element type")
+        }
+    }
+
+    /// Test that an empty->empty transition is handled correctly.
+    #[wasm_bindgen_test]
+    fn root_empty_to_empty() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+
+        let old = seed::empty();
+        call_patch(&doc, &parent, &mailbox, old, seed::empty(), &app);
+        assert_eq!(parent.children().length(), 0);
+    }
+
+    /// Test that a text Node is correctly patched to an Element and vice versa.
+    #[wasm_bindgen_test]
+    fn text_to_element_to_text() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+
+        let mut vdom = seed::empty();
+        vdom = call_patch(&doc, &parent, &mailbox, vdom, Node::new_text("abc"), &app);
+        assert_eq!(parent.child_nodes().length(), 1);
+        let text = parent
+            .first_child()
+            .unwrap()
+            .dyn_ref::<web_sys::Text>()
+            .expect("not a Text node")
+            .clone();
+        assert_eq!(text.text_content().unwrap(), "abc");
+
+        // change to a span (that contains a text node and styling).
+        // span was specifically chosen here because text Els are saved with the span tag.
+        // (or at least they were when the test was written.)
+        vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            vdom,
+            span![style!["color" => "red"], "def"],
+            &app,
+        );
+        assert_eq!(parent.child_nodes().length(), 1);
+        let element = parent
+            .first_child()
+            .unwrap()
+            .dyn_ref::<Element>()
+            .expect("not an Element node")
+            .clone();
+        assert_eq!(&element.tag_name().to_lowercase(), "span");
+
+        // change back to a text node
+        call_patch(&doc, &parent, &mailbox, vdom, Node::new_text("abc"), &app);
+        assert_eq!(parent.child_nodes().length(), 1);
+        let text = parent
+            .first_child()
+            .unwrap()
+            .dyn_ref::<web_sys::Text>()
+            .expect("not a Text node")
+            .clone();
+        assert_eq!(text.text_content().unwrap(), "abc");
+    }
+
+    /// Test that the lifecycle hooks are called correctly.
+    #[wasm_bindgen_test]
+    fn lifecycle_hooks() {
+        let app = create_app();
+        use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+
+        let mut vdom = seed::empty();
+
+        let node_ref: Rc<RefCell<Option<web_sys::Node>>> = Default::default();
+        let mount_op_counter: Rc<AtomicUsize> = Default::default();
+        let update_counter: Rc<AtomicUsize> = Default::default();
+
+        // A real view() function would recreate these closures on each call.
+        // We create the closures once and then clone them, which is hopefully close enough.
+        let did_mount_func = {
+            let node_ref = node_ref.clone();
+            let mount_op_counter = mount_op_counter.clone();
+            move |node: &web_sys::Node| {
+                node_ref.borrow_mut().replace(node.clone());
+                assert_eq!(
+                    mount_op_counter.fetch_add(1, SeqCst),
+                    0,
+                    "did_mount was called more than once"
+                );
+            }
+        };
+        let did_update_func = {
+            let update_counter = update_counter.clone();
+            move |_node: &web_sys::Node| {
+                update_counter.fetch_add(1, SeqCst);
+            }
+        };
+        let will_unmount_func = {
+            let node_ref = node_ref.clone();
+            move |_node: &web_sys::Node| {
+                node_ref.borrow_mut().take();
+                // If the counter wasn't 1, then either:
+                // * did_mount wasn't called - we already check this elsewhere
+                // * did_mount was called more than once - we already check this elsewhere
+                // * will_unmount was called more than once
+                assert_eq!(
+                    mount_op_counter.fetch_add(1, SeqCst),
+                    1,
+                    "will_unmount was called more than once"
+                );
+            }
+        };
+
+        vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            vdom,
+            div![
+                "a",
                 did_mount(did_mount_func.clone()),
                 did_update(did_update_func.clone()),
                 will_unmount(will_unmount_func.clone()),
@@ -662,6 +1784,209 @@ pub mod tests {
         assert!(node_ref.borrow().is_none(), "will_unmount wasn't called");
     }
 
+    #[wasm_bindgen_test]
+    fn replaced_nodes_run_hooks() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+
+        let inserted: Rc<RefCell<Vec<String>>> = Default::default();
+        let removed: Rc<RefCell<Vec<String>>> = Default::default();
+        let hooked_el = |tag: Tag| {
+            let mut el = El::empty(tag);
+            let inserted = inserted.clone();
+            let removed = removed.clone();
+            el.on_insert(move |node| {
+                inserted.borrow_mut().push(node.node_name());
+            })
+            .on_remove(move |node| {
+                removed.borrow_mut().push(node.node_name());
+            });
+            Node::Element(el)
+        };
+
+        let mut vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            hooked_el(Tag::Div),
+            &app,
+        );
+        assert_eq!(*inserted.borrow(), ["DIV"]);
+
+        // A different tag replaces the DOM node.
+        let mut new_vdom = vdom.clone();
+        new_vdom.replace_with(hooked_el(Tag::P));
+        vdom = call_patch(&doc, &parent, &mailbox, vdom, new_vdom, &app);
+        assert_eq!(parent.inner_html(), "<p></p>");
+        assert_eq!(*inserted.borrow(), ["DIV", "P"]);
+        assert_eq!(*removed.borrow(), ["DIV"]);
+
+        // So does a different node type.
+        let mut new_vdom = vdom.clone();
+        let old_vdom = new_vdom.replace_with(Node::new_text("text"));
+        assert!(old_vdom.is_el());
+        call_patch(&doc, &parent, &mailbox, vdom, new_vdom, &app);
+        assert_eq!(parent.inner_html(), "text");
+        assert_eq!(*inserted.borrow(), ["DIV", "P"]);
+        assert_eq!(*removed.borrow(), ["DIV", "P"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn removed_style_disappears() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+
+        let styled = || {
+            let mut el = El::empty(Tag::Div);
+            el.add_style(St::Color, "red")
+                .add_style(St::Display, "flex");
+            el
+        };
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            Node::Element(styled()),
+            &app,
+        );
+
+        let mut new_el = styled();
+        assert_eq!(
+            new_el.remove_style(St::Color),
+            Some(CSSValue::Some("red".into()))
+        );
+        let vdom = call_patch(&doc, &parent, &mailbox, vdom, Node::Element(new_el), &app);
+        let el_ws = parent.first_element_child().unwrap();
+        assert_eq!(el_ws.get_attribute("style"), Some("display:flex".into()));
+
+        let mut new_el = styled();
+        new_el.clear_style();
+        call_patch(&doc, &parent, &mailbox, vdom, Node::Element(new_el), &app);
+        assert_eq!(el_ws.get_attribute("style"), Some("".into()));
+    }
+
+    #[wasm_bindgen_test]
+    fn portal_rendered_into_target() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let target = doc.create_element("div").unwrap();
+        target.set_id("portal-target");
+        util::body().append_child(&target).unwrap();
+
+        let portal = |text: &'static str| Node::new_portal("#portal-target", vec![p![text]]);
+
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            div![portal("modal")],
+            &app,
+        );
+        assert_eq!(parent.inner_html(), "<div></div>");
+        assert_eq!(target.inner_html(), "<p>modal</p>");
+
+        let vdom = call_patch(&doc, &parent, &mailbox, vdom, div![portal("changed")], &app);
+        assert_eq!(target.inner_html(), "<p>changed</p>");
+
+        // Removing the portal's parent removes the portal's children from the target.
+        call_patch(&doc, &parent, &mailbox, vdom, seed::empty(), &app);
+        assert_eq!(parent.inner_html(), "");
+        assert_eq!(target.inner_html(), "");
+
+        util::body().remove_child(&target).unwrap();
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn leave_animation_delays_removal() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+
+        let mut leaving = El::empty(Tag::Li);
+        leaving.animate_leave("leaving", std::time::Duration::from_millis(10));
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            ul![Node::Element(leaving)],
+            &app,
+        );
+        call_patch(&doc, &parent, &mailbox, vdom, ul![], &app);
+        assert_eq!(parent.inner_html(), r#"<ul><li class="leaving"></li></ul>"#);
+
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        gloo_timers::callback::Timeout::new(50, move || sender.send(()).unwrap()).forget();
+        receiver.await.unwrap();
+        assert_eq!(parent.inner_html(), "<ul></ul>");
+    }
+
+    /// Test that patching skips the nodes running their leave animations
+    /// and that the nodes are removed when their animations end.
+    #[wasm_bindgen_test]
+    fn leaving_nodes_skipped_and_removed_on_animation_end() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        let item = |key: &str, leaves: bool| {
+            let mut item = El::empty(Tag::Li);
+            item.key(key).add_text(key.to_owned());
+            if leaves {
+                item.animate_leave("leaving", std::time::Duration::from_secs(10));
+            }
+            Node::Element(item)
+        };
+
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            ul![item("a", true), item("b", false)],
+            &app,
+        );
+        let vdom = call_patch(&doc, &parent, &mailbox, vdom, ul![item("b", false)], &app);
+        let vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            vdom,
+            ul![item("b", false), item("c", false)],
+            &app,
+        );
+        assert_eq!(
+            parent.inner_html(),
+            r#"<ul><li>b</li><li class="leaving">a</li><li>c</li></ul>"#
+        );
+
+        parent
+            .query_selector(".leaving")
+            .unwrap()
+            .unwrap()
+            .dispatch_event(&web_sys::Event::new("animationend").unwrap())
+            .unwrap();
+        assert_eq!(parent.inner_html(), "<ul><li>b</li><li>c</li></ul>");
+
+        call_patch(&doc, &parent, &mailbox, vdom, ul![item("c", false)], &app);
+        assert_eq!(parent.inner_html(), "<ul><li>c</li></ul>");
+    }
+
     /// Tests an update() function that repeatedly sends messages or performs commands.
     #[wasm_bindgen_test(async)]
     async fn update_promises() {
@@ -0,0 +1,129 @@
+//! This module contains a compact text outline of vdom trees; see `El::debug_tree`.
+
+use super::{At, AtValue, El, Node};
+
+/// Maximum number of characters of text nodes shown in the tree.
+const MAX_TEXT_LEN: usize = 30;
+
+/// Outline the element and its descendants up to `max_depth` levels below it;
+/// see `El::debug_tree`.
+pub fn el_to_debug_tree<Ms>(el: &El<Ms>, max_depth: usize) -> String {
+    let mut tree = String::new();
+    write_el(el, 0, max_depth, &mut tree);
+    tree
+}
+
+fn write_el<Ms>(el: &El<Ms>, depth: usize, max_depth: usize, tree: &mut String) {
+    push_line(tree, depth, &el_label(el));
+    write_children(&el.children, depth + 1, max_depth, tree);
+}
+
+fn write_children<Ms>(children: &[Node<Ms>], depth: usize, max_depth: usize, tree: &mut String) {
+    let children = children
+        .iter()
+        .filter(|child| !child.is_empty())
+        .collect::<Vec<_>>();
+    if children.is_empty() {
+        return;
+    }
+    if depth > max_depth {
+        push_line(tree, depth, &format!("... ({} more)", children.len()));
+        return;
+    }
+    for child in children {
+        match child {
+            Node::Element(el) => write_el(el, depth, max_depth, tree),
+            Node::Text(text) => push_line(tree, depth, &text_label(&text.text)),
+            Node::Portal { target, children } => {
+                push_line(tree, depth, &format!("portal -> {}", target));
+                write_children(children, depth + 1, max_depth, tree);
+            }
+            Node::Empty => (),
+        }
+    }
+}
+
+fn push_line(tree: &mut String, depth: usize, label: &str) {
+    if !tree.is_empty() {
+        tree.push('\n');
+    }
+    tree.push_str(&"  ".repeat(depth));
+    tree.push_str(label);
+}
+
+/// `tag#id.class1.class2`
+pub(crate) fn el_label<Ms>(el: &El<Ms>) -> String {
+    let mut label = el.tag.name().into_owned();
+    if let Some(AtValue::Some(id)) = el.attrs.vals.get(&At::Id) {
+        label.push('#');
+        label.push_str(id);
+    }
+    for class in el.classes() {
+        label.push('.');
+        label.push_str(class);
+    }
+    label
+}
+
+/// The text in quotes, with whitespace collapsed and shortened to `MAX_TEXT_LEN` characters.
+fn text_label(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() > MAX_TEXT_LEN {
+        let shortened = text.chars().take(MAX_TEXT_LEN).collect::<String>();
+        format!("\"{}...\"", shortened)
+    } else {
+        format!("\"{}\"", text)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::virtual_dom::Tag;
+    use wasm_bindgen_test::*;
+
+    #[derive(Clone, Debug)]
+    enum Msg {}
+
+    fn el(tag: Tag, children: Vec<Node<Msg>>) -> El<Msg> {
+        let mut el = El::empty(tag);
+        el.children = children;
+        el
+    }
+
+    #[wasm_bindgen_test]
+    fn tree() {
+        let mut item = el(
+            Tag::Li,
+            vec![Node::new_text(
+                "A very long item text that gets shortened in the tree",
+            )],
+        );
+        item.add_attr("id", "item-1").add_class("active");
+        let mut root = el(
+            Tag::Div,
+            vec![
+                Node::Element(el(Tag::Ul, vec![Node::Element(item), Node::Empty])),
+                Node::new_text("  Footer\n text "),
+            ],
+        );
+        root.add_class("container");
+
+        assert_eq!(
+            el_to_debug_tree(&root, 10),
+            "div.container\n\
+             \x20 ul\n\
+             \x20   li#item-1.active\n\
+             \x20     \"A very long item text that get...\"\n\
+             \x20 \"Footer text\""
+        );
+        assert_eq!(
+            el_to_debug_tree(&root, 1),
+            "div.container\n\
+             \x20 ul\n\
+             \x20   ... (1 more)\n\
+             \x20 \"Footer text\""
+        );
+        assert_eq!(el_to_debug_tree(&root, 0), "div.container\n  ... (2 more)");
+    }
+}
@@ -0,0 +1,409 @@
+//! This module contains code that compares two vdom trees and describes their differences
+//! as a list of `Patch`es, without touching the DOM.
+//!
+//! Patching the DOM (see module `patch`) applies only the attribute and style patches
+//! (`diff_details`) of the elements it patches; it walks the children itself, because it also
+//! attaches listeners, runs lifecycle hooks and moves keyed children. The children are paired
+//! the same way: by their keys (see `El::key`) when some of them have them, otherwise by their
+//! positions.
+
+use super::{At, AtValue, El, Node, Style};
+use std::collections::HashMap;
+
+/// Indexes of children leading from the diffed root element to a node.
+/// An empty path points to the root element itself.
+pub type NodePath = Vec<usize>;
+
+/// A single operation needed to change the old vdom tree into the new one.
+///
+/// Child indexes refer to the `children` of the vdom elements (or portals),
+/// so `Node::Empty` children are counted too.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Patch {
+    /// The node has to be recreated from the new tree; eg because its tag,
+    /// namespace or node type has changed.
+    ReplaceNode { path: NodePath },
+    /// Set (or remove, if the value is `AtValue::Ignored`) an attribute.
+    SetAttr {
+        path: NodePath,
+        key: At,
+        val: AtValue,
+    },
+    /// Remove an attribute that isn't in the new element.
+    RemoveAttr { path: NodePath, key: At },
+    /// Rewrite the whole `style` attribute.
+    SetStyle { path: NodePath, style: Style },
+    /// Change the content of a text node.
+    SetText { path: NodePath, text: String },
+    /// Insert the new tree's child with the given index into the element.
+    InsertChild { path: NodePath, index: usize },
+    /// Remove the old tree's child with the given index from the element.
+    RemoveChild { path: NodePath, index: usize },
+    /// Move the old tree's child with the index `from` to the index `to` of the new tree,
+    /// because it's now before a child that was before it. Patches inside the moved child
+    /// use its new index in their paths.
+    MoveChild {
+        path: NodePath,
+        from: usize,
+        to: usize,
+    },
+}
+
+/// Compare two elements, including their children.
+pub fn diff_els<Ms>(old: &El<Ms>, new: &El<Ms>) -> Vec<Patch> {
+    let mut patches = Vec::new();
+    diff_el(old, new, &mut Vec::new(), &mut patches);
+    patches
+}
+
+/// Compare attributes and style of two elements with the same tag; children are ignored.
+pub(crate) fn diff_details<Ms>(old: &El<Ms>, new: &El<Ms>, path: &[usize]) -> Vec<Patch> {
+    let mut patches = Vec::new();
+
+    if old.attrs != new.attrs {
+        for (key, new_val) in &new.attrs.vals {
+            if old.attrs.vals.get(key) != Some(new_val) {
+                patches.push(Patch::SetAttr {
+                    path: path.to_vec(),
+                    key: key.clone(),
+                    val: new_val.clone(),
+                });
+            }
+        }
+        for key in old.attrs.vals.keys() {
+            if new.attrs.vals.get(key).is_none() {
+                patches.push(Patch::RemoveAttr {
+                    path: path.to_vec(),
+                    key: key.clone(),
+                });
+            }
+        }
+    }
+
+    // We can't patch each part of style; rewrite the whole attribute.
+    if old.style != new.style {
+        patches.push(Patch::SetStyle {
+            path: path.to_vec(),
+            style: new.style.clone(),
+        });
+    }
+    patches
+}
+
+/// Whether the different `old` element has to be replaced by the `new` one instead of being
+/// patched; shared with `patch::patch_el`.
+///
+/// Namespaces can't be patched, since they involve create_element_ns instead of create_element.
+/// Custom elements can't be patched, because we need to reinit them (Issue #325).
+pub(crate) fn needs_replace<Ms>(old: &El<Ms>, new: &El<Ms>) -> bool {
+    old.tag != new.tag || old.namespace != new.namespace || old.is_custom()
+}
+
+fn diff_el<Ms>(old: &El<Ms>, new: &El<Ms>, path: &mut NodePath, patches: &mut Vec<Patch>) {
+    if old != new && needs_replace(old, new) {
+        patches.push(Patch::ReplaceNode { path: path.clone() });
+        return;
+    }
+    patches.append(&mut diff_details(old, new, path));
+    diff_children(&old.children, &new.children, path, patches);
+}
+
+/// `path` points to the parent of the children; eg an element or a portal.
+fn diff_children<Ms>(
+    old: &[Node<Ms>],
+    new: &[Node<Ms>],
+    path: &mut NodePath,
+    patches: &mut Vec<Patch>,
+) {
+    if old.iter().any(is_keyed) || new.iter().any(is_keyed) {
+        return diff_keyed_children(old, new, path, patches);
+    }
+    let children_count = old.len().max(new.len());
+    for index in 0..children_count {
+        match (old.get(index), new.get(index)) {
+            (Some(old_child), Some(new_child)) => {
+                path.push(index);
+                diff_node(old_child, new_child, path, patches);
+                path.pop();
+            }
+            (Some(Node::Empty), None) | (None, Some(Node::Empty)) | (None, None) => (),
+            (Some(_), None) => patches.push(Patch::RemoveChild {
+                path: path.clone(),
+                index,
+            }),
+            (None, Some(_)) => patches.push(Patch::InsertChild {
+                path: path.clone(),
+                index,
+            }),
+        }
+    }
+}
+
+fn is_keyed<Ms>(node: &Node<Ms>) -> bool {
+    match node {
+        Node::Element(el) => el.key.is_some(),
+        _ => false,
+    }
+}
+
+/// Pair the children like `patch::patch_keyed_els`: each new keyed element with the old one
+/// with the same key, the other children in order with the old children without keys.
+fn diff_keyed_children<Ms>(
+    old: &[Node<Ms>],
+    new: &[Node<Ms>],
+    path: &mut NodePath,
+    patches: &mut Vec<Patch>,
+) {
+    let mut old_keyed = HashMap::<&str, usize>::new();
+    let mut old_unkeyed = Vec::new();
+    for (index, child) in old.iter().enumerate() {
+        match child {
+            Node::Element(El { key: Some(key), .. }) => {
+                // With duplicate keys, only the first element is reused.
+                old_keyed.entry(key.as_str()).or_insert(index);
+            }
+            _ => old_unkeyed.push(index),
+        }
+    }
+    let mut old_unkeyed = old_unkeyed.into_iter();
+    let mut old_used = vec![false; old.len()];
+    // The greatest old index of the children kept so far; a child before it has been moved.
+    let mut last_kept = None;
+
+    for (index, new_child) in new.iter().enumerate() {
+        let old_index = match new_child {
+            Node::Element(El { key: Some(key), .. }) => old_keyed.remove(key.as_str()),
+            _ => old_unkeyed.next(),
+        };
+        let old_child = old_index.map(|old_index| {
+            old_used[old_index] = true;
+            (old_index, &old[old_index])
+        });
+        match (old_child, new_child) {
+            (None | Some((_, Node::Empty)), Node::Empty) => (),
+            (None | Some((_, Node::Empty)), _) => patches.push(Patch::InsertChild {
+                path: path.clone(),
+                index,
+            }),
+            (Some((old_index, _)), Node::Empty) => patches.push(Patch::RemoveChild {
+                path: path.clone(),
+                index: old_index,
+            }),
+            (Some((old_index, old_child)), _) => {
+                if matches!(last_kept, Some(last_kept) if old_index < last_kept) {
+                    patches.push(Patch::MoveChild {
+                        path: path.clone(),
+                        from: old_index,
+                        to: index,
+                    });
+                } else {
+                    last_kept = Some(old_index);
+                }
+                path.push(index);
+                diff_node(old_child, new_child, path, patches);
+                path.pop();
+            }
+        }
+    }
+
+    for (index, old_child) in old.iter().enumerate() {
+        if !old_used[index] && !matches!(old_child, Node::Empty) {
+            patches.push(Patch::RemoveChild {
+                path: path.clone(),
+                index,
+            });
+        }
+    }
+}
+
+/// `path` points to the compared nodes; the parent's path is `path` without the last index.
+fn diff_node<Ms>(old: &Node<Ms>, new: &Node<Ms>, path: &mut NodePath, patches: &mut Vec<Patch>) {
+    let parent_path = || path[..path.len() - 1].to_vec();
+    let index = path[path.len() - 1];
+
+    match (old, new) {
+        (Node::Element(old_el), Node::Element(new_el)) => diff_el(old_el, new_el, path, patches),
+        (Node::Text(old_text), Node::Text(new_text)) => {
+            if old_text != new_text {
+                patches.push(Patch::SetText {
+                    path: path.clone(),
+                    text: new_text.text.to_string(),
+                });
+            }
+        }
+        (Node::Empty, Node::Empty) => (),
+        (
+            Node::Portal {
+                target: old_target,
+                children: old_children,
+            },
+            Node::Portal {
+                target: new_target,
+                children: new_children,
+            },
+        ) if old_target == new_target => diff_children(old_children, new_children, path, patches),
+        (Node::Empty, _) => patches.push(Patch::InsertChild {
+            path: parent_path(),
+            index,
+        }),
+        (_, Node::Empty) => patches.push(Patch::RemoveChild {
+            path: parent_path(),
+            index,
+        }),
+        _ => patches.push(Patch::ReplaceNode { path: path.clone() }),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::virtual_dom::{Tag, Text};
+    use wasm_bindgen_test::*;
+
+    #[derive(Clone, Debug)]
+    enum Msg {}
+
+    fn el_with_children(tag: Tag, children: Vec<Node<Msg>>) -> El<Msg> {
+        let mut el = El::empty(tag);
+        el.children = children;
+        el
+    }
+
+    #[wasm_bindgen_test]
+    fn same_trees_have_no_patches() {
+        let old = el_with_children(Tag::Div, vec![Node::new_text("a")]);
+        let new = old.clone();
+
+        assert!(diff_els(&old, &new).is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn attrs_and_text_changed() {
+        let mut old = el_with_children(Tag::Div, vec![Node::Text(Text::new("a"))]);
+        old.add_attr("id", "old").add_attr("title", "hello");
+        let mut new = el_with_children(Tag::Div, vec![Node::Text(Text::new("b"))]);
+        new.add_attr("id", "new");
+
+        assert_eq!(
+            diff_els(&old, &new),
+            vec![
+                Patch::SetAttr {
+                    path: vec![],
+                    key: At::Id,
+                    val: AtValue::Some("new".into()),
+                },
+                Patch::RemoveAttr {
+                    path: vec![],
+                    key: At::Title,
+                },
+                Patch::SetText {
+                    path: vec![0],
+                    text: "b".into(),
+                },
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn children_replaced_inserted_and_removed() {
+        let old = el_with_children(
+            Tag::Ul,
+            vec![
+                Node::Element(El::empty(Tag::Li)),
+                Node::Empty,
+                Node::new_text("removed"),
+            ],
+        );
+        let new = el_with_children(
+            Tag::Ul,
+            vec![Node::Element(El::empty(Tag::P)), Node::new_text("inserted")],
+        );
+
+        assert_eq!(
+            diff_els(&old, &new),
+            vec![
+                Patch::ReplaceNode { path: vec![0] },
+                Patch::InsertChild {
+                    path: vec![],
+                    index: 1
+                },
+                Patch::RemoveChild {
+                    path: vec![],
+                    index: 2
+                },
+            ]
+        );
+    }
+
+    fn item(key: &str, text: &str) -> Node<Msg> {
+        let mut el = el_with_children(Tag::Li, vec![Node::new_text(text.to_owned())]);
+        el.key(key);
+        Node::Element(el)
+    }
+
+    #[wasm_bindgen_test]
+    fn keyed_children_moved() {
+        let old = el_with_children(
+            Tag::Ul,
+            vec![
+                item("a", "a"),
+                item("b", "b"),
+                item("c", "c"),
+                item("d", "d"),
+            ],
+        );
+        let new = el_with_children(
+            Tag::Ul,
+            vec![
+                item("c", "c"),
+                item("a", "a"),
+                item("e", "e"),
+                item("b", "changed"),
+            ],
+        );
+
+        assert_eq!(
+            diff_els(&old, &new),
+            vec![
+                Patch::MoveChild {
+                    path: vec![],
+                    from: 0,
+                    to: 1
+                },
+                Patch::InsertChild {
+                    path: vec![],
+                    index: 2
+                },
+                Patch::MoveChild {
+                    path: vec![],
+                    from: 1,
+                    to: 3
+                },
+                Patch::SetText {
+                    path: vec![3, 0],
+                    text: "changed".into(),
+                },
+                Patch::RemoveChild {
+                    path: vec![],
+                    index: 3
+                },
+            ]
+        );
+
+        // Removed children don't make the following ones moved.
+        let new = el_with_children(Tag::Ul, vec![item("b", "b"), item("d", "d")]);
+        assert_eq!(
+            diff_els(&old, &new),
+            vec![
+                Patch::RemoveChild {
+                    path: vec![],
+                    index: 0
+                },
+                Patch::RemoveChild {
+                    path: vec![],
+                    index: 2
+                },
+            ]
+        );
+    }
+}
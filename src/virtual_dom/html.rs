@@ -0,0 +1,91 @@
+//! This module contains the serialization of vdom trees into HTML strings,
+//! without touching the DOM; see `El::to_html_string`.
+
+use super::{AtValue, El, Node};
+
+/// Elements that can't have children, so they don't have closing tags.
+pub(crate) const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Serialize the element, including its children. Listeners, hooks and children
+/// of portals (rendered into other elements) are omitted.
+pub fn el_to_html<Ms>(el: &El<Ms>) -> String {
+    let mut html = format!("<{}", el.tag.as_str());
+
+    for (key, val) in &el.attrs.vals {
+        match val {
+            AtValue::Some(val) => {
+                html += &format!(" {}=\"{}\"", key.as_str(), escape_attr(val));
+            }
+            AtValue::None => html += &format!(" {}", key.as_str()),
+            AtValue::Ignored => (),
+        }
+    }
+    let style = el.style.to_string();
+    if !style.is_empty() {
+        html += &format!(" style=\"{}\"", escape_attr(&style));
+    }
+    html.push('>');
+
+    if VOID_ELEMENTS.contains(&el.tag.as_str()) {
+        return html;
+    }
+    html += &children_to_html(&el.children);
+    html + &format!("</{}>", el.tag.as_str())
+}
+
+/// Serialize the children; eg to get an element's inner HTML.
+pub fn children_to_html<Ms>(children: &[Node<Ms>]) -> String {
+    children.iter().map(node_to_html).collect()
+}
+
+/// See `el_to_html`.
+pub fn node_to_html<Ms>(node: &Node<Ms>) -> String {
+    match node {
+        Node::Element(el) => el_to_html(el),
+        Node::Text(text) => escape_text(&text.text),
+        Node::Empty | Node::Portal { .. } => String::new(),
+    }
+}
+
+pub(crate) fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub(crate) fn escape_attr(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::virtual_dom::{St, Tag};
+    use wasm_bindgen_test::*;
+
+    #[derive(Clone, Debug)]
+    enum Msg {}
+
+    #[wasm_bindgen_test]
+    fn el_to_html_with_children() {
+        let mut input = El::empty(Tag::Input);
+        input.add_attr("disabled", AtValue::None);
+        let mut el: El<Msg> = El::empty(Tag::Div);
+        el.add_attr("title", "\"quoted\" & more")
+            .add_attr("hidden", AtValue::Ignored)
+            .add_style(St::Color, "red")
+            .add_text("<b>1 & 2</b>")
+            .add_child(Node::Element(input))
+            .add_child(Node::Empty)
+            .add_child(Node::new_portal("body", vec![Node::new_text("elsewhere")]));
+
+        assert_eq!(
+            el_to_html(&el),
+            "<div title=\"&quot;quoted&quot; &amp; more\" style=\"color:red\">\
+             &lt;b&gt;1 &amp; 2&lt;/b&gt;<input disabled></div>"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! This module contains conversions between vdom trees and JSON.
+//!
+//! It isn't behind a feature, because `serde` and `serde_json` are already required by Seed.
+//!
+//! An element is represented by an object; all fields except `tag` are optional:
+//!
+//! ```json
+//! {
+//!     "tag": "div",
+//!     "namespace": "http://www.w3.org/2000/svg",
+//!     "attrs": { "id": "main", "hidden": true, "title": null },
+//!     "style": { "color": "red", "z-index": 2 },
+//!     "children": ["Some text", { "tag": "span" }, null]
+//! }
+//! ```
+//!
+//! - Attribute values: strings and numbers are rendered as they are, `true` renders
+//!   the attribute without a value (`AtValue::None`) and `false` or `null` ignores it
+//!   (`AtValue::Ignored`).
+//! - Style values: strings and numbers are rendered, `null` ignores the property.
+//! - Children: strings are text nodes, objects are elements and `null` is `Node::Empty`.
+//!   Objects with the string field `portal` (instead of `tag`) are portals; see `Node::Portal`:
+//!   `{ "portal": "#modals", "children": [..] }`.
+//!
+//! Listeners and lifecycle hooks can't be represented in JSON - find the elements
+//! by their tags or ids and add them separately.
+
+use super::{AtValue, CSSValue, El, Node, Tag};
+use crate::browser::dom::Namespace;
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FromJsonError {
+    /// The value isn't an object, a string or `null`.
+    InvalidNode(Value),
+    /// The element object doesn't contain the string field `tag`.
+    MissingTag(Value),
+    /// The field (`namespace`, `attrs`, `style`, `children` or `portal`) or its item
+    /// has an unexpected type.
+    InvalidField(&'static str, Value),
+}
+
+/// Create a vdom element from its JSON representation.
+pub fn el_from_json<Ms>(value: &Value) -> Result<El<Ms>, FromJsonError> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| FromJsonError::InvalidNode(value.clone()))?;
+
+    let tag = object
+        .get("tag")
+        .and_then(Value::as_str)
+        .ok_or_else(|| FromJsonError::MissingTag(value.clone()))?;
+    let mut el = El::empty(Tag::from(tag));
+
+    if let Some(namespace) = object.get("namespace") {
+        let namespace = namespace
+            .as_str()
+            .ok_or_else(|| FromJsonError::InvalidField("namespace", namespace.clone()))?;
+        el.namespace = Some(Namespace::from(namespace.to_string()));
+    }
+
+    if let Some(attrs) = object.get("attrs") {
+        let attrs = attrs
+            .as_object()
+            .ok_or_else(|| FromJsonError::InvalidField("attrs", attrs.clone()))?;
+        for (key, val) in attrs {
+            let val = match val {
+                Value::String(val) => AtValue::Some(val.clone()),
+                Value::Number(val) => AtValue::Some(val.to_string()),
+                Value::Bool(true) => AtValue::None,
+                Value::Bool(false) | Value::Null => AtValue::Ignored,
+                _ => return Err(FromJsonError::InvalidField("attrs", val.clone())),
+            };
+            el.attrs.add(key.as_str().into(), val);
+        }
+    }
+
+    if let Some(style) = object.get("style") {
+        let style = style
+            .as_object()
+            .ok_or_else(|| FromJsonError::InvalidField("style", style.clone()))?;
+        for (key, val) in style {
+            let val = match val {
+                Value::String(val) => CSSValue::Some(val.clone()),
+                Value::Number(val) => CSSValue::Some(val.to_string()),
+                Value::Null => CSSValue::Ignored,
+                _ => return Err(FromJsonError::InvalidField("style", val.clone())),
+            };
+            el.style.add(key.as_str(), val);
+        }
+    }
+
+    if let Some(children) = object.get("children") {
+        let children = children
+            .as_array()
+            .ok_or_else(|| FromJsonError::InvalidField("children", children.clone()))?;
+        for child in children {
+            el.children.push(node_from_json(child)?);
+        }
+    }
+    Ok(el)
+}
+
+/// Create a vdom node from its JSON representation.
+pub fn node_from_json<Ms>(value: &Value) -> Result<Node<Ms>, FromJsonError> {
+    match value {
+        Value::String(text) => Ok(Node::new_text(text.clone())),
+        Value::Null => Ok(Node::Empty),
+        Value::Object(object) => match object.get("portal") {
+            Some(Value::String(target)) => {
+                let children = match object.get("children") {
+                    Some(Value::Array(children)) => children
+                        .iter()
+                        .map(node_from_json)
+                        .collect::<Result<_, _>>()?,
+                    Some(children) => {
+                        return Err(FromJsonError::InvalidField("children", children.clone()))
+                    }
+                    None => Vec::new(),
+                };
+                Ok(Node::new_portal(target.clone(), children))
+            }
+            Some(target) => Err(FromJsonError::InvalidField("portal", target.clone())),
+            None => el_from_json(value).map(Node::Element),
+        },
+        _ => Err(FromJsonError::InvalidNode(value.clone())),
+    }
+}
+
+/// Create a JSON representation of the element; listeners and hooks are omitted.
+/// Empty fields are omitted too.
+pub fn el_to_json<Ms>(el: &El<Ms>) -> Value {
+    let mut object = Map::new();
+    object.insert("tag".into(), el.tag.as_str().into());
+
+    if let Some(namespace) = &el.namespace {
+        object.insert("namespace".into(), namespace.as_str().into());
+    }
+
+    if !el.attrs.vals.is_empty() {
+        let attrs = el
+            .attrs
+            .vals
+            .iter()
+            .map(|(key, val)| {
+                let val = match val {
+                    AtValue::Some(val) => Value::String(val.clone()),
+                    AtValue::None => Value::Bool(true),
+                    AtValue::Ignored => Value::Null,
+                };
+                (key.as_str().to_string(), val)
+            })
+            .collect();
+        object.insert("attrs".into(), Value::Object(attrs));
+    }
+
+    if !el.style.vals.is_empty() {
+        let style = el
+            .style
+            .vals
+            .iter()
+            .map(|(key, val)| {
+                let val = match val {
+                    CSSValue::Some(val) => Value::String(val.clone()),
+                    CSSValue::Ignored => Value::Null,
+                };
+                (key.as_str().to_string(), val)
+            })
+            .collect();
+        object.insert("style".into(), Value::Object(style));
+    }
+
+    if !el.children.is_empty() {
+        let children = el.children.iter().map(node_to_json).collect();
+        object.insert("children".into(), Value::Array(children));
+    }
+    Value::Object(object)
+}
+
+/// Create a JSON representation of the node; see `el_to_json`.
+pub fn node_to_json<Ms>(node: &Node<Ms>) -> Value {
+    match node {
+        Node::Element(el) => el_to_json(el),
+        Node::Text(text) => Value::String(text.text.to_string()),
+        Node::Empty => Value::Null,
+        Node::Portal { target, children } => {
+            let mut object = Map::new();
+            object.insert("portal".into(), target.as_str().into());
+            if !children.is_empty() {
+                let children = children.iter().map(node_to_json).collect();
+                object.insert("children".into(), Value::Array(children));
+            }
+            Value::Object(object)
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::virtual_dom::{At, St};
+    use serde_json::json;
+    use wasm_bindgen_test::*;
+
+    #[derive(Clone, Debug)]
+    enum Msg {}
+
+    #[wasm_bindgen_test]
+    fn el_from_json_with_children() {
+        let el: El<Msg> = el_from_json(&json!({
+            "tag": "ul",
+            "attrs": { "id": "list", "hidden": true, "title": null },
+            "style": { "color": "red" },
+            "children": [{ "tag": "li", "children": ["first"] }, null, "text"]
+        }))
+        .unwrap();
+
+        assert_eq!(el.tag, Tag::Ul);
+        assert_eq!(el.attrs.vals[&At::Id], AtValue::Some("list".into()));
+        assert_eq!(el.attrs.vals[&At::Hidden], AtValue::None);
+        assert_eq!(el.attrs.vals[&At::Title], AtValue::Ignored);
+        assert_eq!(el.style.vals[&St::Color], CSSValue::Some("red".into()));
+        assert_eq!(el.children.len(), 3);
+        assert_eq!(el.children[0].get_text(), "first");
+        assert!(el.children[1].is_empty());
+        assert_eq!(el.children[2].get_text(), "text");
+    }
+
+    #[wasm_bindgen_test]
+    fn el_from_json_invalid() {
+        assert_eq!(
+            el_from_json::<Msg>(&json!({ "attrs": {} })),
+            Err(FromJsonError::MissingTag(json!({ "attrs": {} })))
+        );
+        assert_eq!(
+            el_from_json::<Msg>(&json!({ "tag": "div", "children": [1] })),
+            Err(FromJsonError::InvalidNode(json!(1)))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn el_to_json_snapshot() {
+        let mut item = El::empty(Tag::Li);
+        item.add_attr("hidden", AtValue::None).add_text("first");
+        let mut el: El<Msg> = El::empty(Tag::Ul);
+        el.add_attr("id", "list")
+            .add_style(St::Color, "red")
+            .add_child(Node::Element(item))
+            .add_child(Node::Empty)
+            .add_text("text");
+
+        assert_eq!(
+            el_to_json(&el),
+            json!({
+                "tag": "ul",
+                "attrs": { "id": "list" },
+                "style": { "color": "red" },
+                "children": [
+                    { "tag": "li", "attrs": { "hidden": true }, "children": ["first"] },
+                    null,
+                    "text"
+                ]
+            })
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn json_round_trip() {
+        let value = json!({
+            "tag": "svg",
+            "namespace": "http://www.w3.org/2000/svg",
+            "attrs": { "viewBox": "0 0 10 10", "title": null },
+            "style": { "display": null, "opacity": "0.5" },
+            "children": [
+                { "tag": "path", "attrs": { "d": "M0 0" } },
+                "text",
+                null,
+                { "portal": "body", "children": [{ "tag": "text" }] }
+            ]
+        });
+        let el: El<Msg> = el_from_json(&value).unwrap();
+
+        assert_eq!(el_to_json(&el), value);
+    }
+}
@@ -0,0 +1,151 @@
+//! This module contains rewriting of links in imported HTML and markdown;
+//! see `El::from_html_with_options` and `MarkdownOptions::links`.
+
+use super::{At, AtValue, El, Node, Tag};
+use crate::browser::util;
+
+/// Options for links in imported HTML; eg to embed third-party content safely.
+/// The default ones keep the links as they are.
+#[derive(Clone, Debug, Default)]
+pub struct LinkOptions {
+    /// Add `target="_blank"` and `rel="noopener noreferrer"` to `http(s)` links (`a[href]`)
+    /// to origins other than the `base_url`'s one (or the current page's one, if not set).
+    pub open_external_in_new_tab: bool,
+    /// Resolve relative URLs of links (`a[href]`) and images (`img[src]`) against this URL.
+    /// Fragment-only links (eg `#usage`) are kept, so they still point into the page.
+    pub base_url: Option<String>,
+}
+
+struct Base {
+    url: String,
+    origin: String,
+}
+
+/// Rewrite links in the nodes and their descendants according to the `options`.
+pub(crate) fn rewrite_links<Ms>(nodes: &mut [Node<Ms>], options: &LinkOptions) {
+    if !options.open_external_in_new_tab && options.base_url.is_none() {
+        return;
+    }
+    let url = match &options.base_url {
+        Some(base_url) => base_url.clone(),
+        None => util::window()
+            .location()
+            .href()
+            .expect("Problem getting the current URL"),
+    };
+    let origin = match web_sys::Url::new(&url) {
+        Ok(parsed) => parsed.origin(),
+        Err(_) => {
+            crate::error("Invalid base URL of links");
+            return;
+        }
+    };
+    rewrite_nodes(nodes, options, &Base { url, origin });
+}
+
+fn rewrite_nodes<Ms>(nodes: &mut [Node<Ms>], options: &LinkOptions, base: &Base) {
+    for node in nodes {
+        match node {
+            Node::Element(el) => {
+                rewrite_el(el, options, base);
+                rewrite_nodes(&mut el.children, options, base);
+            }
+            Node::Portal { children, .. } => rewrite_nodes(children, options, base),
+            Node::Text(_) | Node::Empty => (),
+        }
+    }
+}
+
+fn rewrite_el<Ms>(el: &mut El<Ms>, options: &LinkOptions, base: &Base) {
+    let url_at = match el.tag {
+        Tag::A => At::Href,
+        Tag::Img => At::Src,
+        _ => return,
+    };
+    let url = match el.attrs.vals.get(&url_at) {
+        Some(AtValue::Some(url)) if !url.starts_with('#') => url.clone(),
+        _ => return,
+    };
+    let resolved = match web_sys::Url::new_with_base(&url, &base.url) {
+        Ok(resolved) => resolved,
+        Err(_) => return,
+    };
+
+    if options.base_url.is_some() {
+        el.attrs.add(url_at.clone(), resolved.href());
+    }
+    let is_external = matches!(resolved.protocol().as_str(), "http:" | "https:")
+        && resolved.origin() != base.origin;
+    if url_at == At::Href && options.open_external_in_new_tab && is_external {
+        el.attrs.add(At::Target, "_blank");
+        el.attrs.add(At::Rel, "noopener noreferrer");
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Clone, Debug)]
+    enum Msg {}
+
+    fn link(href: &str) -> Node<Msg> {
+        let mut a = El::empty(Tag::A);
+        a.add_attr("href", href);
+        Node::Element(a)
+    }
+
+    fn attr(node: &Node<Msg>, at: At) -> Option<AtValue> {
+        match node {
+            Node::Element(el) => el.attrs.vals.get(&at).cloned(),
+            _ => None,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn links_rewritten() {
+        let mut image = El::empty(Tag::Img);
+        image.add_attr("src", "img/logo.png");
+        let mut nodes = vec![
+            link("/docs"),
+            link("https://example.com/page"),
+            link("#usage"),
+            Node::Element(image),
+        ];
+
+        rewrite_links(
+            &mut nodes,
+            &LinkOptions {
+                open_external_in_new_tab: true,
+                base_url: Some("https://seed-rs.org/guide/".into()),
+            },
+        );
+
+        assert_eq!(
+            attr(&nodes[0], At::Href),
+            Some(AtValue::Some("https://seed-rs.org/docs".into()))
+        );
+        assert_eq!(attr(&nodes[0], At::Target), None);
+        assert_eq!(
+            attr(&nodes[1], At::Target),
+            Some(AtValue::Some("_blank".into()))
+        );
+        assert_eq!(
+            attr(&nodes[1], At::Rel),
+            Some(AtValue::Some("noopener noreferrer".into()))
+        );
+        assert_eq!(
+            attr(&nodes[2], At::Href),
+            Some(AtValue::Some("#usage".into()))
+        );
+        assert_eq!(
+            attr(&nodes[3], At::Src),
+            Some(AtValue::Some(
+                "https://seed-rs.org/guide/img/logo.png".into()
+            ))
+        );
+    }
+}
@@ -1,8 +1,22 @@
 use super::Ev;
 use crate::app::MessageMapper;
 use crate::browser::{dom::lifecycle_hooks::fmt_hook_fn, util::ClosureNew};
-use std::{fmt, mem, rc::Rc};
-use wasm_bindgen::{closure::Closure, JsCast};
+use gloo_timers::callback::Timeout;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt, mem,
+    rc::Rc,
+    time::Duration,
+};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+thread_local! {
+    // Pending debounced handlers, by the ids stored on their event targets. They're kept
+    // off the listeners, so they survive reattaching the listeners on each render.
+    static DEBOUNCE_TIMEOUTS: RefCell<HashMap<u32, Timeout>> = RefCell::new(HashMap::new());
+    static NEXT_DEBOUNCE_ID: Cell<u32> = const { Cell::new(0) };
+}
 
 pub struct EventHandler<Ms>(Rc<dyn Fn(web_sys::Event) -> Ms>);
 
@@ -48,6 +62,7 @@ impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for EventHandler<
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Category {
     Custom,
+    Focus,
     Input,
     Keyboard,
     Mouse,
@@ -56,6 +71,26 @@ pub enum Category {
     Simple,
 }
 
+/// Options of a listener; see `El::on_with_options`.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/EventTarget/addEventListener#options)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ListenerOptions {
+    /// The handler never calls `prevent_default`, so the browser can eg scroll without waiting.
+    pub passive: bool,
+    /// Handle the event in the capture phase - before the listeners of descendants.
+    pub capture: bool,
+    /// Remove the listener after the first event. The first event is remembered by the DOM
+    /// element, so the listener isn't called again when the element is re-rendered.
+    pub once: bool,
+    /// Call `event.prevent_default()` before the handler.
+    pub prevent_default: bool,
+    /// Call `event.stop_propagation()` before the handler.
+    pub stop_propagation: bool,
+    /// Call the handler only when no other event comes for the duration, with the last event;
+    /// see `Listener::debounce`.
+    pub debounce: Option<Duration>,
+}
+
 /// Ev-handling for Elements
 pub struct Listener<Ms> {
     pub trigger: Ev,
@@ -67,6 +102,7 @@ pub struct Listener<Ms> {
     // are not assoicated with a message.
     pub control_val: Option<String>,
     pub control_checked: Option<bool>,
+    pub options: ListenerOptions,
 
     // category and message are used as an aid for comparing Listeners, and therefore diffing.
     // todo: Neither are fully implemented.
@@ -84,6 +120,7 @@ impl<Ms: Clone> Clone for Listener<Ms> {
             closure: None,
             control_val: self.control_val.clone(),
             control_checked: self.control_checked,
+            options: self.options,
             category: self.category,
             message: self.message.clone(),
         }
@@ -94,12 +131,13 @@ impl<Ms> fmt::Debug for Listener<Ms> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Listener {{ trigger:{:#?}, handler:{:#?}, closure:{:#?}, control:{:#?}{:#?}, category:{:#?}",
+            "Listener {{ trigger:{:#?}, handler:{:#?}, closure:{:#?}, control:{:#?}{:#?}, options:{:#?}, category:{:#?}",
             self.trigger,
             fmt_hook_fn(&self.handler),
             fmt_hook_fn(&self.closure),
             self.control_val,
             self.control_checked,
+            self.options,
             self.category,
         )
     }
@@ -111,15 +149,26 @@ impl<Ms> Listener<Ms> {
         handler: Option<impl Into<EventHandler<Ms>>>,
         category: Option<Category>,
         message: Option<Ms>,
+    ) -> Self {
+        // We use &str instead of Event here to allow flexibility in helper funcs,
+        // without macros by using ToString.
+        Self::new_ev(trigger.into(), handler, category, message)
+    }
+
+    /// See `new`; unlike `new`, `trigger` may be `Ev::Custom`.
+    pub(crate) fn new_ev(
+        trigger: Ev,
+        handler: Option<impl Into<EventHandler<Ms>>>,
+        category: Option<Category>,
+        message: Option<Ms>,
     ) -> Self {
         Self {
-            // We use &str instead of Event here to allow flexibility in helper funcs,
-            // without macros by using ToString.
-            trigger: trigger.into(),
+            trigger,
             handler: handler.map(Into::into),
             closure: None,
             control_val: None,
             control_checked: None,
+            options: ListenerOptions::default(),
             category,
             message,
         }
@@ -134,6 +183,7 @@ impl<Ms> Listener<Ms> {
             closure: None,
             control_val: Some(val),
             control_checked: None,
+            options: ListenerOptions::default(),
             category: None,
             message: None,
         }
@@ -147,27 +197,82 @@ impl<Ms> Listener<Ms> {
             closure: None,
             control_val: None,
             control_checked: Some(checked),
+            options: ListenerOptions::default(),
             category: None,
             message: None,
         }
     }
 
+    /// Set the listener's options; eg `mouse_ev(Ev::Click, handler).with_options(options)`.
+    /// Passive listeners can't prevent the default action, so `passive` with `prevent_default`
+    /// is reported as an error.
+    pub fn with_options(mut self, options: ListenerOptions) -> Self {
+        if options.passive && options.prevent_default {
+            crate::error(format!(
+                "The passive listener of `{}` can't prevent the default action",
+                self.trigger.as_str()
+            ));
+        }
+        self.options = options;
+        self
+    }
+
+    /// Send the message only when the events stop coming for the `delay`; eg to search
+    /// while typing. The handler is called with the last event; `prevent_default`
+    /// and `stop_propagation` options are still applied to each event.
+    /// The pending message is bound to the DOM element, so it isn't dropped when the element
+    /// is re-rendered.
+    pub fn debounce(mut self, delay: Duration) -> Self {
+        self.options.debounce = Some(delay);
+        self
+    }
+
     /// This method is where the processing logic for events happens.
     pub fn attach<T>(&mut self, el_ws: &T, mailbox: crate::virtual_dom::mailbox::Mailbox<Ms>)
     where
         T: AsRef<web_sys::EventTarget>,
     {
         let handler = self.handler.clone().expect("Can't find old handler");
+        let options = self.options;
+        let target = el_ws.as_ref().clone();
+        let debounce_key = format!("seedDebounce{}", self.trigger.as_str());
+        let once_key = JsValue::from_str(&format!("seedOnce{}", self.trigger.as_str()));
         // This is the closure ran when a DOM element has an user defined callback
         let closure = Closure::new(move |event: web_sys::Event| {
-            let msg = handler.call(event);
-            mailbox.send(msg);
+            if options.once && !is_first_event(&target, &once_key, &event) {
+                return;
+            }
+            if options.prevent_default {
+                event.prevent_default();
+            }
+            if options.stop_propagation {
+                event.stop_propagation();
+            }
+            match options.debounce {
+                Some(delay) => {
+                    let handler = handler.clone();
+                    let mailbox = mailbox.clone();
+                    debounce(&target, &debounce_key, delay, move || {
+                        mailbox.send(handler.call(event));
+                    });
+                }
+                None => {
+                    let msg = handler.call(event);
+                    mailbox.send(msg);
+                }
+            }
         });
 
+        let mut listener_options = web_sys::AddEventListenerOptions::new();
+        listener_options
+            .passive(options.passive)
+            .capture(options.capture)
+            .once(options.once);
         (el_ws.as_ref() as &web_sys::EventTarget)
-            .add_event_listener_with_callback(
+            .add_event_listener_with_callback_and_add_event_listener_options(
                 self.trigger.as_str(),
                 closure.as_ref().unchecked_ref(),
+                &listener_options,
             )
             .expect("Problem adding listener to element");
 
@@ -185,18 +290,67 @@ impl<Ms> Listener<Ms> {
         let closure = self.closure.take().expect("Can't find closure to detach");
 
         (el_ws.as_ref() as &web_sys::EventTarget)
-            .remove_event_listener_with_callback(
+            .remove_event_listener_with_callback_and_bool(
                 self.trigger.as_str(),
                 closure.as_ref().unchecked_ref(),
+                self.options.capture,
             )
             .expect("Problem removing listener from element");
     }
 }
 
+/// Remember the first event of `once` listeners on the `target`, so the listeners reattached
+/// on render aren't called again. Listeners of the same event share the `key`, so all of them
+/// are called with the first event.
+fn is_first_event(target: &web_sys::EventTarget, key: &JsValue, event: &web_sys::Event) -> bool {
+    match js_sys::Reflect::get(target, key) {
+        Ok(first_event) if !first_event.is_undefined() => {
+            &first_event == event.as_ref() as &JsValue
+        }
+        _ => {
+            if js_sys::Reflect::set(target, key, event).is_err() {
+                crate::error("Problem storing the first event");
+            }
+            true
+        }
+    }
+}
+
+/// Call `callback` after the `delay`, unless it's called again with the same `target` and `key`
+/// before; see `Listener::debounce`.
+fn debounce(
+    target: &web_sys::EventTarget,
+    key: &str,
+    delay: Duration,
+    callback: impl FnOnce() + 'static,
+) {
+    let key = JsValue::from_str(key);
+    if let Some(pending_id) = js_sys::Reflect::get(target, &key)
+        .ok()
+        .and_then(|id| id.as_f64())
+    {
+        // Dropping the timeout cancels it.
+        DEBOUNCE_TIMEOUTS.with(|timeouts| timeouts.borrow_mut().remove(&(pending_id as u32)));
+    }
+
+    let id = NEXT_DEBOUNCE_ID.with(|next_id| next_id.replace(next_id.get().wrapping_add(1)));
+    if js_sys::Reflect::set(target, &key, &JsValue::from(id)).is_err() {
+        crate::error("Problem storing debounce timeout");
+    }
+    let target = target.clone();
+    let timeout = Timeout::new(delay.as_millis() as u32, move || {
+        let _ = js_sys::Reflect::delete_property(target.unchecked_ref(), &key);
+        let _fired = DEBOUNCE_TIMEOUTS.with(|timeouts| timeouts.borrow_mut().remove(&id));
+        callback();
+    });
+    DEBOUNCE_TIMEOUTS.with(|timeouts| timeouts.borrow_mut().insert(id, timeout));
+}
+
 impl<Ms> PartialEq for Listener<Ms> {
     fn eq(&self, other: &Self) -> bool {
         // Todo: This isn't (yet) a comprehensive check, but can catch some differences.
         self.trigger == other.trigger
+            && self.options == other.options
             && self.category == other.category
             // We use discriminant so we don't have to force Ms to impl PartialEq.
             && mem::discriminant(&self.message) == mem::discriminant(&other.message)
@@ -212,6 +366,7 @@ impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for Listener<Ms>
             closure: self.closure,
             control_val: self.control_val,
             control_checked: self.control_checked,
+            options: self.options,
             category: self.category,
             message: self.message.map(f),
         }
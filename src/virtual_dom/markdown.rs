@@ -0,0 +1,309 @@
+//! This module contains markdown rendering used in `El::from_markdown_with_options`.
+
+use super::LinkOptions;
+use pulldown_cmark::{html, CowStr, Event, Parser, Tag};
+use std::collections::HashMap;
+
+/// Options for `El::from_markdown_with_options`; the default ones render the same HTML
+/// as `El::from_markdown`.
+#[derive(Clone, Debug, Default)]
+pub struct MarkdownOptions {
+    /// Highlight fenced code blocks with a known language (eg ` ```rust `) by wrapping
+    /// their tokens in `span`s with classes, using
+    /// [syntect](https://docs.rs/syntect). Generate the stylesheet for the classes
+    /// with `syntect::html::css_for_theme_with_class_style` and `ClassStyle::Spaced`.
+    #[cfg(feature = "syntax-highlighting")]
+    pub highlight_code: bool,
+    /// Set `id`s of headings to their slugified text (eg `## Getting started` =>
+    /// `id="getting-started"`), so they're linkable. Duplicates get numeric suffixes (`-1`, `-2`, ..).
+    pub heading_ids: bool,
+    /// Add a link `<a class="heading-anchor" href="#{id}">#</a>` to the start of each heading;
+    /// implies `heading_ids`.
+    pub heading_anchors: bool,
+    /// Rewriting of link targets; eg to open external links in a new tab.
+    pub links: LinkOptions,
+}
+
+/// Render the markdown into HTML.
+pub fn to_html(markdown: &str, options: &MarkdownOptions) -> String {
+    let mut events: Box<dyn Iterator<Item = Event>> = Box::new(Parser::new(markdown));
+
+    #[cfg(feature = "syntax-highlighting")]
+    {
+        if options.highlight_code {
+            events = Box::new(highlighting::highlight_code_blocks(events));
+        }
+    }
+    if options.heading_ids || options.heading_anchors {
+        events = Box::new(add_heading_ids(events, options.heading_anchors));
+    }
+
+    let mut html_text = String::new();
+    html::push_html(&mut html_text, events);
+    html_text
+}
+
+/// Render the markdown's inline content (emphasis, code, links, ..) into HTML without
+/// the paragraph wrapper; consecutive paragraphs are separated by a space. Other blocks
+/// (eg headings or lists) are rendered as usual.
+pub fn inline_to_html(markdown: &str) -> String {
+    let mut is_after_paragraph = false;
+    let events = Parser::new(markdown).filter_map(|event| {
+        let is_next_paragraph = is_after_paragraph && event == Event::Start(Tag::Paragraph);
+        is_after_paragraph = event == Event::End(Tag::Paragraph);
+        match event {
+            Event::Start(Tag::Paragraph) if is_next_paragraph => {
+                Some(Event::Text(CowStr::from(" ")))
+            }
+            Event::Start(Tag::Paragraph) | Event::End(Tag::Paragraph) => None,
+            event => Some(event),
+        }
+    });
+
+    let mut html_text = String::new();
+    html::push_html(&mut html_text, events);
+    html_text
+}
+
+/// Lowercase the text, replace whitespace with `-` and remove other characters
+/// except alphanumeric ones, `-` and `_`; `-` is trimmed from the ends. The slug is empty
+/// if there isn't any alphanumeric character or `_`.
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for character in text.trim().chars() {
+        if character.is_alphanumeric() || character == '_' {
+            slug.extend(character.to_lowercase());
+        } else if (character.is_whitespace() || character == '-') && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_owned()
+}
+
+/// Replace the start and end of each heading with `Event::Html` containing its id.
+fn add_heading_ids<'a>(
+    mut events: impl Iterator<Item = Event<'a>>,
+    anchors: bool,
+) -> impl Iterator<Item = Event<'a>> {
+    let mut used_ids = HashMap::<String, usize>::new();
+    let mut heading_count = 0;
+    std::iter::from_fn(move || {
+        let event = events.next()?;
+        let level = match event {
+            Event::Start(Tag::Heading(level)) => level,
+            event => return Some(vec![event]),
+        };
+
+        let mut content = Vec::new();
+        let mut text = String::new();
+        for event in events.by_ref() {
+            match &event {
+                Event::End(Tag::Heading(_)) => break,
+                Event::Text(part) | Event::Code(part) => text.push_str(part),
+                _ => (),
+            }
+            content.push(event);
+        }
+
+        heading_count += 1;
+        let slug = match slugify(&text) {
+            // Eg a heading with only punctuation or emoji.
+            slug if slug.is_empty() => format!("section-{}", heading_count),
+            slug => slug,
+        };
+        let id = match used_ids.get_mut(&slug) {
+            Some(count) => {
+                *count += 1;
+                format!("{}-{}", slug, count)
+            }
+            None => slug.clone(),
+        };
+        used_ids.entry(slug).or_insert(0);
+        used_ids.entry(id.clone()).or_insert(0);
+
+        let mut block = vec![Event::Html(CowStr::from(format!(
+            "<h{} id=\"{}\">",
+            level, id
+        )))];
+        if anchors {
+            block.push(Event::Html(CowStr::from(format!(
+                "<a class=\"heading-anchor\" href=\"#{}\">#</a>",
+                id
+            ))));
+        }
+        block.extend(content);
+        block.push(Event::Html(CowStr::from(format!("</h{}>\n", level))));
+        Some(block)
+    })
+    .flatten()
+}
+
+#[cfg(feature = "syntax-highlighting")]
+mod highlighting {
+    use pulldown_cmark::{CowStr, Event, Tag};
+    use syntect::html::{ClassStyle, ClassedHTMLGenerator};
+    use syntect::parsing::SyntaxSet;
+    use syntect::util::LinesWithEndings;
+
+    thread_local! {
+        // Loading the syntaxes is slow, so do it only once and only when needed.
+        static SYNTAX_SET: SyntaxSet = SyntaxSet::load_defaults_newlines();
+    }
+
+    /// Replace code blocks with a known language by `Event::Html` with highlighted code.
+    pub(super) fn highlight_code_blocks<'a>(
+        mut events: impl Iterator<Item = Event<'a>>,
+    ) -> impl Iterator<Item = Event<'a>> {
+        std::iter::from_fn(move || {
+            let event = events.next()?;
+            let language = match &event {
+                Event::Start(Tag::CodeBlock(info)) => {
+                    info.split_whitespace().next().unwrap_or("").to_owned()
+                }
+                _ => return Some(vec![event]),
+            };
+
+            let mut block = vec![event];
+            let mut code = String::new();
+            for event in events.by_ref() {
+                if let Event::Text(text) = &event {
+                    code.push_str(text);
+                }
+                let is_end = matches!(event, Event::End(Tag::CodeBlock(_)));
+                block.push(event);
+                if is_end {
+                    break;
+                }
+            }
+
+            Some(match highlight(&code, &language) {
+                Some(highlighted) => vec![Event::Html(CowStr::from(format!(
+                    "<pre><code class=\"language-{}\">{}</code></pre>\n",
+                    language, highlighted
+                )))],
+                // Unknown language - render the code block as usual.
+                None => block,
+            })
+        })
+        .flatten()
+    }
+
+    fn highlight(code: &str, language: &str) -> Option<String> {
+        if language.is_empty() {
+            return None;
+        }
+        SYNTAX_SET.with(|syntax_set| {
+            let syntax = syntax_set.find_syntax_by_token(language)?;
+            let mut generator =
+                ClassedHTMLGenerator::new_with_class_style(syntax, syntax_set, ClassStyle::Spaced);
+            for line in LinesWithEndings::from(code) {
+                generator
+                    .parse_html_for_line_which_includes_newline(line)
+                    .ok()?;
+            }
+            Some(generator.finalize())
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn default_options() {
+        assert_eq!(
+            to_html(
+                "# Title\n\n```rust\nlet a = 1;\n```\n",
+                &MarkdownOptions::default()
+            ),
+            "<h1>Title</h1>\n<pre><code class=\"language-rust\">let a = 1;\n</code></pre>\n"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn heading_ids() {
+        let options = MarkdownOptions {
+            heading_ids: true,
+            ..MarkdownOptions::default()
+        };
+
+        assert_eq!(
+            to_html(
+                "# Getting *started*\n\n## Getting started\n\n### Getting started-1\n\nText",
+                &options
+            ),
+            "<h1 id=\"getting-started\">Getting <em>started</em></h1>\n\
+             <h2 id=\"getting-started-1\">Getting started</h2>\n\
+             <h3 id=\"getting-started-1-1\">Getting started-1</h3>\n\
+             <p>Text</p>\n"
+        );
+        assert_eq!(
+            to_html("# ???\n\n## Hello -", &options),
+            "<h1 id=\"section-1\">???</h1>\n<h2 id=\"hello\">Hello -</h2>\n"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn heading_anchors() {
+        let options = MarkdownOptions {
+            heading_anchors: true,
+            ..MarkdownOptions::default()
+        };
+
+        assert_eq!(
+            to_html("## What's `new`?", &options),
+            "<h2 id=\"whats-new\"><a class=\"heading-anchor\" href=\"#whats-new\">#</a>\
+             What's <code>new</code>?</h2>\n"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn inline_markdown() {
+        assert_eq!(
+            inline_to_html("**Bold** and `code`,\n[a link](/docs)"),
+            "<strong>Bold</strong> and <code>code</code>,\n<a href=\"/docs\">a link</a>"
+        );
+        assert_eq!(inline_to_html("First.\n\nSecond."), "First. Second.");
+        assert_eq!(inline_to_html("# Title\n\nText"), "<h1>Title</h1>\nText");
+        assert_eq!(
+            inline_to_html("* item\n\nText"),
+            "<ul>\n<li>item</li>\n</ul>\nText"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn inline_markdown_added() {
+        use crate::virtual_dom::{html::el_to_html, El, Tag as ElTag};
+
+        let mut el: El<()> = El::empty(ElTag::Label);
+        el.add_text("Note: ").add_inline_markdown("*Free* shipping");
+        assert_eq!(
+            el_to_html(&el),
+            "<label>Note: <em>Free</em> shipping</label>"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn slugs() {
+        assert_eq!(slugify("  Hello,  World - 2020! "), "hello-world-2020");
+        assert_eq!(slugify("snake_case and Ünïcode"), "snake_case-and-ünïcode");
+        assert_eq!(slugify("- Hello -"), "hello");
+        assert_eq!(slugify("?!"), "");
+    }
+
+    #[cfg(feature = "syntax-highlighting")]
+    #[wasm_bindgen_test]
+    fn highlighted_code() {
+        let options = MarkdownOptions {
+            highlight_code: true,
+            ..MarkdownOptions::default()
+        };
+        let html = to_html("```rust\nlet a = 1;\n```\n\n```unknown\nx\n```\n", &options);
+
+        assert!(html.starts_with("<pre><code class=\"language-rust\"><span class=\"source rust\">"));
+        assert!(html.contains("<span class=\"storage type rust\">let</span>"));
+        assert!(html.ends_with("<pre><code class=\"language-unknown\">x\n</code></pre>\n"));
+    }
+}
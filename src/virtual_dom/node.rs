@@ -16,7 +16,16 @@ pub enum Node<Ms: 'static> {
     Element(El<Ms>),
     //    Svg(El<Ms>),  // May be best to handle using namespace field on El
     Text(Text),
+    /// Renders nothing; eg `if show { el } else { Node::Empty }` or `Node::from(optional_el)`.
+    /// It's skipped when the children are attached, so it doesn't affect the DOM structure.
     Empty,
+    /// Children rendered into the DOM element matching the `target` selector
+    /// (eg `"body"` or `"#modals"`) instead of the portal's parent; eg for modals or tooltips.
+    /// They're still a part of the vdom tree, so their messages are handled as usual.
+    Portal {
+        target: String,
+        children: Vec<Node<Ms>>,
+    },
 }
 
 impl<Ms: Clone + 'static> Clone for Node<Ms> {
@@ -25,6 +34,10 @@ impl<Ms: Clone + 'static> Clone for Node<Ms> {
             Self::Element(e) => Self::Element(e.clone()),
             Self::Text(t) => Self::Text(t.clone()),
             Self::Empty => Self::Empty,
+            Self::Portal { target, children } => Self::Portal {
+                target: target.clone(),
+                children: children.clone(),
+            },
         }
     }
 }
@@ -36,11 +49,34 @@ impl<Ms> Node<Ms> {
         El::from_markdown(markdown)
     }
 
+    /// See `El::from_markdown_with_options`
+    pub fn from_markdown_with_options(
+        markdown: &str,
+        options: &super::MarkdownOptions,
+    ) -> Vec<Node<Ms>> {
+        El::from_markdown_with_options(markdown, options)
+    }
+
+    /// See `El::from_html_with_options`
+    pub fn from_html_with_options(html: &str, options: &super::LinkOptions) -> Vec<Node<Ms>> {
+        El::from_html_with_options(html, options)
+    }
+
+    /// See `El::from_markdown_blocks`
+    pub fn from_markdown_blocks(markdown: &str) -> impl Iterator<Item = Node<Ms>> + '_ {
+        El::from_markdown_blocks(markdown)
+    }
+
     /// See `El::from_html`
     pub fn from_html(html: &str) -> Vec<Node<Ms>> {
         El::from_html(html)
     }
 
+    /// See `El::from_svg_string`
+    pub fn from_svg_string(svg: &str) -> Vec<Node<Ms>> {
+        El::from_svg_string(svg)
+    }
+
     /// See `El::add_child`
     pub fn add_child(&mut self, node: Node<Ms>) -> &mut Self {
         if let Node::Element(el) = self {
@@ -117,6 +153,14 @@ impl<Ms> Node<Ms> {
         Node::Text(Text::new(text))
     }
 
+    /// See `Node::Portal`
+    pub fn new_portal(target: impl Into<String>, children: Vec<Node<Ms>>) -> Self {
+        Node::Portal {
+            target: target.into(),
+            children,
+        }
+    }
+
     pub fn is_text(&self) -> bool {
         if let Node::Text(_) = self {
             true
@@ -138,6 +182,9 @@ impl<Ms> Node<Ms> {
             false
         }
     }
+    pub fn is_portal(&self) -> bool {
+        matches!(self, Node::Portal { .. })
+    }
 
     pub fn text(&self) -> Option<&Text> {
         if let Node::Text(t) = self {
@@ -157,15 +204,71 @@ impl<Ms> Node<Ms> {
 
 // Backing node manipulation
 impl<Ms> Node<Ms> {
+    /// Replace the node with `new` and return the old one.
+    /// When patched, the DOM node is replaced as well - and the old element's `will_unmount`
+    /// and the new element's `did_mount` hooks are called - unless both nodes are elements
+    /// with the same tag; then only their differences are patched.
+    pub fn replace_with(&mut self, new: Node<Ms>) -> Node<Ms> {
+        std::mem::replace(self, new)
+    }
+
     pub fn strip_ws_nodes_from_self_and_children(&mut self) {
         match self {
             Node::Text(t) => t.strip_ws_node(),
             Node::Element(e) => e.strip_ws_nodes_from_self_and_children(),
             Node::Empty => (),
+            Node::Portal { children, .. } => children
+                .iter_mut()
+                .for_each(Node::strip_ws_nodes_from_self_and_children),
         }
     }
 }
 
+/// Insert a new separator (eg `Node::new_text(", ")`) between each pair of the nodes;
+/// eg for breadcrumbs or tag lists. `Node::Empty`s are removed, so they don't get separators.
+pub fn intersperse<Ms>(nodes: Vec<Node<Ms>>, separator: impl Fn() -> Node<Ms>) -> Vec<Node<Ms>> {
+    let mut result = Vec::with_capacity(nodes.len() * 2);
+    for node in nodes.into_iter().filter(|node| !node.is_empty()) {
+        if !result.is_empty() {
+            result.push(separator());
+        }
+        result.push(node);
+    }
+    result
+}
+
+/// Render the items into elements with keys (see `El::key`), so their DOM nodes are reused
+/// when the items are reordered, added or removed; eg
+/// `ul![keyed_list(&model.todos, |todo| todo.id, |todo| li![todo.title.as_str()])]`.
+pub fn keyed_list<T, K: ToString, Ms>(
+    items: impl IntoIterator<Item = T>,
+    key: impl Fn(&T) -> K,
+    render: impl Fn(&T) -> El<Ms>,
+) -> Vec<Node<Ms>> {
+    items
+        .into_iter()
+        .map(|item| {
+            let mut el = render(&item);
+            el.key(key(&item));
+            Node::Element(el)
+        })
+        .collect()
+}
+
+/// `None` is rendered as `Node::Empty`.
+impl<Ms> From<Option<Node<Ms>>> for Node<Ms> {
+    fn from(node: Option<Node<Ms>>) -> Self {
+        node.unwrap_or(Node::Empty)
+    }
+}
+
+/// `None` is rendered as `Node::Empty`.
+impl<Ms> From<Option<El<Ms>>> for Node<Ms> {
+    fn from(el: Option<El<Ms>>) -> Self {
+        el.map_or(Node::Empty, Node::Element)
+    }
+}
+
 impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for Node<Ms> {
     type SelfWithOtherMs = Node<OtherMs>;
     /// See note on impl for El
@@ -174,6 +277,10 @@ impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for Node<Ms> {
             Node::Element(el) => Node::Element(el.map_msg(f)),
             Node::Text(text) => Node::Text(text),
             Node::Empty => Node::Empty,
+            Node::Portal { target, children } => Node::Portal {
+                target,
+                children: children.map_msg(f),
+            },
         }
     }
 }
@@ -1,10 +1,28 @@
-use super::super::{At, AtValue, Attrs, CSSValue, Listener, Node, St, Style, Tag, Text};
+use super::super::{
+    debug_tree, diff, html, json, links, markdown, snapshot, validate, values, At, AtValue, Attrs,
+    AutoCapitalize, Autocomplete, CSSValue, Decoding, DialogState, Dir, Easing, EnterKeyHint,
+    FormMethod, InputMode, LinkOptions, Listener, ListenerOptions, MarkdownOptions, Node, Patch,
+    Politeness, PopoverMode, Props, Role, St, Style, Tag, Template, Text, ValidationWarning,
+};
 use crate::app::MessageMapper;
 use crate::browser::{
-    dom::{virtual_dom_bridge, LifecycleHooks, Namespace},
+    dom::{
+        el_ref::ElRef,
+        event_handler::{self, form_submit_ev, raw_ev},
+        key_combo, lifecycle_hooks, observers,
+        scoped_styles::ScopedRule,
+        virtual_dom_bridge, LifecycleHooks, Namespace,
+    },
     util,
 };
+use crate::dom_entity_names::Ev;
 use std::borrow::Cow;
+use std::collections::{hash_map::DefaultHasher, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::iter::Peekable;
+use std::rc::Rc;
+use std::time::Duration;
+use wasm_bindgen::JsCast;
 
 /// A component in our virtual DOM.
 /// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/Element)
@@ -23,6 +41,17 @@ pub struct El<Ms: 'static> {
     pub node_ws: Option<web_sys::Node>,
     pub namespace: Option<Namespace>,
     pub hooks: LifecycleHooks<Ms>,
+    /// Identity of the element among its siblings; see `El::key`.
+    pub key: Option<String>,
+    /// See `El::auto_memo`; boxed, because few elements use it.
+    pub(crate) memo: Option<Box<Memo>>,
+}
+
+/// State of an `auto_memo` element.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Memo {
+    /// Hash of the rendered element's content; see `El::content_hash`.
+    pub content_hash: Option<u64>,
 }
 
 impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for El<Ms> {
@@ -53,6 +82,8 @@ impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for El<Ms> {
             node_ws: self.node_ws,
             namespace: self.namespace,
             hooks: self.hooks.map_msg(f),
+            key: self.key,
+            memo: self.memo,
         }
     }
 }
@@ -76,9 +107,19 @@ impl<Ms> El<Ms> {
             node_ws: None,
             namespace: None,
             hooks: LifecycleHooks::new(),
+            key: None,
+            memo: None,
         }
     }
 
+    /// Create an empty element with space reserved for `capacity` children;
+    /// useful when building long lists.
+    pub fn with_children_capacity(tag: Tag, capacity: usize) -> Self {
+        let mut el = El::empty(tag);
+        el.children.reserve(capacity);
+        el
+    }
+
     /// Create an empty SVG element, specifying only the tag
     pub fn empty_svg(tag: Tag) -> Self {
         let mut el = El::empty(tag);
@@ -89,11 +130,38 @@ impl<Ms> El<Ms> {
     // todo: Return El instead of Node here? (Same with from_html)
     /// Create elements from a markdown string.
     pub fn from_markdown(markdown: &str) -> Vec<Node<Ms>> {
-        let parser = pulldown_cmark::Parser::new(markdown);
-        let mut html_text = String::new();
-        pulldown_cmark::html::push_html(&mut html_text, parser);
+        Self::from_markdown_with_options(markdown, &MarkdownOptions::default())
+    }
 
-        Self::from_html(&html_text)
+    /// Create elements from a markdown string; see `MarkdownOptions`.
+    pub fn from_markdown_with_options(markdown: &str, options: &MarkdownOptions) -> Vec<Node<Ms>> {
+        Self::from_html_with_options(&markdown::to_html(markdown, options), &options.links)
+    }
+
+    /// Add the markdown's inline content as children, without the `<p>` wrapper of `from_markdown`;
+    /// eg `add_inline_markdown("**Free** shipping")` for labels and captions. Consecutive
+    /// paragraphs are joined with a space.
+    pub fn add_inline_markdown(&mut self, markdown: &str) -> &mut Self {
+        self.children
+            .extend(Self::from_html(&markdown::inline_to_html(markdown)));
+        self
+    }
+
+    /// Similar to `from_markdown`, but the nodes are created lazily, one top-level block
+    /// (paragraph, heading, list, ...) at a time; eg to render the beginning of a large document
+    /// first. All nodes together are the same as the ones from `from_markdown`.
+    pub fn from_markdown_blocks(markdown: &str) -> impl Iterator<Item = Node<Ms>> + '_ {
+        let mut events = pulldown_cmark::Parser::new(markdown).peekable();
+        let mut nodes = VecDeque::new();
+        std::iter::from_fn(move || loop {
+            if let Some(node) = nodes.pop_front() {
+                return Some(node);
+            }
+            let block = next_markdown_block(&mut events)?;
+            let mut html_text = String::new();
+            pulldown_cmark::html::push_html(&mut html_text, block.into_iter());
+            nodes.extend(Self::from_html(&html_text));
+        })
     }
 
     /// Create elements from an HTML string.
@@ -120,12 +188,383 @@ impl<Ms> El<Ms> {
         result
     }
 
+    /// Create elements from an SVG string; eg an icon sprite or a user-provided image.
+    /// Unlike `from_html`, the string is parsed inside an `svg` element, so top-level elements
+    /// without an `svg` root (eg `<path>`s) get the SVG namespace too.
+    pub fn from_svg_string(svg: &str) -> Vec<Node<Ms>> {
+        let wrapper = util::document()
+            .create_element_ns(Some(Namespace::Svg.as_str()), "svg")
+            .expect("Problem creating web-sys element with namespace");
+        wrapper.set_inner_html(svg);
+
+        let children = wrapper.child_nodes();
+        (0..children.length())
+            .filter_map(|i| children.get(i))
+            .filter_map(|child| virtual_dom_bridge::node_from_ws(&child))
+            .collect()
+    }
+
+    /// Create an element from an existing DOM element (eg rendered by the server), including
+    /// its children, with their DOM nodes assigned; so when it's patched as the old vdom,
+    /// the DOM nodes are reused instead of recreated. Returns `None` for non-element nodes.
+    ///
+    /// Note: Listeners of the DOM nodes aren't imported; the new vdom's ones are attached.
+    pub fn from_dom_node(node: &web_sys::Node) -> Option<Self> {
+        let mut el = Self::from(node.dyn_ref::<web_sys::Element>()?);
+        virtual_dom_bridge::adopt_ws_nodes(&mut el, node);
+        Some(el)
+    }
+
+    /// Create elements from an HTML string and rewrite their links; see `LinkOptions`.
+    pub fn from_html_with_options(html: &str, options: &LinkOptions) -> Vec<Node<Ms>> {
+        let mut nodes = Self::from_html(html);
+        links::rewrite_links(&mut nodes, options);
+        nodes
+    }
+
+    /// Compare two elements, including their children, and return the operations
+    /// needed to change `old` into `new`. The DOM isn't touched.
+    pub fn diff(old: &El<Ms>, new: &El<Ms>) -> Vec<Patch> {
+        diff::diff_els(old, new)
+    }
+
+    /// Create an element from its JSON representation. See module `virtual_dom::json`
+    /// for the format. Listeners can't be represented in JSON, so they have to be added separately.
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, json::FromJsonError> {
+        json::el_from_json(value)
+    }
+
+    /// Create a JSON representation of the element, including its children. Listeners and
+    /// lifecycle hooks are omitted. See module `virtual_dom::json` for the format.
+    pub fn to_json(&self) -> serde_json::Value {
+        json::el_to_json(self)
+    }
+
+    /// Check the element and its descendants for obvious mistakes - attributes on elements
+    /// where they have no effect (eg `href` on a `div`), children of void elements, more listeners
+    /// of the same event on an element and duplicate ids. Only HTML elements are checked
+    /// for attributes. The check runs only in debug builds; it always returns no warnings
+    /// in release builds.
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        if cfg!(debug_assertions) {
+            validate::validate_el(self)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Check that the element's children have only the allowed tags; eg that a `Tabs` component
+    /// gets only `Tab`s. Returns `ValidationWarning::DisallowedChild` for the other child
+    /// elements; text and empty nodes are ignored. The check is compiled only into debug
+    /// builds; it always returns no warnings in release builds.
+    pub fn assert_children_tags(&self, allowed: &[Tag]) -> Vec<ValidationWarning> {
+        #[cfg(debug_assertions)]
+        {
+            validate::check_children_tags(self, allowed)
+        }
+        #[cfg(not(debug_assertions))]
+        {
+            let _ = allowed;
+            Vec::new()
+        }
+    }
+
+    /// Serialize the element, including its children, into HTML. Listeners, lifecycle hooks
+    /// and children of portals are omitted.
+    pub fn to_html_string(&self) -> String {
+        html::el_to_html(self)
+    }
+
+    /// Serialize the element and its descendants deterministically, for golden tests of views:
+    /// one node per line, sorted attributes and style properties, whitespace in texts collapsed
+    /// and blank texts omitted. Listeners are shown as `on:{event}` markers after
+    /// the attributes.
+    ///
+    /// ```text
+    /// <ul class="items" id="list" on:click>
+    ///   <li>first item</li>
+    /// </ul>
+    /// ```
+    pub fn snapshot(&self) -> String {
+        snapshot::el_to_snapshot(self)
+    }
+
+    /// Outline the element and its descendants (up to `max_depth` levels below it),
+    /// one node per line: `tag#id.class`, shortened text in quotes; eg for test failures or logs.
+    ///
+    /// ```text
+    /// div.container
+    ///   ul
+    ///     li#item-1.active
+    ///       ... (1 more)
+    /// ```
+    pub fn debug_tree(&self, max_depth: usize) -> String {
+        debug_tree::el_to_debug_tree(self, max_depth)
+    }
+
+    /// The rendered element's position and size relative to the viewport
+    /// (`getBoundingClientRect()`). Returns `None` if the element hasn't been rendered;
+    /// read it after render, eg through `el_ref` (see `ElRef::bounding_rect`).
+    pub fn bounding_rect(&self) -> Option<web_sys::DomRect> {
+        self.node_ws
+            .as_ref()
+            .and_then(|node_ws| node_ws.dyn_ref::<web_sys::Element>())
+            .map(web_sys::Element::get_bounding_client_rect)
+    }
+
+    /// The nodes assigned to the rendered `slot` element (including the ones of nested slots);
+    /// eg in a custom element's shadow DOM. Returns no nodes if the element isn't a rendered
+    /// `slot`. Listen to `slotchange_ev` to get notified when they change.
+    pub fn assigned_nodes(&self) -> Vec<web_sys::Node> {
+        self.node_ws
+            .as_ref()
+            .and_then(|node_ws| node_ws.dyn_ref::<web_sys::HtmlSlotElement>())
+            .map(event_handler::assigned_nodes)
+            .unwrap_or_default()
+    }
+
+    /// Get the inner HTML - the live one from the DOM if the element has been rendered,
+    /// so it includes changes made by other scripts; otherwise the serialized vdom children.
+    /// Returns `None` if the element's DOM node isn't an element.
+    pub fn inner_html(&self) -> Option<String> {
+        match &self.node_ws {
+            Some(node_ws) => node_ws
+                .dyn_ref::<web_sys::Element>()
+                .map(web_sys::Element::inner_html),
+            None => Some(html::children_to_html(&self.children)),
+        }
+    }
+
     /// Add a new child to the element
     pub fn add_child(&mut self, element: Node<Ms>) -> &mut Self {
         self.children.push(element);
         self
     }
 
+    /// Add the child only if `cond` is `true`. The child is created in both cases;
+    /// use `Node::from(Option<El>)` with `then` if creating it is expensive.
+    pub fn add_child_if(&mut self, cond: bool, node: Node<Ms>) -> &mut Self {
+        if cond {
+            self.children.push(node);
+        }
+        self
+    }
+
+    /// Add the nodes to the end of the element's children; eg `extend_children(items.iter().map(view_item))`.
+    pub fn extend_children(&mut self, nodes: impl IntoIterator<Item = Node<Ms>>) -> &mut Self {
+        self.children.extend(nodes);
+        self
+    }
+
+    /// Parse an HTML string and add the resulting nodes to the end of the element's children.
+    /// Parsing is the same as in `from_html`.
+    pub fn append_html(&mut self, html: &str) -> &mut Self {
+        self.children.extend(Self::from_html(html));
+        self
+    }
+
+    /// Similar to `append_html`, but the parsed nodes are inserted before the existing children.
+    pub fn prepend_html(&mut self, html: &str) -> &mut Self {
+        self.children.splice(0..0, Self::from_html(html));
+        self
+    }
+
+    /// Check the element's tag; custom tags are compared by their lowercase names,
+    /// so eg `Tag::Custom("DIV".into())` is the same tag as `Tag::Div`. See `Tag::name`.
+    pub fn is_tag(&self, tag: &Tag) -> bool {
+        self.tag.name() == tag.name()
+    }
+
+    /// Replace each direct child with `f(child)`; eg to wrap every list item in a decorator.
+    /// Grandchildren aren't visited - see `map_children_deep`.
+    pub fn map_children(&mut self, f: impl FnMut(Node<Ms>) -> Node<Ms>) -> &mut Self {
+        self.children = std::mem::take(&mut self.children)
+            .into_iter()
+            .map(f)
+            .collect();
+        self
+    }
+
+    /// Similar to `map_children`, but all descendants (including portals' children) are mapped.
+    /// Nodes are mapped bottom-up - `f` gets a node whose children have already been mapped.
+    pub fn map_children_deep(&mut self, mut f: impl FnMut(Node<Ms>) -> Node<Ms>) -> &mut Self {
+        fn map_nodes<Ms>(
+            nodes: Vec<Node<Ms>>,
+            f: &mut impl FnMut(Node<Ms>) -> Node<Ms>,
+        ) -> Vec<Node<Ms>> {
+            nodes
+                .into_iter()
+                .map(|node| {
+                    let node = match node {
+                        Node::Element(mut el) => {
+                            el.children = map_nodes(std::mem::take(&mut el.children), f);
+                            Node::Element(el)
+                        }
+                        Node::Portal { target, children } => Node::Portal {
+                            target,
+                            children: map_nodes(children, f),
+                        },
+                        node => node,
+                    };
+                    f(node)
+                })
+                .collect()
+        }
+        self.children = map_nodes(std::mem::take(&mut self.children), &mut f);
+        self
+    }
+
+    /// Create an element from the template and `fill` it; see `Template::instantiate`.
+    pub fn from_template(template: &Template<Ms>, fill: impl FnOnce(&mut El<Ms>)) -> Self
+    where
+        Ms: Clone,
+    {
+        template.instantiate(fill)
+    }
+
+    /// Remove the listeners of the element and, recursively, of its descendants (including
+    /// the portals' ones), and the messages of their lifecycle hooks, so the tree is inert -
+    /// it can't send messages; eg before cloning it, to make dropping the interactivity explicit.
+    /// The hooks' DOM actions are kept. See `clone_with` to rebuild the listeners instead.
+    pub fn strip_listeners(&mut self) -> &mut Self {
+        fn strip_nodes<Ms>(nodes: &mut [Node<Ms>]) {
+            for node in nodes {
+                match node {
+                    Node::Element(el) => {
+                        el.strip_listeners();
+                    }
+                    Node::Portal { children, .. } => strip_nodes(children),
+                    Node::Text(_) | Node::Empty => (),
+                }
+            }
+        }
+        self.listeners.clear();
+        let hooks = &mut self.hooks;
+        if let Some(did_mount) = &mut hooks.did_mount {
+            did_mount.message = None;
+        }
+        if let Some(did_update) = &mut hooks.did_update {
+            did_update.message = None;
+        }
+        if let Some(will_unmount) = &mut hooks.will_unmount {
+            will_unmount.message = None;
+        }
+        strip_nodes(&mut self.children);
+        self
+    }
+
+    /// Clone the element and its descendants, replacing listeners of each element
+    /// with the ones returned by `rebuild` (it gets the original element). Unlike with `clone`,
+    /// the listeners don't have to be cloneable, so they can be created again from the model.
+    /// Lifecycle hooks aren't cloned in both cases and, like with `clone`, the clone doesn't
+    /// reference the DOM nodes.
+    pub fn clone_with(&self, rebuild: impl Fn(&El<Ms>) -> Vec<Listener<Ms>>) -> Self
+    where
+        Ms: Clone,
+    {
+        fn clone_nodes<Ms: Clone>(
+            nodes: &[Node<Ms>],
+            rebuild: &impl Fn(&El<Ms>) -> Vec<Listener<Ms>>,
+        ) -> Vec<Node<Ms>> {
+            nodes
+                .iter()
+                .map(|node| match node {
+                    Node::Element(el) => Node::Element(clone_el(el, rebuild)),
+                    Node::Portal { target, children } => Node::Portal {
+                        target: target.clone(),
+                        children: clone_nodes(children, rebuild),
+                    },
+                    Node::Text(text) => {
+                        let mut text = text.clone();
+                        text.strip_ws_node();
+                        Node::Text(text)
+                    }
+                    Node::Empty => Node::Empty,
+                })
+                .collect()
+        }
+        fn clone_el<Ms: Clone>(
+            el: &El<Ms>,
+            rebuild: &impl Fn(&El<Ms>) -> Vec<Listener<Ms>>,
+        ) -> El<Ms> {
+            El {
+                tag: el.tag.clone(),
+                attrs: el.attrs.clone(),
+                style: el.style.clone(),
+                children: clone_nodes(&el.children, rebuild),
+                node_ws: None,
+                listeners: rebuild(el),
+                namespace: el.namespace.clone(),
+                hooks: LifecycleHooks::new(),
+                key: el.key.clone(),
+                memo: el.memo.as_ref().map(|_| Box::default()),
+            }
+        }
+        clone_el(self, &rebuild)
+    }
+
+    /// Add the element as the last child of the `parent` and return the `parent`;
+    /// eg `el.wrap(container)` for decorating components.
+    pub fn wrap(self, mut parent: El<Ms>) -> El<Ms> {
+        parent.children.push(Node::Element(self));
+        parent
+    }
+
+    /// Replace descendant `div`s and `span`s that only wrap a single child with that child;
+    /// eg to shrink machine-generated trees. Wrappers with attributes, style, listeners,
+    /// lifecycle hooks or a namespace are never collapsed. The element itself is kept.
+    ///
+    /// It changes the structure of the tree (and so CSS selectors can stop matching),
+    /// so it's never applied automatically.
+    pub fn collapse_redundant_wrappers(mut self) -> Self {
+        fn collapse<Ms>(node: Node<Ms>) -> Node<Ms> {
+            match node {
+                Node::Element(el) => {
+                    let mut el = el.collapse_redundant_wrappers();
+                    if el.is_redundant_wrapper() {
+                        el.children.remove(0)
+                    } else {
+                        Node::Element(el)
+                    }
+                }
+                Node::Portal { target, children } => Node::Portal {
+                    target,
+                    children: children.into_iter().map(collapse).collect(),
+                },
+                node => node,
+            }
+        }
+        self.children = std::mem::take(&mut self.children)
+            .into_iter()
+            .map(collapse)
+            .collect();
+        self
+    }
+
+    fn is_redundant_wrapper(&self) -> bool {
+        let hooks = &self.hooks;
+        (self.tag == Tag::Div || self.tag == Tag::Span)
+            && self.children.len() == 1
+            && self.attrs.vals.is_empty()
+            && self.style.vals.is_empty()
+            && self.listeners.is_empty()
+            && self.namespace.is_none()
+            && hooks.did_mount.is_none()
+            && hooks.did_update.is_none()
+            && hooks.will_unmount.is_none()
+            && hooks.leave_animation.is_none()
+            && !hooks.preserve_scroll
+    }
+
+    /// Swap the children at positions `i` and `j`; eg for drag-reorder UIs.
+    ///
+    /// # Panics
+    /// Panics if `i` or `j` is out of bounds.
+    pub fn swap_children(&mut self, i: usize, j: usize) -> &mut Self {
+        self.children.swap(i, j);
+        self
+    }
+
     /// Add an attribute (eg class, or href)
     pub fn add_attr(
         &mut self,
@@ -138,6 +577,25 @@ impl<Ms> El<Ms> {
         self
     }
 
+    /// Replace all attributes; see `merge_attrs` to keep the existing ones.
+    pub fn set_attrs(&mut self, attrs: Attrs) -> &mut Self {
+        self.attrs = attrs;
+        self
+    }
+
+    /// Add the attributes; see `Attrs::merge` for how the conflicting values are combined.
+    pub fn merge_attrs(&mut self, attrs: Attrs) -> &mut Self {
+        self.attrs.merge(attrs);
+        self
+    }
+
+    /// Transform the attribute values of the element and, recursively, of all its descendants
+    /// (including the portals' ones); eg to make the relative URLs absolute after `from_html`.
+    pub fn map_attrs(&mut self, mut f: impl FnMut(&At, &mut AtValue)) -> &mut Self {
+        map_attrs_of_el(self, &mut f);
+        self
+    }
+
     /// Add a class. May be cleaner than `add_attr`
     pub fn add_class(&mut self, name: impl Into<Cow<'static, str>>) -> &mut Self {
         let name = name.into();
@@ -157,18 +615,916 @@ impl<Ms> El<Ms> {
         self
     }
 
+    /// Get the classes from the `class` attribute; empty if there isn't one.
+    pub fn classes(&self) -> Vec<&str> {
+        match self.attrs.vals.get(&At::Class) {
+            Some(AtValue::Some(classes)) => classes.split_whitespace().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get the number of classes; see `classes`.
+    pub fn class_count(&self) -> usize {
+        self.classes().len()
+    }
+
+    /// Get the number of attributes (the `class` attribute counts as one).
+    pub fn attr_count(&self) -> usize {
+        self.attrs.vals.len()
+    }
+
+    /// Get the number of style properties.
+    pub fn style_count(&self) -> usize {
+        self.style.vals.len()
+    }
+
+    /// Make the element a popover, shown and hidden by the browser.
+    /// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/Popover_API)
+    pub fn popover(&mut self, mode: PopoverMode) -> &mut Self {
+        self.attrs.add(At::Popover, mode.as_str());
+        self
+    }
+
+    /// Make the element (a `button` or an `input`) toggle the popover with the given id.
+    pub fn popover_target(&mut self, id: &str) -> &mut Self {
+        self.attrs.add(At::PopoverTarget, id);
+        self
+    }
+
+    /// Create a `dialog` that is shown or closed by calling its methods `show()`, `showModal()`
+    /// and `close()` whenever the `state` changes between renders.
+    /// The state is also rendered as the attribute `data-dialog`, so the changes are diffed.
+    ///
+    /// The browser closes modal dialogs on Esc (and `form[method=dialog]` closes them too),
+    /// so handle `on_dialog_close` and update your model; otherwise the dialog isn't shown again
+    /// until its state changes. Switching an open dialog between `Open` and `Modal` closes it
+    /// and shows it again, so the `close` event is fired as well.
+    pub fn dialog(state: DialogState) -> Self {
+        let mut dialog = El::empty(Tag::Dialog);
+        dialog
+            .add_attr("data-dialog", state.as_str())
+            .on_insert(move |node| virtual_dom_bridge::sync_dialog(node, state))
+            .on_update(move |node| virtual_dom_bridge::sync_dialog(node, state));
+        dialog
+    }
+
+    /// Set the element's content to the raw HTML, without parsing it into vdom nodes; eg for
+    /// a large document rendered by the server. The HTML's hash is rendered as the attribute
+    /// `data-html-hash`, so `innerHTML` is rewritten only when the HTML changes.
+    /// The element shouldn't have other children.
+    pub fn set_inner_html(&mut self, html: &str) -> &mut Self {
+        let mut hasher = DefaultHasher::new();
+        html.hash(&mut hasher);
+        let hash = format!("{:x}", hasher.finish());
+
+        let (inserted_html, inserted_hash) = (html.to_owned(), hash.clone());
+        let (updated_html, updated_hash) = (html.to_owned(), hash.clone());
+        self.add_attr("data-html-hash", hash)
+            .on_insert(move |node| {
+                virtual_dom_bridge::sync_inner_html(node, &inserted_html, &inserted_hash)
+            })
+            .on_update(move |node| {
+                virtual_dom_bridge::sync_inner_html(node, &updated_html, &updated_hash)
+            })
+    }
+
+    /// Add a listener with its options in one call; eg when the options are computed.
+    /// The handler gets the raw event, like in `raw_ev`. See also `Listener::with_options`.
+    pub fn on_with_options(
+        &mut self,
+        trigger: impl ToString + Copy,
+        options: ListenerOptions,
+        handler: impl FnOnce(web_sys::Event) -> Ms + 'static + Clone,
+    ) -> &mut Self {
+        self.add_listener(raw_ev(trigger, handler).with_options(options))
+    }
+
+    /// Send a message with the dialog's `returnValue` when the (`dialog`) element is closed.
+    pub fn on_dialog_close(
+        &mut self,
+        handler: impl FnOnce(String) -> Ms + 'static + Clone,
+    ) -> &mut Self {
+        self.add_listener(raw_ev(Ev::Close, move |event| {
+            let return_value = event
+                .target()
+                .and_then(|target| target.dyn_into::<web_sys::HtmlDialogElement>().ok())
+                .map(|dialog| dialog.return_value())
+                .unwrap_or_default();
+            (handler.clone())(return_value)
+        }))
+    }
+
+    /// Load the image or iframe only when it's near the viewport (`loading="lazy"`).
+    /// In debug builds, an error is logged if the element isn't an `img` or `iframe`.
+    pub fn lazy_load(&mut self) -> &mut Self {
+        self.warn_unless_tag(&[Tag::Img, Tag::Iframe], "lazy_load");
+        self.attrs.add(At::Loading, "lazy");
+        self
+    }
+
+    /// Set how the image should be decoded (`decoding`); eg `Decoding::Async` for large images.
+    /// In debug builds, an error is logged if the element isn't an `img`.
+    pub fn decoding(&mut self, decoding: Decoding) -> &mut Self {
+        self.warn_unless_tag(&[Tag::Img], "decoding");
+        self.attrs.add(At::Decoding, decoding.as_str());
+        self
+    }
+
+    /// Set the `width` and `height` attributes in pixels (without units); eg the canvas
+    /// resolution, which CSS `width`/`height` would only scale. In debug builds, an error
+    /// is logged for elements without these attributes (only `canvas`, `embed`, `iframe`, `img`,
+    /// `input`, `object`, `svg` and `video` have them).
+    pub fn size(&mut self, width: u32, height: u32) -> &mut Self {
+        self.warn_unless_tag(
+            &[
+                Tag::Canvas,
+                Tag::Embed,
+                Tag::Iframe,
+                Tag::Img,
+                Tag::Input,
+                Tag::Object,
+                Tag::Svg,
+                Tag::Video,
+            ],
+            "size",
+        );
+        self.attrs.add(At::Width, width.to_string());
+        self.attrs.add(At::Height, height.to_string());
+        self
+    }
+
+    fn warn_unless_tag(&self, tags: &[Tag], method: &str) {
+        if cfg!(debug_assertions) && !tags.contains(&self.tag) {
+            crate::error(&format!(
+                "`El::{}` has no effect on `{}` elements",
+                method,
+                self.tag.name()
+            ));
+        }
+    }
+
+    /// Set the language of the element's content (`lang`) as a BCP 47 tag (eg `"en"` or `"pt-BR"`),
+    /// so screen readers pronounce it correctly and browsers hyphenate it; eg for a quote
+    /// in another language than the page. In debug builds, an error is logged for malformed tags.
+    pub fn lang(&mut self, tag: &str) -> &mut Self {
+        if cfg!(debug_assertions) && !values::is_language_tag(tag) {
+            crate::error(&format!("Invalid language tag: `{}`", tag));
+        }
+        self.attrs.add(At::Lang, tag);
+        self
+    }
+
+    /// Set the direction of the element's text (`dir`); eg `Dir::Auto` for user-generated
+    /// content. See `util::set_document_dir` for the direction of the whole page.
+    pub fn dir(&mut self, dir: Dir) -> &mut Self {
+        self.attrs.add(At::Dir, dir.as_str());
+        self
+    }
+
+    /// Associate the form control with the `form` with the given id (`form`); eg for controls
+    /// rendered outside of their form because of the page layout.
+    pub fn form_id(&mut self, id: &str) -> &mut Self {
+        self.attrs.add(At::Form, id);
+        self
+    }
+
+    /// Submit the form to the URL when the submit button is used (`formaction`),
+    /// instead of the form's `action`.
+    pub fn form_action(&mut self, url: &str) -> &mut Self {
+        self.attrs.add(At::FormAction, url);
+        self
+    }
+
+    /// Submit the form with the method when the submit button is used (`formmethod`),
+    /// instead of the form's `method`.
+    pub fn form_method(&mut self, method: FormMethod) -> &mut Self {
+        self.attrs.add(At::FormMethod, method.as_str());
+        self
+    }
+
+    /// Set the virtual keyboard shown for the field (`inputmode`).
+    pub fn input_mode(&mut self, mode: InputMode) -> &mut Self {
+        self.attrs.add(At::InputMode, mode.as_str());
+        self
+    }
+
+    /// Set the label of the virtual keyboard's enter key (`enterkeyhint`).
+    pub fn enter_key_hint(&mut self, hint: EnterKeyHint) -> &mut Self {
+        self.attrs.add(At::EnterKeyHint, hint.as_str());
+        self
+    }
+
+    /// Set the kind of data the browser may fill in (`autocomplete`).
+    pub fn autocomplete(&mut self, autocomplete: Autocomplete) -> &mut Self {
+        self.attrs.add(At::AutoComplete, autocomplete.as_str());
+        self
+    }
+
+    /// Set how the typed text is capitalized on virtual keyboards (`autocapitalize`).
+    pub fn autocapitalize(&mut self, autocapitalize: AutoCapitalize) -> &mut Self {
+        self.attrs.add(At::AutoCapitalize, autocapitalize.as_str());
+        self
+    }
+
+    /// Enable or disable autocorrection (`autocorrect`; Safari only).
+    pub fn autocorrect(&mut self, enabled: bool) -> &mut Self {
+        self.attrs
+            .add(At::AutoCorrect, if enabled { "on" } else { "off" });
+        self
+    }
+
+    /// Enable or disable spell checking (`spellcheck`).
+    pub fn spellcheck(&mut self, enabled: bool) -> &mut Self {
+        self.attrs
+            .add(At::SpellCheck, if enabled { "true" } else { "false" });
+        self
+    }
+
+    /// Set the `data-testid` attribute used by integration tests to find the element.
+    /// It's emitted only in debug builds (`debug_assertions`) or with the `testing` feature;
+    /// otherwise it's a no-op, so test hooks don't get into the production DOM.
+    pub fn test_id(&mut self, id: &str) -> &mut Self {
+        if cfg!(any(debug_assertions, feature = "testing")) {
+            self.add_attr("data-testid", id);
+        }
+        self
+    }
+
+    /// Set the `autofocus` attribute. Browsers use it when the page loads (eg for server-rendered
+    /// HTML) and when a `dialog` or popover containing the element opens; Seed also focuses
+    /// the element when it creates its DOM node. Adding the attribute to an already rendered
+    /// element doesn't focus it. See `focus_on_insert` to focus the element without the attribute.
+    pub fn autofocus_attr(&mut self) -> &mut Self {
+        self.attrs.add(At::AutoFocus, AtValue::None);
+        self
+    }
+
+    /// Focus the element whenever it's inserted into the DOM (eg with a modal that's rendered
+    /// only when open), using an `on_insert` hook. Unlike `autofocus_attr`, it doesn't change
+    /// the element's HTML, so the browser doesn't move focus to it when eg an enclosing
+    /// `dialog` opens.
+    pub fn focus_on_insert(&mut self) -> &mut Self {
+        self.on_insert(|node| {
+            let is_focused = node
+                .dyn_ref::<web_sys::HtmlElement>()
+                .is_some_and(|element| element.focus().is_ok());
+            if !is_focused {
+                crate::error("Problem focusing the inserted element");
+            }
+        })
+    }
+
+    /// Set the element's position in the keyboard navigation (`tabindex`); `0` makes it
+    /// focusable in the document order, `-1` focusable only programmatically.
+    pub fn tab_index(&mut self, index: i32) -> &mut Self {
+        self.attrs.add(At::TabIndex, index);
+        self
+    }
+
+    /// Make the element focusable by the keyboard; eg a custom button (`tabindex="0"`).
+    pub fn focusable(&mut self) -> &mut Self {
+        self.tab_index(0)
+    }
+
+    /// Remove the element from the tab order, but keep it focusable by `focus()` (`tabindex="-1"`).
+    pub fn not_tabbable(&mut self) -> &mut Self {
+        self.tab_index(-1)
+    }
+
+    /// Make the element's children a single tab stop (a "roving tabindex"); eg for menus
+    /// and toolbars. The `active`-th child element is `focusable`, the other ones are
+    /// `not_tabbable`; text and empty children aren't counted. Move the `active` index
+    /// on arrow keys (see `on_key`) and focus the new active child (see `el_ref`).
+    pub fn roving_tab_index(&mut self, active: usize) -> &mut Self {
+        let items = self.children.iter_mut().filter_map(|child| match child {
+            Node::Element(el) => Some(el),
+            _ => None,
+        });
+        for (index, item) in items.enumerate() {
+            if index == active {
+                item.focusable();
+            } else {
+                item.not_tabbable();
+            }
+        }
+        self
+    }
+
+    /// Set the `disabled` attribute if `disabled` is `true`, otherwise remove it; eg to disable
+    /// a submit button while the form is being sent. The attribute is reflected
+    /// to the `disabled` property, so the control is really disabled.
+    pub fn disabled_if(&mut self, disabled: bool) -> &mut Self {
+        self.attrs.add(
+            At::Disabled,
+            if disabled {
+                AtValue::None
+            } else {
+                AtValue::Ignored
+            },
+        );
+        self
+    }
+
+    /// Show or hide the element with the `hidden` attribute. Unlike rendering `Node::Empty`
+    /// instead, the hidden element stays in the DOM, so it keeps its state (eg the text
+    /// typed into inputs, scroll position or a playing video). Toggling it patches the element,
+    /// so its `did_update` hook is called, but not `did_mount` or `will_unmount`.
+    /// Note: A `display` style set on the element overrides `hidden`.
+    pub fn set_visible(&mut self, visible: bool) -> &mut Self {
+        self.attrs.add(
+            At::Hidden,
+            if visible {
+                AtValue::Ignored
+            } else {
+                AtValue::None
+            },
+        );
+        self
+    }
+
+    /// Mark the element as being updated (`aria-busy`), so screen readers wait
+    /// with announcing its content.
+    pub fn aria_busy(&mut self, busy: bool) -> &mut Self {
+        self.attrs
+            .add(At::AriaBusy, if busy { "true" } else { "false" });
+        self
+    }
+
+    /// Mark whether the element controlled by this one (eg by a disclosure button
+    /// or a menu button) is expanded (`aria-expanded`).
+    pub fn aria_expanded(&mut self, expanded: bool) -> &mut Self {
+        self.attrs
+            .add(At::AriaExpanded, if expanded { "true" } else { "false" });
+        self
+    }
+
+    /// Reference the element(s) with the given id(s) controlled by this one (`aria-controls`).
+    pub fn aria_controls(&mut self, id: &str) -> &mut Self {
+        self.attrs.add(At::AriaControls, id);
+        self
+    }
+
+    /// Reference the element(s) with the given id(s) that describe this one (`aria-describedby`).
+    pub fn aria_describedby(&mut self, id: &str) -> &mut Self {
+        self.attrs.add(At::AriaDescribedBy, id);
+        self
+    }
+
+    /// Reference the element(s) with the given id(s) that label this one (`aria-labelledby`).
+    pub fn aria_labelledby(&mut self, id: &str) -> &mut Self {
+        self.attrs.add(At::AriaLabelledBy, id);
+        self
+    }
+
+    /// Set how screen readers announce updates of the element's content (`aria-live`).
+    pub fn aria_live(&mut self, politeness: Politeness) -> &mut Self {
+        self.attrs.add(At::AriaLive, politeness.as_str());
+        self
+    }
+
+    /// Set the element's ARIA role (`role`). Prefer semantic elements that imply the role;
+    /// see `El::landmark`.
+    pub fn role(&mut self, role: Role) -> &mut Self {
+        self.attrs.add(At::Role, role.as_str());
+        self
+    }
+
+    /// Create the semantic element for the role; eg `nav` for `Role::Navigation`.
+    /// The `role` attribute is set only if the element doesn't always imply it - `header`
+    /// and `footer` are landmarks only outside of `article`, `aside`, `main`, `nav` and `section`,
+    /// `section` and `form` only with an accessible name. Roles without
+    /// a semantic element (eg `Role::Search`) are set on a `div`.
+    pub fn landmark(role: Role) -> Self {
+        let (tag, implicit_role) = match role {
+            Role::Banner => (Tag::Header, false),
+            Role::Navigation => (Tag::Nav, true),
+            Role::Main => (Tag::Main, true),
+            Role::ContentInfo => (Tag::Footer, false),
+            Role::Complementary => (Tag::Aside, true),
+            Role::Region => (Tag::Section, false),
+            Role::Form => (Tag::Form, false),
+            Role::Search | Role::Alert | Role::Status => (Tag::Div, false),
+        };
+        let mut el = El::empty(tag);
+        if !implicit_role {
+            el.role(role);
+        }
+        el
+    }
+
+    /// A `header` with the role `banner`; see `El::landmark`.
+    pub fn banner_landmark() -> Self {
+        Self::landmark(Role::Banner)
+    }
+
+    /// A `nav`; see `El::landmark`.
+    pub fn nav_landmark() -> Self {
+        Self::landmark(Role::Navigation)
+    }
+
+    /// A `main`; see `El::landmark`.
+    pub fn main_landmark() -> Self {
+        Self::landmark(Role::Main)
+    }
+
+    /// A `footer` with the role `contentinfo`; see `El::landmark`.
+    pub fn content_info_landmark() -> Self {
+        Self::landmark(Role::ContentInfo)
+    }
+
+    /// An `aside`; see `El::landmark`.
+    pub fn complementary_landmark() -> Self {
+        Self::landmark(Role::Complementary)
+    }
+
+    /// Create a `button` (`type="button"`) that shows and hides the element with the `target_id`;
+    /// eg an accordion header. Render it with the current `expanded` state and toggle
+    /// the target (eg with `set_visible`) in its click handler.
+    pub fn disclosure_button(target_id: &str, expanded: bool) -> Self {
+        let mut button = El::empty(Tag::Button);
+        button.attrs.add(At::Type, "button");
+        button.aria_expanded(expanded).aria_controls(target_id);
+        button
+    }
+
+    /// Create a `div` whose content updates are announced by screen readers; eg for toasts
+    /// or form errors. The `role` is `alert` for `Politeness::Assertive`, otherwise `status`.
+    /// The region should be rendered before its content changes, otherwise
+    /// some screen readers don't announce it.
+    pub fn live_region(politeness: Politeness) -> Self {
+        let mut region = El::empty(Tag::Div);
+        region.role(match politeness {
+            Politeness::Assertive => Role::Alert,
+            Politeness::Polite | Politeness::Off => Role::Status,
+        });
+        region.attrs.add(At::AriaAtomic, "true");
+        region.aria_live(politeness);
+        region
+    }
+
+    /// Wrap a form control with its `label` and an optional description into a `div`,
+    /// with all ids and references set. The control's `id` is used if it has one,
+    /// otherwise a `unique_id` is generated. The description's id is `{id}-description`.
+    pub fn labelled_input(label: &str, description: Option<&str>, mut input: El<Ms>) -> Self {
+        let id = match input.attrs.vals.get(&At::Id) {
+            Some(AtValue::Some(id)) => id.clone(),
+            _ => {
+                let id = util::unique_id("input");
+                input.attrs.add(At::Id, &id);
+                id
+            }
+        };
+
+        let mut label_el = El::empty(Tag::Label);
+        label_el.attrs.add(At::For, &id);
+        label_el.add_text(label.to_owned());
+
+        let description_el = description.map(|description| {
+            let description_id = format!("{}-description", id);
+            input.aria_describedby(&description_id);
+            let mut description_el = El::empty(Tag::P);
+            description_el.attrs.add(At::Id, description_id);
+            description_el.add_text(description.to_owned());
+            description_el
+        });
+
+        let mut field = El::empty(Tag::Div);
+        field
+            .add_child(Node::Element(label_el))
+            .add_child(Node::Element(input));
+        if let Some(description_el) = description_el {
+            field.add_child(Node::Element(description_el));
+        }
+        field
+    }
+
     /// Add a new style (eg display, or height)
     pub fn add_style(&mut self, key: impl Into<St>, val: impl Into<CSSValue>) -> &mut Self {
         self.style.vals.insert(key.into(), val.into());
         self
     }
 
+    /// Add a style property with a value in pixels; eg `add_style_px(St::Width, 12.5)` => `12.5px`.
+    /// Infinite and `NaN` values aren't valid CSS, so the property is ignored (not rendered).
+    pub fn add_style_px(&mut self, key: impl Into<St>, px: f64) -> &mut Self {
+        self.add_style(key, unit_value(px, "px"))
+    }
+
+    /// Add a style property with a value in `rem`s; see `add_style_px`.
+    pub fn add_style_rem(&mut self, key: impl Into<St>, rem: f64) -> &mut Self {
+        self.add_style(key, unit_value(rem, "rem"))
+    }
+
+    /// Add a style property with a value in percents; eg `add_style_percent(St::Width, 50.)` => `50%`.
+    /// See `add_style_px`.
+    pub fn add_style_percent(&mut self, key: impl Into<St>, percent: f64) -> &mut Self {
+        self.add_style(key, unit_value(percent, "%"))
+    }
+
+    /// Set style properties to references of theme tokens; eg `apply_tokens(&[(St::Color, "primary")])`
+    /// => `color: var(--primary)`. See `Style::from_theme`.
+    pub fn apply_tokens(&mut self, tokens: &[(St, &str)]) -> &mut Self {
+        for (key, token) in tokens {
+            self.add_style(key.clone(), CSSValue::var(token));
+        }
+        self
+    }
+
+    /// Remove a style property and return its value; it's removed from the DOM
+    /// in the next render too.
+    pub fn remove_style(&mut self, key: impl Into<St>) -> Option<CSSValue> {
+        self.style.vals.shift_remove(&key.into())
+    }
+
+    /// Remove all style properties.
+    pub fn clear_style(&mut self) -> &mut Self {
+        self.style.vals.clear();
+        self
+    }
+
+    /// Replace the whole style; see `merge_style` to keep the existing properties.
+    pub fn set_style(&mut self, style: Style) -> &mut Self {
+        self.style = style;
+        self
+    }
+
+    /// Add the style's properties; the new values override the existing ones.
+    pub fn merge_style(&mut self, style: Style) -> &mut Self {
+        self.style.merge(style);
+        self
+    }
+
+    /// Hide the element visually, but keep it in the accessibility tree; eg for labels
+    /// of icon buttons or skip links. (`display: none` would hide it from screen readers too.)
+    pub fn visually_hidden(&mut self) -> &mut Self {
+        self.add_style(St::Position, "absolute")
+            .add_style(St::Width, "1px")
+            .add_style(St::Height, "1px")
+            .add_style(St::Padding, "0")
+            .add_style(St::Margin, "-1px")
+            .add_style(St::Overflow, "hidden")
+            .add_style(St::Clip, "rect(0, 0, 0, 0)")
+            .add_style(St::WhiteSpace, "nowrap")
+            .add_style(St::Border, "0")
+    }
+
+    /// Animate changes of the given properties (all properties if `props` is empty).
+    /// The transition starts when a property gets a different value in the next render:
+    ///
+    /// ```rust,no_run
+    /// # use seed::{prelude::*, *};
+    /// # use std::time::Duration;
+    /// # type Msg = ();
+    /// # let open = true;
+    /// let mut panel: El<Msg> = El::empty(Tag::Div);
+    /// panel
+    ///     .add_style(St::Opacity, if open { "1" } else { "0" })
+    ///     .transition(&[St::Opacity], Duration::from_millis(300), Easing::EaseInOut);
+    /// ```
+    pub fn transition(&mut self, props: &[St], duration: Duration, easing: Easing) -> &mut Self {
+        let timing = format!("{}ms {}", duration.as_millis(), easing);
+        let value = if props.is_empty() {
+            format!("all {}", timing)
+        } else {
+            props
+                .iter()
+                .map(|prop| format!("{} {}", prop.as_str(), timing))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        self.add_style(St::Transition, value)
+    }
+
+    /// Style the element while the pointer is over it (`:hover`), which inline styles can't do.
+    /// The style is injected into the document head as a rule for a class generated
+    /// from the style (eg `.seed-1f2e3d:hover`), the class is added to the element
+    /// and the rule is removed with the last element using it.
+    /// Inline styles of the element win over the rule.
+    pub fn hover_style(&mut self, style: Style) -> &mut Self {
+        self.add_scoped_rule(ScopedRule::pseudo_class(":hover", &style))
+    }
+
+    /// Style the element while it's focused (`:focus`); see `hover_style`.
+    pub fn focus_style(&mut self, style: Style) -> &mut Self {
+        self.add_scoped_rule(ScopedRule::pseudo_class(":focus", &style))
+    }
+
+    /// Style the element while it's being activated - eg pressed (`:active`); see `hover_style`.
+    pub fn active_style(&mut self, style: Style) -> &mut Self {
+        self.add_scoped_rule(ScopedRule::pseudo_class(":active", &style))
+    }
+
+    /// Style the element when the media query matches; eg `"(max-width: 600px)"`
+    /// or `"print"`. It's injected as `@media {query} { .seed-1f2e3d { .. } }` into
+    /// the document head - in the same way as `hover_style`, so the rule is removed
+    /// with the last element using it. Each query may be used once per element.
+    /// The query is inserted as it is, so a query with braces is rejected (no rule is injected).
+    pub fn media_style(&mut self, query: &str, style: Style) -> &mut Self {
+        self.add_scoped_rule(ScopedRule::media(query, &style))
+    }
+
+    fn add_scoped_rule(&mut self, rule: ScopedRule) -> &mut Self {
+        let rule = Rc::new(rule);
+        self.add_class(rule.class.clone());
+        let (insert_rule, update_rule, remove_rule) = (rule.clone(), rule.clone(), rule);
+        self.on_insert(move |node| insert_rule.insert(node))
+            .on_update(move |node| update_rule.update(node))
+            .on_remove(move |node| remove_rule.remove(node))
+    }
+
+    /// Get the events of the element's listeners, in the order they were added (an event
+    /// is repeated if it has more listeners); eg to test that a button has a `click` listener.
+    pub fn listener_triggers(&self) -> Vec<Ev> {
+        self.listeners
+            .iter()
+            .map(|listener| listener.trigger)
+            .collect()
+    }
+
+    /// Get the element's listeners of the event; eg to check that a wrapped component
+    /// didn't get another listener of the same event (see `El::validate`).
+    pub fn listeners_for(&self, trigger: Ev) -> Vec<&Listener<Ms>> {
+        self.listeners
+            .iter()
+            .filter(|listener| listener.trigger == trigger)
+            .collect()
+    }
+
     /// Add a new listener
     pub fn add_listener(&mut self, listener: Listener<Ms>) -> &mut Self {
         self.listeners.push(listener);
         self
     }
 
+    /// Move the listeners of the `other` element to the end of the element's ones; eg to combine
+    /// behavior of two configured elements. `other` is left without listeners. Listeners
+    /// whose messages can't be cloned can't be cloned either, so moving them works for any
+    /// element. Move them before the elements are rendered - the listeners of a rendered
+    /// element are attached to its DOM node.
+    pub fn merge_listeners_from(&mut self, other: &mut El<Ms>) -> &mut Self {
+        self.listeners.append(&mut other.listeners);
+        self
+    }
+
+    /// Merge in the props forwarded from a parent component: the classes are appended,
+    /// attributes and styles merged (the props' values win) and listeners added.
+    pub fn spread(&mut self, props: Props<Ms>) -> &mut Self {
+        for class in props.classes {
+            self.add_class(class);
+        }
+        self.merge_attrs(props.attrs).merge_style(props.style);
+        self.listeners.extend(props.listeners);
+        self
+    }
+
+    /// Set the element's identity among its siblings (eg the id of the rendered item),
+    /// so the element keeps its DOM node (and state like focus or the text typed into inputs)
+    /// when the siblings are reordered, added or removed. Keys should be unique among siblings;
+    /// see `keyed_list`.
+    pub fn key(&mut self, key: impl ToString) -> &mut Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    /// Replace the element's lifecycle hooks.
+    pub fn set_hooks(&mut self, hooks: LifecycleHooks<Ms>) -> &mut Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Add actions to run after the element has been inserted into the DOM.
+    /// They're called after the already set `did_mount` actions.
+    pub fn on_insert(&mut self, actions: impl FnMut(&web_sys::Node) + 'static) -> &mut Self {
+        match &mut self.hooks.did_mount {
+            Some(did_mount) => lifecycle_hooks::append_actions(&mut did_mount.actions, actions),
+            None => self.hooks.did_mount = Some(lifecycle_hooks::did_mount(actions)),
+        }
+        self
+    }
+
+    /// Add actions to run after the element has been patched.
+    /// They're called after the already set `did_update` actions.
+    pub fn on_update(&mut self, actions: impl FnMut(&web_sys::Node) + 'static) -> &mut Self {
+        match &mut self.hooks.did_update {
+            Some(did_update) => lifecycle_hooks::append_actions(&mut did_update.actions, actions),
+            None => self.hooks.did_update = Some(lifecycle_hooks::did_update(actions)),
+        }
+        self
+    }
+
+    /// Add actions to run after the element has been removed from the DOM - also when it's
+    /// removed with one of its ancestors. They're called after the already set `will_unmount`
+    /// actions.
+    pub fn on_remove(&mut self, actions: impl FnMut(&web_sys::Node) + 'static) -> &mut Self {
+        match &mut self.hooks.will_unmount {
+            Some(will_unmount) => {
+                lifecycle_hooks::append_actions(&mut will_unmount.actions, actions)
+            }
+            None => self.hooks.will_unmount = Some(lifecycle_hooks::will_unmount(actions)),
+        }
+        self
+    }
+
+    /// Store the element's `web_sys` node into the `el_ref` while the element is in the DOM;
+    /// eg to call `play()` on a video or to measure the layout.
+    pub fn el_ref(&mut self, el_ref: &ElRef) -> &mut Self {
+        let inserted_ref = el_ref.clone();
+        let removed_ref = el_ref.clone();
+        self.on_insert(move |node| inserted_ref.set(Some(node.clone())))
+            .on_remove(move |node| {
+                // A replacement element may have been inserted already.
+                if let Some(stored) = removed_ref.get() {
+                    if stored.is_same_node(Some(node)) {
+                        removed_ref.set(None);
+                    }
+                }
+            })
+    }
+
+    /// Send a message with the element's content box whenever its size changes
+    /// (and once after it's inserted), using a `ResizeObserver`.
+    /// The observer is disconnected when the element is removed.
+    pub fn on_resize(
+        &mut self,
+        handler: impl FnOnce(observers::ContentRect) -> Ms + 'static + Clone,
+    ) -> &mut Self {
+        self.on_insert(observers::observe_resize)
+            .on_remove(observers::unobserve_resize)
+            .add_listener(observers::resize_listener(handler))
+    }
+
+    /// Send a message whenever the element enters or leaves the viewport (or crosses
+    /// one of `options.thresholds`), using an `IntersectionObserver`; eg for lazy loading
+    /// or infinite scroll. The observer is disconnected when the element is removed.
+    pub fn on_intersect(
+        &mut self,
+        options: observers::IntersectOptions,
+        handler: impl FnOnce(observers::IntersectionInfo) -> Ms + 'static + Clone,
+    ) -> &mut Self {
+        self.on_insert(move |node| observers::observe_intersection(node, &options))
+            .on_remove(observers::unobserve_intersection)
+            .add_listener(observers::intersection_listener(handler))
+    }
+
+    /// Send a message when the user clicks outside of the element and its descendants;
+    /// eg to close a dropdown. The document-level listener is removed with the element.
+    pub fn on_click_outside(&mut self, msg_factory: impl Fn() -> Ms + 'static) -> &mut Self {
+        self.on_insert(observers::observe_click_outside)
+            .on_remove(observers::unobserve_click_outside)
+            .add_listener(observers::click_outside_listener(msg_factory))
+    }
+
+    /// Send a message with the pasted plain text instead of pasting it; eg to insert it
+    /// without formatting into a rich text editor. See `paste_ev` for the whole event.
+    pub fn on_paste(&mut self, handler: impl FnOnce(String) -> Ms + 'static + Clone) -> &mut Self {
+        self.add_listener(event_handler::paste_ev(move |event| {
+            event.prevent_default();
+            (handler.clone())(event_handler::clipboard_text(&event))
+        }))
+    }
+
+    /// Handle copying from the element; eg `el.on_copy(|event| { set_clipboard_text(&event,
+    /// &link); Msg::LinkCopied })`. See `copy_ev`.
+    pub fn on_copy(
+        &mut self,
+        handler: impl FnOnce(web_sys::Event) -> Ms + 'static + Clone,
+    ) -> &mut Self {
+        self.add_listener(event_handler::copy_ev(handler))
+    }
+
+    /// Send a message with the form's field values (by their names) when it's submitted,
+    /// instead of submitting it; see `form_submit_ev`.
+    pub fn on_submit_form(
+        &mut self,
+        handler: impl FnOnce(std::collections::HashMap<String, String>) -> Ms + 'static + Clone,
+    ) -> &mut Self {
+        self.add_listener(form_submit_ev(handler))
+    }
+
+    /// Send a message when the CSS animation with the name (`@keyframes` name) ends
+    /// on the element or its descendant; eg to remove a notification after it fades out.
+    /// Other animations are ignored - use `animationend_ev` to get all of them.
+    pub fn on_animation_end(
+        &mut self,
+        animation_name: &str,
+        msg_factory: impl Fn() -> Ms + 'static,
+    ) -> &mut Self {
+        let animation_name = animation_name.to_owned();
+        let event_name = format!("seedanimationend:{}", animation_name);
+        let msg_factory = Rc::new(msg_factory);
+        self.add_listener(event_handler::custom_ev(&event_name, move |_| {
+            msg_factory()
+        }))
+        .on_insert(move |node| {
+            let animation_name = animation_name.clone();
+            observers::pass_matching_events(
+                node,
+                "animationend",
+                event_name.clone(),
+                move |event| event_handler::animation_name(event) == animation_name,
+            )
+        })
+    }
+
+    /// Send a message when the CSS transition of the property (eg `"opacity"`) ends
+    /// on the element or its descendant. Other transitions are ignored - use `transitionend_ev`
+    /// to get all of them.
+    pub fn on_transition_end(
+        &mut self,
+        property: &str,
+        msg_factory: impl Fn() -> Ms + 'static,
+    ) -> &mut Self {
+        let property = property.to_owned();
+        let event_name = format!("seedtransitionend:{}", property);
+        let msg_factory = Rc::new(msg_factory);
+        self.add_listener(event_handler::custom_ev(&event_name, move |_| {
+            msg_factory()
+        }))
+        .on_insert(move |node| {
+            let property = property.clone();
+            observers::pass_matching_events(
+                node,
+                "transitionend",
+                event_name.clone(),
+                move |event| event_handler::transition_property(event) == property,
+            )
+        })
+    }
+
+    /// Send a message when the key (`KeyboardEvent.key`; eg `"Enter"` or `"ArrowDown"`)
+    /// is pressed while the element or its descendant has focus; modifiers are ignored.
+    /// The browser's default action of the key is prevented.
+    pub fn on_key(&mut self, key: &str, msg_factory: impl Fn() -> Ms + 'static) -> &mut Self {
+        let key = key.to_owned();
+        let event_name = format!("seedkey:{}", key);
+        self.add_listener(key_combo::key_listener(&event_name, msg_factory))
+            .on_insert(move |node| {
+                let key = key.clone();
+                key_combo::listen_keydown(node, event_name.clone(), move |event| event.key() == key)
+            })
+    }
+
+    /// Like `on_key`, but with exact modifiers; eg `on_key_combo("Ctrl+K", || Msg::OpenSearch)`.
+    /// See `KeyCombo::parse` for the format.
+    pub fn on_key_combo(
+        &mut self,
+        combo: &str,
+        msg_factory: impl Fn() -> Ms + 'static,
+    ) -> &mut Self {
+        let combo = key_combo::KeyCombo::parse(combo);
+        let event_name = format!("seedkeycombo:{}", combo);
+        self.add_listener(key_combo::key_listener(&event_name, msg_factory))
+            .on_insert(move |node| {
+                let combo = combo.clone();
+                key_combo::listen_keydown(node, event_name.clone(), move |event| {
+                    combo.matches(event)
+                })
+            })
+    }
+
+    /// Add the class to the element when it's inserted into the DOM and remove it
+    /// in the next frame; the class should contain the starting values of a `transition`.
+    pub fn animate_enter(&mut self, class: &str) -> &mut Self {
+        let class = class.to_owned();
+        self.on_insert(move |node| virtual_dom_bridge::animate_enter(node, &class))
+    }
+
+    /// Add the class to the element when it's removed from the vdom, and keep its DOM node
+    /// until its animation (or transition) ends - for the `duration` at most.
+    /// (`on_remove` hooks are called immediately.)
+    ///
+    /// Note: The element is removed only when it disappears from its parent -
+    /// replacing it with another element or a text node isn't animated.
+    pub fn animate_leave(&mut self, class: &str, duration: Duration) -> &mut Self {
+        self.hooks.leave_animation = Some(lifecycle_hooks::LeaveAnimation {
+            class: class.to_owned(),
+            duration,
+        });
+        self
+    }
+
+    /// Animate the element's moves when its siblings are reordered, added or removed:
+    /// it's rendered at its old position and the class (with a `transition` of `transform`;
+    /// eg `transition: transform 300ms`) is added for the `duration` while it moves to the new one.
+    ///
+    /// Note: Only elements with a key (see `El::key`) are animated; their inline `transform`
+    /// and `transition` styles are removed. See also `transition_group`.
+    pub fn animate_move(&mut self, class: &str, duration: Duration) -> &mut Self {
+        self.hooks.move_animation = Some(lifecycle_hooks::MoveAnimation {
+            class: class.to_owned(),
+            duration,
+        });
+        self
+    }
+
+    /// Keep the element's scroll position (`scrollTop` and `scrollLeft`) when it's patched,
+    /// or when it's replaced by a new element (eg because its tag has changed);
+    /// eg for chat logs or long lists whose content is re-rendered.
+    pub fn preserve_scroll(&mut self) -> &mut Self {
+        self.hooks.preserve_scroll = true;
+        self
+    }
+
     /// Add a text node to the element. (ie between the HTML tags).
     pub fn add_text(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.children.push(Node::Text(Text::new(text)));
@@ -183,6 +1539,15 @@ impl<Ms> El<Ms> {
         self
     }
 
+    /// Replace all children (not only text nodes) with the text; it's never interpreted as HTML.
+    /// The text node is patched with `set_text_content` when the text changes;
+    /// the strings are only compared, not diffed.
+    pub fn set_inner_text(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.children.clear();
+        self.children.push(Node::new_text(text));
+        self
+    }
+
     // Pull text from child text nodes
     pub fn get_text(&self) -> String {
         self.children
@@ -194,6 +1559,122 @@ impl<Ms> El<Ms> {
             .collect()
     }
 
+    /// The number of characters of all text in the element and its descendants,
+    /// like `textContent.length` in the DOM. Children of portals aren't counted.
+    pub fn text_content_length(&self) -> usize {
+        fn length<Ms>(children: &[Node<Ms>]) -> usize {
+            children
+                .iter()
+                .map(|child| match child {
+                    Node::Text(text_node) => text_node.text.chars().count(),
+                    Node::Element(el) => length(&el.children),
+                    Node::Empty | Node::Portal { .. } => 0,
+                })
+                .sum()
+        }
+        length(&self.children)
+    }
+
+    /// `true` if the element has any children except `Node::Empty`s.
+    pub fn has_children(&self) -> bool {
+        self.children.iter().any(|child| !child.is_empty())
+    }
+
+    /// `true` if the element renders no content - its only children (if any) are `Node::Empty`s
+    /// and whitespace-only text nodes. Child elements are content, even if they're empty
+    /// themselves (eg `img`). Attributes and style are ignored.
+    pub fn is_empty(&self) -> bool {
+        self.children.iter().all(|child| match child {
+            Node::Text(text_node) => text_node.text.trim().is_empty(),
+            Node::Empty => true,
+            Node::Element(_) | Node::Portal { .. } => false,
+        })
+    }
+
+    /// Remove the element's DOM node from its parent and return it; the vdom isn't changed.
+    /// Returns `None` if the element hasn't been rendered yet.
+    ///
+    /// The vdom and the DOM diverge while the node is detached: patching still updates
+    /// the detached node, but new siblings may be inserted in wrong positions.
+    /// Insert the node back (eg with `append_child`) before the next render.
+    pub fn detach_ws(&mut self) -> Option<web_sys::Node> {
+        let node_ws = self.node_ws.as_ref()?;
+        if let Some(parent) = node_ws.parent_node() {
+            virtual_dom_bridge::remove_node(node_ws, &parent);
+        }
+        Some(node_ws.clone())
+    }
+
+    /// Insert the DOM node before the element's DOM node, as its sibling (like
+    /// [insertAdjacentElement](https://developer.mozilla.org/en-US/docs/Web/API/Element/insertAdjacentElement)
+    /// with `beforebegin`); eg for third-party code that places its elements relative to ours.
+    /// Returns `false` if the element hasn't been rendered or its DOM node has no parent.
+    ///
+    /// It mutates the live DOM only, so the vdom doesn't know about the inserted node - patching
+    /// may insert new siblings in wrong positions. Don't insert it next to keyed or conditionally
+    /// rendered elements, or wrap the element in its own parent.
+    pub fn insert_before_self(&self, node: &web_sys::Node) -> bool {
+        self.insert_next_to_self(node, false)
+    }
+
+    /// Insert the DOM node after the element's DOM node, as its sibling (`afterend`);
+    /// see `insert_before_self`.
+    pub fn insert_after_self(&self, node: &web_sys::Node) -> bool {
+        self.insert_next_to_self(node, true)
+    }
+
+    fn insert_next_to_self(&self, node: &web_sys::Node, after: bool) -> bool {
+        let node_ws = match &self.node_ws {
+            Some(node_ws) => node_ws,
+            None => return false,
+        };
+        let parent = match node_ws.parent_node() {
+            Some(parent) => parent,
+            None => return false,
+        };
+        let next = if after {
+            node_ws.next_sibling()
+        } else {
+            Some(node_ws.clone())
+        };
+        virtual_dom_bridge::insert_node(node, &parent, next);
+        true
+    }
+
+    /// Clone the element together with the references to the DOM nodes of the element
+    /// and its descendants (unlike `clone`); eg to inspect the rendered nodes later.
+    /// Don't mount both elements - they would share the same DOM nodes.
+    pub fn clone_keeping_ws(&self) -> Self
+    where
+        Ms: Clone,
+    {
+        fn clone_nodes<Ms: Clone>(nodes: &[Node<Ms>]) -> Vec<Node<Ms>> {
+            nodes
+                .iter()
+                .map(|node| match node {
+                    Node::Element(el) => Node::Element(el.clone_keeping_ws()),
+                    Node::Portal { target, children } => Node::Portal {
+                        target: target.clone(),
+                        children: clone_nodes(children),
+                    },
+                    node => node.clone(),
+                })
+                .collect()
+        }
+        Self {
+            tag: self.tag.clone(),
+            attrs: self.attrs.clone(),
+            style: self.style.clone(),
+            children: clone_nodes(&self.children),
+            node_ws: self.node_ws.clone(),
+            listeners: self.listeners.clone(),
+            namespace: self.namespace.clone(),
+            hooks: LifecycleHooks::new(),
+            key: self.key.clone(),
+            memo: self.memo.clone(),
+        }
+    }
+
     /// Remove websys nodes.
     pub fn strip_ws_nodes_from_self_and_children(&mut self) {
         self.node_ws.take();
@@ -202,6 +1683,44 @@ impl<Ms> El<Ms> {
         }
     }
 
+    /// Merge adjacent text children into one and remove the empty ones, recursively
+    /// (like [Node.normalize](https://developer.mozilla.org/en-US/docs/Web/API/Node/normalize)).
+    /// `Node::Empty`s between text nodes are removed too, because they aren't rendered.
+    pub fn normalize(&mut self) -> &mut Self {
+        normalize_nodes(&mut self.children);
+        self
+    }
+
+    /// Skip patching of the element and its descendants when their content doesn't change
+    /// between renders. Unlike with keys, nothing has to be compared by hand - the tag,
+    /// attributes, style, namespace, key and, recursively, children are hashed in each render
+    /// (see `content_hash`) and the subtree is diffed only if the hash differs from the previous
+    /// one. Listeners aren't a part of the content; they're reattached in each render.
+    ///
+    /// Hashing costs time proportional to the subtree's size, so it pays off for large subtrees
+    /// that rarely change (eg a long rendered article), but makes the render slower for small
+    /// or often changing ones. `did_update` hooks in a skipped subtree aren't called.
+    ///
+    /// Custom elements are always re-rendered (see Issue #325), so it has no effect on them.
+    pub fn auto_memo(&mut self) -> &mut Self {
+        if self.memo.is_none() {
+            self.memo = Some(Box::default());
+        }
+        self
+    }
+
+    /// The content hash stored by the last patch of the `auto_memo` element.
+    pub(crate) fn memo_hash(&self) -> Option<u64> {
+        self.memo.as_ref().and_then(|memo| memo.content_hash)
+    }
+
+    /// Hash of the element's content, as used by `auto_memo`.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash_el_content(self, &mut hasher);
+        hasher.finish()
+    }
+
     /// Is it a custom element?
     pub fn is_custom(&self) -> bool {
         // @TODO: replace with `matches!` macro once stable
@@ -214,20 +1733,173 @@ impl<Ms> El<Ms> {
 }
 
 /// Allow the user to clone their Els. Note that there's no easy way to clone the
-/// closures within listeners or lifestyle hooks, so we omit them.
+/// closures within listeners or lifestyle hooks, so we omit them - listeners keep
+/// only their handlers and hooks are lost. See `El::clone_with` for listeners
+/// that can't be cloned.
+/// A clone is a new element that hasn't been rendered yet - it doesn't reference the DOM nodes
+/// of the element and its descendants, so both elements can be mounted. See `clone_keeping_ws`.
 impl<Ms: Clone> Clone for El<Ms> {
     fn clone(&self) -> Self {
-        Self {
+        let mut el = Self {
             tag: self.tag.clone(),
             attrs: self.attrs.clone(),
             style: self.style.clone(),
             children: self.children.clone(),
-            node_ws: self.node_ws.clone(),
+            node_ws: None,
             listeners: self.listeners.clone(),
             namespace: self.namespace.clone(),
             hooks: LifecycleHooks::new(),
+            key: self.key.clone(),
+            memo: self.memo.as_ref().map(|_| Box::default()),
+        };
+        // Only text nodes keep them when cloned on their own; the cloned elements don't.
+        strip_text_ws_nodes(&mut el.children);
+        el
+    }
+}
+
+/// Remove websys nodes of the text nodes, including the ones of portals, but not of the
+/// elements' children.
+fn strip_text_ws_nodes<Ms>(nodes: &mut [Node<Ms>]) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => text.strip_ws_node(),
+            Node::Portal { children, .. } => strip_text_ws_nodes(children),
+            Node::Element(_) | Node::Empty => (),
+        }
+    }
+}
+
+/// `{number}{unit}`; `CSSValue::Ignored` if the number isn't finite.
+fn unit_value(number: f64, unit: &str) -> CSSValue {
+    if number.is_finite() {
+        CSSValue::Some(format!("{}{}", number, unit))
+    } else {
+        CSSValue::Ignored
+    }
+}
+
+fn hash_el_content<Ms>(el: &El<Ms>, hasher: &mut DefaultHasher) {
+    el.tag.as_str().hash(hasher);
+    for (key, value) in &el.attrs.vals {
+        key.hash(hasher);
+        match value {
+            AtValue::Some(value) => Some(value).hash(hasher),
+            AtValue::None => None::<&String>.hash(hasher),
+            AtValue::Ignored => continue,
         }
     }
+    // Separates attributes from the style.
+    0xff_u8.hash(hasher);
+    for (key, value) in &el.style.vals {
+        if let CSSValue::Some(value) = value {
+            key.hash(hasher);
+            value.hash(hasher);
+        }
+    }
+    el.namespace.as_ref().map(Namespace::as_str).hash(hasher);
+    el.key.hash(hasher);
+    hash_nodes_content(&el.children, hasher);
+}
+
+fn hash_nodes_content<Ms>(nodes: &[Node<Ms>], hasher: &mut DefaultHasher) {
+    nodes.len().hash(hasher);
+    for node in nodes {
+        match node {
+            Node::Element(el) => {
+                0_u8.hash(hasher);
+                hash_el_content(el, hasher);
+            }
+            Node::Text(text) => {
+                1_u8.hash(hasher);
+                text.text.hash(hasher);
+            }
+            Node::Portal { target, children } => {
+                2_u8.hash(hasher);
+                target.hash(hasher);
+                hash_nodes_content(children, hasher);
+            }
+            Node::Empty => 3_u8.hash(hasher),
+        }
+    }
+}
+
+fn map_attrs_of_el<Ms>(el: &mut El<Ms>, f: &mut dyn FnMut(&At, &mut AtValue)) {
+    for (key, value) in el.attrs.vals.iter_mut() {
+        f(key, value);
+    }
+    map_attrs_of_nodes(&mut el.children, f);
+}
+
+fn map_attrs_of_nodes<Ms>(nodes: &mut [Node<Ms>], f: &mut dyn FnMut(&At, &mut AtValue)) {
+    for node in nodes {
+        match node {
+            Node::Element(el) => map_attrs_of_el(el, f),
+            Node::Portal { children, .. } => map_attrs_of_nodes(children, f),
+            Node::Text(_) | Node::Empty => (),
+        }
+    }
+}
+
+fn normalize_nodes<Ms>(nodes: &mut Vec<Node<Ms>>) {
+    let mut normalized: Vec<Node<Ms>> = Vec::with_capacity(nodes.len());
+    for mut node in nodes.drain(..) {
+        match &mut node {
+            Node::Text(text) => {
+                if text.text.is_empty() {
+                    continue;
+                }
+                // Drop the `Node::Empty`s after the previous text node.
+                let empty_count = normalized
+                    .iter()
+                    .rev()
+                    .take_while(|node| matches!(node, Node::Empty))
+                    .count();
+                let previous_index = normalized.len().checked_sub(empty_count + 1);
+                if let Some(Node::Text(previous)) =
+                    previous_index.map(|index| &mut normalized[index])
+                {
+                    previous.text.to_mut().push_str(&text.text);
+                    previous.node_ws = None;
+                    normalized.truncate(normalized.len() - empty_count);
+                    continue;
+                }
+            }
+            Node::Element(el) => normalize_nodes(&mut el.children),
+            Node::Portal { children, .. } => normalize_nodes(children),
+            Node::Empty => (),
+        }
+        normalized.push(node);
+    }
+    *nodes = normalized;
+}
+
+/// Take the events of the next top-level markdown block. Consecutive top-level HTML events
+/// (lines of an HTML block) are taken together.
+fn next_markdown_block<'a>(
+    events: &mut Peekable<pulldown_cmark::Parser<'a>>,
+) -> Option<Vec<pulldown_cmark::Event<'a>>> {
+    use pulldown_cmark::Event;
+
+    let mut block = Vec::new();
+    let mut depth = 0_usize;
+    while let Some(event) = events.next() {
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            _ => (),
+        }
+        let is_html = matches!(event, Event::Html(_));
+        block.push(event);
+        if depth == 0 && !(is_html && matches!(events.peek(), Some(Event::Html(_)))) {
+            break;
+        }
+    }
+    if block.is_empty() {
+        None
+    } else {
+        Some(block)
+    }
 }
 
 impl<Ms> PartialEq for El<Ms> {
@@ -239,5 +1911,6 @@ impl<Ms> PartialEq for El<Ms> {
             && self.style == other.style
             && self.listeners == other.listeners
             && self.namespace == other.namespace
+            && self.key == other.key
     }
 }
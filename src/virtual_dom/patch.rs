@@ -1,9 +1,10 @@
 //! This module contains code related to patching the VDOM. It can be considered
 //! a subset of the `vdom` module.
 
-use super::{At, AtValue, El, Listener, Mailbox, Node, Tag, View};
-use crate::app::App;
+use super::{diff, At, AtValue, El, Listener, Mailbox, Node, Tag, View};
+use crate::app::{error_boundary, App};
 use crate::browser::dom::virtual_dom_bridge;
+use std::collections::HashMap;
 use wasm_bindgen::JsCast;
 use web_sys::{Document, Window};
 
@@ -15,9 +16,16 @@ pub(crate) fn attach_listeners<Ms>(el: &mut El<Ms>, mailbox: &Mailbox<Ms>) {
             listener.attach(el_ws, mailbox.clone());
         }
     }
-    for child in &mut el.children {
-        if let Node::Element(child_el) = child {
-            attach_listeners(child_el, mailbox);
+    attach_nodes_listeners(&mut el.children, mailbox);
+}
+
+/// See `attach_listeners`; includes children of portals.
+pub(crate) fn attach_nodes_listeners<Ms>(nodes: &mut [Node<Ms>], mailbox: &Mailbox<Ms>) {
+    for node in nodes {
+        match node {
+            Node::Element(el) => attach_listeners(el, mailbox),
+            Node::Portal { children, .. } => attach_nodes_listeners(children, mailbox),
+            Node::Text(_) | Node::Empty => (),
         }
     }
 }
@@ -29,9 +37,16 @@ pub(crate) fn detach_listeners<Ms>(el: &mut El<Ms>) {
             listener.detach(el_ws);
         }
     }
-    for child in &mut el.children {
-        if let Node::Element(child_el) = child {
-            detach_listeners(child_el);
+    detach_nodes_listeners(&mut el.children);
+}
+
+/// See `detach_listeners`; includes children of portals.
+fn detach_nodes_listeners<Ms>(nodes: &mut [Node<Ms>]) {
+    for node in nodes {
+        match node {
+            Node::Element(el) => detach_listeners(el),
+            Node::Portal { children, .. } => detach_nodes_listeners(children),
+            Node::Text(_) | Node::Empty => (),
         }
     }
 }
@@ -55,16 +70,66 @@ pub(crate) fn setup_window_listeners<Ms>(
 
 /// Remove a node from the vdom and `web_sys` DOM.
 pub(crate) fn remove_node<Ms>(node: &web_sys::Node, parent: &web_sys::Node, el_vdom: &mut El<Ms>) {
-    virtual_dom_bridge::remove_node(node, parent);
+    match &el_vdom.hooks.leave_animation {
+        Some(animation) => virtual_dom_bridge::remove_node_animated(node, parent, animation),
+        None => virtual_dom_bridge::remove_node(node, parent),
+    }
+    unmount(node, el_vdom);
+}
 
-    if let Some(unmount_actions) = &mut el_vdom.hooks.will_unmount {
-        (unmount_actions.actions)(node);
+/// Run the `will_unmount` actions of the removed element and its descendants (eg to stop
+/// their observers). The descendants' actions are run first. Children of the portals among
+/// the descendants are removed from the portals' targets; removing the element doesn't
+/// remove them.
+fn unmount<Ms>(el_ws: &web_sys::Node, el: &mut El<Ms>) {
+    unmount_children(&mut el.children);
+    if let Some(unmount_actions) = &mut el.hooks.will_unmount {
+        (unmount_actions.actions)(el_ws);
         //                if let Some(message) = unmount_actions.message.clone() {
         //                    app.update(message);
         //                }
     }
 }
 
+/// See `unmount`.
+fn unmount_children<Ms>(nodes: &mut [Node<Ms>]) {
+    for node in nodes {
+        match node {
+            Node::Element(el) => {
+                if let Some(el_ws) = el.node_ws.take() {
+                    unmount(&el_ws, el);
+                    el.node_ws.replace(el_ws);
+                }
+            }
+            Node::Portal { .. } => remove_from_dom(node),
+            Node::Text(_) | Node::Empty => (),
+        }
+    }
+}
+
+/// Remove the node from its current DOM parent; portals' children from their targets.
+fn remove_from_dom<Ms>(node: &mut Node<Ms>) {
+    match node {
+        Node::Element(el) => {
+            if let Some(el_ws) = el.node_ws.take() {
+                if let Some(parent) = el_ws.parent_node() {
+                    remove_node(&el_ws, &parent, el);
+                }
+                el.node_ws.replace(el_ws);
+            }
+        }
+        Node::Text(text) => {
+            if let Some(text_ws) = text.node_ws.as_ref() {
+                if let Some(parent) = text_ws.parent_node() {
+                    virtual_dom_bridge::remove_node(text_ws, &parent);
+                }
+            }
+        }
+        Node::Portal { children, .. } => children.iter_mut().for_each(remove_from_dom),
+        Node::Empty => (),
+    }
+}
+
 /// Set up controlled components: Input, Select, and `TextArea` elements must stay in sync with the
 /// model; don't let them get out of sync from typing or other events, which can occur if a change
 /// doesn't trigger a re-render, or if something else modifies them using a side effect.
@@ -113,6 +178,18 @@ fn patch_el<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
     mailbox: &Mailbox<Ms>,
     app: &App<Ms, Mdl, ElC, GMs>,
 ) -> Option<&'a web_sys::Node> {
+    // Custom elements are re-rendered anyway; see below.
+    if new.memo.is_some() && !new.is_custom() {
+        let content_hash = new.content_hash();
+        if let Some(memo) = &mut new.memo {
+            memo.content_hash = Some(content_hash);
+        }
+        if old.memo_hash() == Some(content_hash) {
+            take_over_el(old, new, mailbox);
+            return new.node_ws.as_ref();
+        }
+    }
+
     if old != *new {
         // At this step, we already assume we have the right element - either
         // by entering this func directly for the top-level, or recursively after
@@ -126,11 +203,15 @@ fn patch_el<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
         // Assume all listeners have been removed from the old el_ws (if any), and the
         // old el vdom's elements are still attached.
 
-        // Namespaces can't be patched, since they involve create_element_ns instead of create_element.
-        // Custom elements can't be patched, because we need to reinit them (Issue #325). (@TODO is there a better way?)
         // Something about this element itself is different: patch it.
-        if old.tag != new.tag || old.namespace != new.namespace || old.is_custom() {
-            let old_el_ws = old.node_ws.as_ref().expect("Missing websys el");
+        // (@TODO is there a better way for custom elements?)
+        if diff::needs_replace(&old, new) {
+            let old_el_ws = old.node_ws.clone().expect("Missing websys el");
+            let scroll_position = if new.hooks.preserve_scroll {
+                virtual_dom_bridge::scroll_position(&old_el_ws)
+            } else {
+                None
+            };
 
             // We don't use assign_nodes directly here, since we only have access to
             // the El, not wrapping node.
@@ -138,14 +219,14 @@ fn patch_el<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
             for mut child in &mut new.children {
                 virtual_dom_bridge::assign_ws_nodes(document, &mut child);
             }
-            if let Some(unmount_actions) = &mut old.hooks.will_unmount {
-                let old_ws = old.node_ws.as_ref().expect("Missing websys el");
-                (unmount_actions.actions)(old_ws);
-            }
+            unmount(&old_el_ws, &mut old);
             virtual_dom_bridge::attach_el_and_children(new, parent);
 
             let new_ws = new.node_ws.as_ref().expect("Missing websys el");
-            virtual_dom_bridge::replace_child(new_ws, old_el_ws, parent);
+            virtual_dom_bridge::replace_child(new_ws, &old_el_ws, parent);
+            if let Some(scroll_position) = scroll_position {
+                virtual_dom_bridge::set_scroll_position(new_ws, scroll_position);
+            }
 
             attach_listeners(new, mailbox);
             // We've re-rendered this child and all children; we're done with this recursion.
@@ -172,36 +253,87 @@ fn patch_el<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
         listener.attach(&old_el_ws, mailbox.clone());
     }
 
-    let old_children_iter = old.children.into_iter();
-    let new_children_iter = new.children.iter_mut();
+    // Removing and inserting children can change the scroll position.
+    let scroll_position = if new.hooks.preserve_scroll {
+        virtual_dom_bridge::scroll_position(&old_el_ws)
+    } else {
+        None
+    };
 
     patch_els(
         document,
         mailbox,
         app,
         &old_el_ws,
-        old_children_iter,
-        new_children_iter,
+        old.children,
+        &mut new.children,
     );
 
+    if let Some(scroll_position) = scroll_position {
+        virtual_dom_bridge::set_scroll_position(&old_el_ws, scroll_position);
+    }
+
     new.node_ws = Some(old_el_ws);
     new.node_ws.as_ref()
 }
 
-pub(crate) fn patch_els<'a, Ms, Mdl, ElC, GMs, OI, NI>(
+/// Reuse the DOM nodes of the `old` element with the same content (see `El::auto_memo`)
+/// without diffing, and attach the new listeners.
+fn take_over_el<Ms>(mut old: El<Ms>, new: &mut El<Ms>, mailbox: &Mailbox<Ms>) {
+    new.node_ws = old.node_ws.take();
+    let old_hash = old.memo_hash();
+    if let Some(memo) = &mut new.memo {
+        memo.content_hash = memo.content_hash.or(old_hash);
+    }
+    if let Some(el_ws) = new.node_ws.as_ref() {
+        for listener in &mut new.listeners {
+            listener.attach(el_ws, mailbox.clone());
+        }
+    }
+    take_over_nodes(old.children, &mut new.children, mailbox);
+}
+
+fn take_over_nodes<Ms>(old: Vec<Node<Ms>>, new: &mut [Node<Ms>], mailbox: &Mailbox<Ms>) {
+    for (old, new) in old.into_iter().zip(new) {
+        match (old, new) {
+            (Node::Element(old), Node::Element(new)) => take_over_el(old, new, mailbox),
+            (Node::Text(old), Node::Text(new)) => new.node_ws = old.node_ws,
+            (
+                Node::Portal {
+                    children: old_children,
+                    ..
+                },
+                Node::Portal { children, .. },
+            ) => take_over_nodes(old_children, children, mailbox),
+            _ => (),
+        }
+    }
+}
+
+pub(crate) fn patch_els<Ms, Mdl, ElC, GMs>(
     document: &Document,
     mailbox: &Mailbox<Ms>,
     app: &App<Ms, Mdl, ElC, GMs>,
     old_el_ws: &web_sys::Node,
-    old_children_iter: OI,
-    new_children_iter: NI,
+    old_children: Vec<Node<Ms>>,
+    new_children: &mut [Node<Ms>],
 ) where
     ElC: View<Ms>,
-    OI: ExactSizeIterator<Item = Node<Ms>>,
-    NI: ExactSizeIterator<Item = &'a mut Node<Ms>>,
 {
-    let mut old_children_iter = old_children_iter.peekable();
-    let mut new_children_iter = new_children_iter.peekable();
+    if old_children.iter().any(is_keyed) || new_children.iter().any(is_keyed) {
+        patch_keyed_els(
+            document,
+            mailbox,
+            app,
+            old_el_ws,
+            old_children,
+            new_children,
+        );
+        return;
+    }
+
+    let mut old_children_iter = old_children.into_iter().peekable();
+    let mut new_children_iter = new_children.iter_mut().peekable();
     let mut last_visited_node: Option<web_sys::Node> = None;
 
     // TODO: Lines below commented out, because they were breaking `lifecycle_hooks` test
@@ -224,10 +356,7 @@ pub(crate) fn patch_els<'a, Ms, Mdl, ElC, GMs, OI, NI>(
             child_old,
             child_new,
             old_el_ws,
-            match last_visited_node.as_ref() {
-                Some(node) => node.next_sibling(),
-                None => old_el_ws.first_child(),
-            },
+            virtual_dom_bridge::next_patched_node(old_el_ws, last_visited_node.as_ref()),
             mailbox,
             app,
         ) {
@@ -240,38 +369,146 @@ pub(crate) fn patch_els<'a, Ms, Mdl, ElC, GMs, OI, NI>(
     // We ran out of old children to patch; create new ones.
     for child_new in new_children_iter {
         virtual_dom_bridge::assign_ws_nodes(document, child_new);
-
-        match child_new {
-            Node::Element(child_new_el) => {
-                virtual_dom_bridge::attach_el_and_children(child_new_el, old_el_ws);
-                attach_listeners(child_new_el, mailbox);
-            }
-            Node::Text(child_new_text) => {
-                virtual_dom_bridge::attach_text_node(child_new_text, old_el_ws);
-            }
-            Node::Empty => (),
-        }
+        virtual_dom_bridge::attach_node(child_new, old_el_ws);
+        attach_nodes_listeners(std::slice::from_mut(child_new), mailbox);
     }
 
     // Now purge any existing no-longer-needed children; they're not part of the new vdom.
     // while let Some(mut child) = old_children_iter.next() {
     for child in old_children_iter {
+        remove_child(child, old_el_ws);
+    }
+}
+
+fn is_keyed<Ms>(node: &Node<Ms>) -> bool {
+    match node {
+        Node::Element(el) => el.key.is_some(),
+        _ => false,
+    }
+}
+
+/// Patch children when some of them have keys (see `El::key`): each new keyed element
+/// is patched with the old one with the same key and its DOM node is moved to the new
+/// position. The other children are patched in order with the old children without keys.
+fn patch_keyed_els<Ms, Mdl, ElC, GMs>(
+    document: &Document,
+    mailbox: &Mailbox<Ms>,
+    app: &App<Ms, Mdl, ElC, GMs>,
+    old_el_ws: &web_sys::Node,
+    old_children: Vec<Node<Ms>>,
+    new_children: &mut [Node<Ms>],
+) where
+    ElC: View<Ms>,
+{
+    let mut old_children = old_children.into_iter().map(Some).collect::<Vec<_>>();
+    let mut old_keyed = HashMap::<String, usize>::new();
+    let mut old_unkeyed = Vec::new();
+    for (index, child) in old_children.iter().enumerate() {
         match child {
-            Node::Element(mut child_el) => {
-                let child_ws = child_el.node_ws.take().expect("Missing child el_ws");
-                remove_node(&child_ws, old_el_ws, &mut child_el);
-                child_el.node_ws.replace(child_ws);
+            Some(Node::Element(El { key: Some(key), .. })) => {
+                // With duplicate keys, only the first element is reused.
+                old_keyed.entry(key.clone()).or_insert(index);
             }
-            Node::Text(mut child_text) => {
-                let child_ws = child_text.node_ws.take().expect("Missing child node_ws");
-                virtual_dom_bridge::remove_node(&child_ws, old_el_ws);
-                child_text.node_ws.replace(child_ws);
+            _ => old_unkeyed.push(index),
+        }
+    }
+    let mut old_unkeyed = old_unkeyed.into_iter();
+    let old_rects = move_animation_rects(&old_children);
+
+    let mut last_visited_node: Option<web_sys::Node> = None;
+    for child_new in new_children.iter_mut() {
+        let old_index = match child_new {
+            Node::Element(El { key: Some(key), .. }) => old_keyed.remove(key.as_str()),
+            _ => old_unkeyed.next(),
+        };
+        let child_old = old_index
+            .and_then(|index| old_children[index].take())
+            .unwrap_or(Node::Empty);
+        let next_node = |last_visited_node: &Option<web_sys::Node>| {
+            virtual_dom_bridge::next_patched_node(old_el_ws, last_visited_node.as_ref())
+        };
+
+        let next = next_node(&last_visited_node);
+        if let Some(new_el_ws) = patch(
+            document, child_old, child_new, old_el_ws, next, mailbox, app,
+        ) {
+            // The reused node may be at its old position; move it after the previous sibling.
+            let expected_node = next_node(&last_visited_node);
+            if !new_el_ws.is_same_node(expected_node.as_ref()) {
+                virtual_dom_bridge::insert_node(new_el_ws, old_el_ws, expected_node);
+            }
+            last_visited_node = Some(new_el_ws.clone());
+        }
+    }
+
+    for child in old_children.into_iter().flatten() {
+        remove_child(child, old_el_ws);
+    }
+
+    if !old_rects.is_empty() {
+        for child_new in new_children {
+            if let Node::Element(El {
+                key: Some(key),
+                node_ws: Some(node_ws),
+                hooks,
+                ..
+            }) = child_new
+            {
+                if let (Some(old_rect), Some(animation)) =
+                    (old_rects.get(key), &hooks.move_animation)
+                {
+                    virtual_dom_bridge::animate_move(node_ws, old_rect, animation);
+                }
             }
-            Node::Empty => (),
         }
     }
 }
 
+/// Positions of the old keyed elements with move animations (see `El::animate_move`)
+/// before they're patched.
+fn move_animation_rects<Ms>(
+    old_children: &[Option<Node<Ms>>],
+) -> HashMap<String, web_sys::DomRect> {
+    old_children
+        .iter()
+        .filter_map(|child| match child {
+            Some(Node::Element(El {
+                key: Some(key),
+                node_ws: Some(node_ws),
+                hooks,
+                ..
+            })) if hooks.move_animation.is_some() => node_ws
+                .dyn_ref::<web_sys::Element>()
+                .map(|el_ws| (key.clone(), el_ws.get_bounding_client_rect())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Remove the old child that isn't a part of the new vdom.
+fn remove_child<Ms>(child: Node<Ms>, parent: &web_sys::Node) {
+    match child {
+        Node::Element(mut child_el) => {
+            let child_ws = child_el.node_ws.take().expect("Missing child el_ws");
+            remove_node(&child_ws, parent, &mut child_el);
+            child_el.node_ws.replace(child_ws);
+        }
+        Node::Text(mut child_text) => {
+            let child_ws = child_text.node_ws.take().expect("Missing child node_ws");
+            virtual_dom_bridge::remove_node(&child_ws, parent);
+            child_text.node_ws.replace(child_ws);
+        }
+        mut portal @ Node::Portal { .. } => remove_from_dom(&mut portal),
+        Node::Empty => (),
+    }
+}
+
+/// Attach the new portal's children to its target; their `web_sys` nodes have to be assigned.
+fn add_portal_helper<Ms>(target: &str, children: &mut [Node<Ms>], mailbox: &Mailbox<Ms>) {
+    virtual_dom_bridge::attach_portal(target, children);
+    attach_nodes_listeners(children, mailbox);
+}
+
 // Reduces code repetition
 fn add_el_helper<Ms>(
     new: &mut El<Ms>,
@@ -305,6 +542,20 @@ pub(crate) fn patch<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
     next_node: Option<web_sys::Node>,
     mailbox: &Mailbox<Ms>,
     app: &App<Ms, Mdl, ElC, GMs>,
+) -> Option<&'a web_sys::Node> {
+    // Remember the element for the error boundary until it's patched.
+    let _path_guard = error_boundary::enter_node(new);
+    patch_node(document, old, new, parent, next_node, mailbox, app)
+}
+
+fn patch_node<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
+    document: &Document,
+    old: Node<Ms>,
+    new: &'a mut Node<Ms>,
+    parent: &web_sys::Node,
+    next_node: Option<web_sys::Node>,
+    mailbox: &Mailbox<Ms>,
+    app: &App<Ms, Mdl, ElC, GMs>,
 ) -> Option<&'a web_sys::Node> {
     // Old_el_ws is what we're patching, with items from the new vDOM el; or replacing.
     // We go through each combination of new and old variants to determine how to patch.
@@ -336,6 +587,8 @@ pub(crate) fn patch<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
                         .expect("old el_ws missing when replacing with text node");
 
                     virtual_dom_bridge::replace_child(new_node_ws, &old_node_ws, parent);
+
+                    unmount(&old_node_ws, &mut old_el);
                     new_text.node_ws.as_ref()
                 }
                 Node::Empty => {
@@ -346,6 +599,19 @@ pub(crate) fn patch<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
                     remove_node(&old_el_ws, parent, &mut old_el);
                     None
                 }
+                Node::Portal { target, children } => {
+                    let old_el_ws = old_el
+                        .node_ws
+                        .take()
+                        .expect("old el_ws missing when patching Element to Portal");
+                    remove_node(&old_el_ws, parent, &mut old_el);
+
+                    children
+                        .iter_mut()
+                        .for_each(|child| virtual_dom_bridge::assign_ws_nodes(document, child));
+                    add_portal_helper(target, children, mailbox);
+                    None
+                }
             }
         }
         Node::Empty => {
@@ -364,6 +630,10 @@ pub(crate) fn patch<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
                     virtual_dom_bridge::insert_node(new_node_ws, parent, next_node);
                     new_text.node_ws.as_ref()
                 }
+                Node::Portal { target, children } => {
+                    add_portal_helper(target, children, mailbox);
+                    None
+                }
                 // If new and old are empty, we don't need to do anything.
                 Node::Empty => None,
             }
@@ -387,6 +657,14 @@ pub(crate) fn patch<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
                     );
                     None
                 }
+                Node::Portal { target, children } => {
+                    virtual_dom_bridge::remove_node(
+                        &old_text.node_ws.expect("Can't find old text"),
+                        parent,
+                    );
+                    add_portal_helper(target, children, mailbox);
+                    None
+                }
                 Node::Text(new_text) => {
                     let old_node_ws = old_text
                         .node_ws
@@ -401,5 +679,22 @@ pub(crate) fn patch<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
                 }
             }
         }
+        Node::Portal { target, children } => {
+            if let Node::Portal {
+                target: new_target,
+                children: new_children,
+            } = new
+            {
+                // Children of portals with the same target are patched like element's children.
+                if *new_target == target {
+                    if let Some(target_ws) = virtual_dom_bridge::portal_target(&target) {
+                        patch_els(document, mailbox, app, &target_ws, children, new_children);
+                    }
+                    return None;
+                }
+            }
+            remove_from_dom(&mut Node::Portal { target, children });
+            patch(document, Node::Empty, new, parent, next_node, mailbox, app)
+        }
     }
 }
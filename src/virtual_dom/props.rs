@@ -0,0 +1,36 @@
+//! This module contains `Props` - a bundle of attributes, styles and listeners
+//! passed from a parent component to a child element; see `El::spread`.
+
+use super::{Attrs, Listener, Style};
+
+/// Attributes, styles, listeners and classes forwarded to an element by `El::spread`
+/// (or as a macro argument; eg `button![props, "Save"]`), like the spread of props in JSX.
+///
+/// ```rust,no_run
+/// # use seed::{prelude::*, *};
+/// # type Msg = ();
+/// fn button<Ms>(props: Props<Ms>, label: &str) -> Node<Ms> {
+///     button![class!["btn"], props, label]
+/// }
+/// ```
+pub struct Props<Ms> {
+    /// Merged by `Attrs::merge`, so the `class` attribute is concatenated too.
+    pub attrs: Attrs,
+    /// Its properties override the element's ones.
+    pub style: Style,
+    /// Appended to the element's listeners.
+    pub listeners: Vec<Listener<Ms>>,
+    /// Appended to the element's classes.
+    pub classes: Vec<String>,
+}
+
+impl<Ms> Default for Props<Ms> {
+    fn default() -> Self {
+        Self {
+            attrs: Attrs::empty(),
+            style: Style::empty(),
+            listeners: Vec::new(),
+            classes: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! This module contains deterministic text representations of vdom trees for golden tests;
+//! see `El::snapshot`.
+
+use super::{html, AtValue, CSSValue, El, Node};
+
+/// Serialize the element and its descendants; see `El::snapshot`.
+pub fn el_to_snapshot<Ms>(el: &El<Ms>) -> String {
+    let mut snapshot = String::new();
+    write_el(el, 0, &mut snapshot);
+    snapshot
+}
+
+fn write_el<Ms>(el: &El<Ms>, depth: usize, snapshot: &mut String) {
+    let tag = el.tag.as_str();
+    let mut attrs = el
+        .attrs
+        .vals
+        .iter()
+        .filter_map(|(key, val)| match val {
+            AtValue::Some(val) => Some(format!(
+                "{}=\"{}\"",
+                key.as_str(),
+                html::escape_attr(&collapse_whitespace(val))
+            )),
+            AtValue::None => Some(key.as_str().to_owned()),
+            AtValue::Ignored => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut style = el
+        .style
+        .vals
+        .iter()
+        .filter_map(|(key, val)| match val {
+            CSSValue::Some(val) => Some(format!("{}:{}", key.as_str(), val)),
+            CSSValue::Ignored => None,
+        })
+        .collect::<Vec<_>>();
+    if !style.is_empty() {
+        style.sort();
+        attrs.push(format!("style=\"{}\"", html::escape_attr(&style.join(";"))));
+    }
+    attrs.sort();
+
+    let mut triggers = el
+        .listeners
+        .iter()
+        .map(|listener| format!("on:{}", listener.trigger.as_str()))
+        .collect::<Vec<_>>();
+    triggers.sort();
+    triggers.dedup();
+    attrs.extend(triggers);
+
+    let mut line = format!("<{}", tag);
+    for attr in attrs {
+        line.push(' ');
+        line.push_str(&attr);
+    }
+    line.push('>');
+
+    if html::VOID_ELEMENTS.contains(&tag) {
+        push_line(snapshot, depth, &line);
+        return;
+    }
+    let closing_tag = format!("</{}>", tag);
+    let mut children = el.children.iter().filter(|child| !is_blank(child));
+    match (children.next(), children.next()) {
+        (None, _) => {
+            push_line(snapshot, depth, &(line + &closing_tag));
+            return;
+        }
+        // A single text is kept on the element's line.
+        (Some(Node::Text(text)), None) => {
+            let line = line + &text_line(&text.text) + &closing_tag;
+            push_line(snapshot, depth, &line);
+            return;
+        }
+        _ => (),
+    }
+    push_line(snapshot, depth, &line);
+    write_children(&el.children, depth + 1, snapshot);
+    push_line(snapshot, depth, &closing_tag);
+}
+
+fn write_children<Ms>(children: &[Node<Ms>], depth: usize, snapshot: &mut String) {
+    for child in children {
+        match child {
+            Node::Element(el) => write_el(el, depth, snapshot),
+            Node::Text(text) if !is_blank(child) => {
+                push_line(snapshot, depth, &text_line(&text.text));
+            }
+            Node::Portal { target, children } => {
+                push_line(snapshot, depth, &format!("<portal target=\"{}\">", target));
+                write_children(children, depth + 1, snapshot);
+                push_line(snapshot, depth, "</portal>");
+            }
+            Node::Text(_) | Node::Empty => (),
+        }
+    }
+}
+
+fn is_blank<Ms>(node: &Node<Ms>) -> bool {
+    match node {
+        Node::Text(text) => text.text.trim().is_empty(),
+        Node::Empty => true,
+        Node::Element(_) | Node::Portal { .. } => false,
+    }
+}
+
+fn text_line(text: &str) -> String {
+    html::escape_text(&collapse_whitespace(text))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn push_line(snapshot: &mut String, depth: usize, line: &str) {
+    if !snapshot.is_empty() {
+        snapshot.push('\n');
+    }
+    snapshot.push_str(&"  ".repeat(depth));
+    snapshot.push_str(line);
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        browser::dom::event_handler::simple_ev,
+        virtual_dom::{At, Ev, St, Tag},
+    };
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn stable_snapshot() {
+        let mut input = El::empty(Tag::Input);
+        input.add_attr("type", "text").add_attr("autofocus", "");
+        input.attrs.add(At::Disabled, AtValue::None);
+        let mut item = El::empty(Tag::Li);
+        item.add_text("  first\n   item ").add_child(Node::Empty);
+        let mut second_item = El::empty(Tag::Li);
+        second_item
+            .add_text("a < b")
+            .add_child(Node::Element(El::empty(Tag::Br)));
+        let mut list: El<()> = El::empty(Tag::Ul);
+        list.add_attr("id", "list")
+            .add_attr("class", "items")
+            .add_style(St::Margin, "0")
+            .add_style(St::Color, "red")
+            .add_listener(simple_ev(Ev::Input, ()))
+            .add_listener(simple_ev(Ev::Click, ()))
+            .add_listener(simple_ev(Ev::Click, ()))
+            .add_child(Node::Element(item))
+            .add_text("\n  ")
+            .add_child(Node::Element(second_item))
+            .add_child(Node::Element(input));
+
+        assert_eq!(
+            el_to_snapshot(&list),
+            "<ul class=\"items\" id=\"list\" style=\"color:red;margin:0\" on:click on:input>\n  \
+             <li>first item</li>\n  \
+             <li>\n    \
+             a &lt; b\n    \
+             <br>\n  \
+             </li>\n  \
+             <input autofocus=\"\" disabled type=\"text\">\n\
+             </ul>"
+        );
+    }
+}
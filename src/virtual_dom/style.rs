@@ -2,6 +2,13 @@ use super::{CSSValue, St};
 use indexmap::IndexMap;
 use std::fmt;
 
+/// A set of design tokens (eg colors and spacings) rendered as CSS custom properties;
+/// see `Style::from_theme`.
+pub trait Theme {
+    /// Token names without the `--` prefix and their values; eg `("primary", "#3273dc".into())`.
+    fn tokens(&self) -> Vec<(&'static str, CSSValue)>;
+}
+
 /// Handle Style separately from Attrs, since it commonly involves multiple parts,
 /// and has a different semantic meaning.
 #[derive(Clone, Debug, PartialEq)]
@@ -24,6 +31,16 @@ impl Style {
         self.vals.insert(key.into(), val.into());
     }
 
+    /// Custom properties `--{token}: {value}` of the theme. Set them on a root element
+    /// and reference them in its descendants with `CSSValue::var` or `El::apply_tokens`.
+    pub fn from_theme(theme: &impl Theme) -> Self {
+        let mut style = Self::empty();
+        for (token, value) in theme.tokens() {
+            style.add(St::Custom(format!("--{}", token)), value);
+        }
+        style
+    }
+
     /// Combine with another Style; if there's a conflict, use the other one.
     pub fn merge(&mut self, other: Self) {
         self.vals.extend(other.vals.into_iter());
@@ -49,3 +66,28 @@ impl fmt::Display for Style {
         write!(f, "{}", string)
     }
 }
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    struct DarkTheme;
+
+    impl Theme for DarkTheme {
+        fn tokens(&self) -> Vec<(&'static str, CSSValue)> {
+            vec![("primary", "#3273dc".into()), ("gap", "8px".into())]
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn style_from_theme() {
+        let mut style = Style::from_theme(&DarkTheme);
+        style.add(St::Color, CSSValue::var("primary"));
+
+        assert_eq!(
+            style.to_string(),
+            "--primary:#3273dc;--gap:8px;color:var(--primary)"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! This module contains `Template` - an element built once and cloned for each of many
+//! similar elements (eg table rows); see `El::from_template`.
+
+use super::El;
+
+/// A listener-free element to create similar elements from; eg
+/// `let row = Template::new(tr![td![C!["name"]], td![C!["price"]]]);` and then
+/// `row.instantiate(|row| { ... })` for each item.
+///
+/// Cloning an element without listeners doesn't lose anything but lifecycle hooks,
+/// so the instances only need their own data, listeners and hooks.
+#[derive(Debug)]
+pub struct Template<Ms: 'static> {
+    el: El<Ms>,
+}
+
+impl<Ms: 'static> Template<Ms> {
+    /// Create a template from the element; its listeners are removed (see `El::strip_listeners`).
+    pub fn new(mut el: El<Ms>) -> Self {
+        el.strip_listeners();
+        Self { el }
+    }
+
+    /// The template's element.
+    pub fn el(&self) -> &El<Ms> {
+        &self.el
+    }
+}
+
+impl<Ms: Clone + 'static> Template<Ms> {
+    /// Clone the template and `fill` the new element with its data and listeners.
+    pub fn instantiate(&self, fill: impl FnOnce(&mut El<Ms>)) -> El<Ms> {
+        let mut el = self.el.clone();
+        fill(&mut el);
+        el
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        browser::dom::event_handler::simple_ev,
+        virtual_dom::{Ev, Node, Tag},
+    };
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn instantiate() {
+        let mut cell = El::empty(Tag::Td);
+        cell.add_class("name");
+        let mut row: El<()> = El::empty(Tag::Tr);
+        row.add_listener(simple_ev(Ev::Click, ()))
+            .add_child(Node::Element(cell));
+        let template = Template::new(row);
+        assert!(template.el().listeners.is_empty());
+
+        let rows = ["a", "b"]
+            .iter()
+            .map(|name| {
+                template.instantiate(|row| {
+                    if let Some(Node::Element(cell)) = row.children.first_mut() {
+                        cell.add_text(*name);
+                    }
+                    row.add_listener(simple_ev(Ev::Click, ()));
+                })
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            rows[0].snapshot(),
+            "<tr on:click>\n  <td class=\"name\">a</td>\n</tr>"
+        );
+        assert_eq!(
+            rows[1].snapshot(),
+            "<tr on:click>\n  <td class=\"name\">b</td>\n</tr>"
+        );
+        assert_eq!(
+            template.el().snapshot(),
+            "<tr>\n  <td class=\"name\"></td>\n</tr>"
+        );
+    }
+}
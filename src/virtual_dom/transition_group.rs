@@ -0,0 +1,53 @@
+//! This module contains `transition_group` - enter, leave and move animations
+//! of list items, like React's `TransitionGroup`.
+
+use super::{El, Node};
+use std::time::Duration;
+
+/// Animations of the list items; see `transition_group`. The default one has no animations.
+#[derive(Clone, Debug, Default)]
+pub struct TransitionGroup {
+    /// The class of the added items; see `El::animate_enter`.
+    pub enter_class: Option<String>,
+    /// The class of the removed items; see `El::animate_leave`. Add `position: absolute`
+    /// to it, so that the other items can move into the removed item's space right away.
+    pub leave_class: Option<String>,
+    pub leave_duration: Duration,
+    /// The class of the moved items; see `El::animate_move`.
+    pub move_class: Option<String>,
+    pub move_duration: Duration,
+}
+
+/// Add the animations of the `config` to the elements, so they're played when the elements
+/// are rendered, removed or moved among their siblings; eg
+/// `ul![transition_group(keyed_list(..), &config)]`.
+///
+/// The elements should have keys (see `keyed_list`), otherwise they're patched in order,
+/// so only the added or removed ones at the end of the list are animated and moves aren't detected.
+/// In debug builds, an error is logged for elements without keys.
+pub fn transition_group<Ms>(
+    mut children: Vec<Node<Ms>>,
+    config: &TransitionGroup,
+) -> Vec<Node<Ms>> {
+    for child in &mut children {
+        if let Node::Element(el) = child {
+            add_animations(el, config);
+        }
+    }
+    children
+}
+
+fn add_animations<Ms>(el: &mut El<Ms>, config: &TransitionGroup) {
+    if cfg!(debug_assertions) && el.key.is_none() {
+        crate::error("`transition_group` children should have keys");
+    }
+    if let Some(class) = &config.enter_class {
+        el.animate_enter(class);
+    }
+    if let Some(class) = &config.leave_class {
+        el.animate_leave(class, config.leave_duration);
+    }
+    if let Some(class) = &config.move_class {
+        el.animate_move(class, config.move_duration);
+    }
+}
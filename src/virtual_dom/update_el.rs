@@ -1,4 +1,4 @@
-use super::{Attrs, El, Listener, Node, Style, Tag, Text};
+use super::{Attrs, El, Listener, Node, Props, Style, Tag, Text};
 use crate::browser::dom::lifecycle_hooks::{DidMount, DidUpdate, WillUnmount};
 
 /// `UpdateEl` is used to distinguish arguments in element-creation macros, and handle
@@ -76,6 +76,12 @@ impl<Ms> UpdateEl<El<Ms>> for Vec<Listener<Ms>> {
     }
 }
 
+impl<Ms> UpdateEl<El<Ms>> for Props<Ms> {
+    fn update(self, el: &mut El<Ms>) {
+        el.spread(self);
+    }
+}
+
 impl<Ms> UpdateEl<El<Ms>> for DidMount<Ms> {
     fn update(self, el: &mut El<Ms>) {
         el.hooks.did_mount = Some(self)
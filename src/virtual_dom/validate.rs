@@ -0,0 +1,257 @@
+//! This module contains a development-time check of vdom trees; see `El::validate`.
+
+use super::{diff::NodePath, html::VOID_ELEMENTS, At, AtValue, El, Ev, Node, Tag};
+use std::collections::HashMap;
+
+/// An obvious mistake found by `El::validate`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationWarning {
+    /// The attribute has no effect on the element; eg `href` on a `div`.
+    InvalidAttr { path: NodePath, tag: Tag, attr: At },
+    /// The element is a void element (eg `img` or `input`), so its children aren't rendered.
+    ChildrenInVoidElement { path: NodePath, tag: Tag },
+    /// The element has more listeners of the same event, so each event sends more messages;
+    /// eg because both a component and its wrapper add a `click` listener.
+    DuplicateListener { path: NodePath, trigger: Ev },
+    /// More elements in the tree have the same `id`.
+    DuplicateId { id: String, paths: Vec<NodePath> },
+    /// The child's tag isn't one of the allowed ones; see `El::assert_children_tags`.
+    DisallowedChild { path: NodePath, tag: Tag },
+}
+
+/// Attributes that are valid only on the listed HTML elements.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/HTML/Attributes)
+const RESTRICTED_ATTRS: &[(&str, &[&str])] = &[
+    ("action", &["form"]),
+    ("alt", &["area", "img", "input"]),
+    ("checked", &["input"]),
+    ("colspan", &["td", "th"]),
+    (
+        "disabled",
+        &[
+            "button", "fieldset", "input", "optgroup", "option", "select", "textarea",
+        ],
+    ),
+    ("download", &["a", "area"]),
+    ("for", &["label", "output"]),
+    (
+        "form",
+        &[
+            "button", "fieldset", "input", "object", "output", "select", "textarea",
+        ],
+    ),
+    ("formaction", &["button", "input"]),
+    ("formmethod", &["button", "input"]),
+    ("href", &["a", "area", "base", "link"]),
+    ("loading", &["iframe", "img"]),
+    ("method", &["form"]),
+    ("multiple", &["input", "select"]),
+    ("placeholder", &["input", "textarea"]),
+    ("poster", &["video"]),
+    ("rowspan", &["td", "th"]),
+    ("selected", &["option"]),
+    (
+        "src",
+        &[
+            "audio", "embed", "iframe", "img", "input", "script", "source", "track", "video",
+        ],
+    ),
+    (
+        "value",
+        &[
+            "button", "data", "input", "li", "meter", "option", "output", "param", "progress",
+            "select", "textarea",
+        ],
+    ),
+];
+
+/// Validate the element and its descendants; see `El::validate`.
+pub fn validate_el<Ms>(el: &El<Ms>) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+    let mut ids = HashMap::<String, Vec<NodePath>>::new();
+    validate_el_recursive(el, &mut Vec::new(), &mut ids, &mut warnings);
+
+    let mut duplicate_ids = ids
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(id, paths)| ValidationWarning::DuplicateId { id, paths })
+        .collect::<Vec<_>>();
+    // `HashMap` order isn't stable; order them by the first occurrence.
+    duplicate_ids.sort_by(|a, b| match (a, b) {
+        (
+            ValidationWarning::DuplicateId { paths: a, .. },
+            ValidationWarning::DuplicateId { paths: b, .. },
+        ) => a[0].cmp(&b[0]),
+        _ => std::cmp::Ordering::Equal,
+    });
+    warnings.append(&mut duplicate_ids);
+    warnings
+}
+
+/// Check the tags of the element's children; see `El::assert_children_tags`.
+#[cfg_attr(not(debug_assertions), allow(dead_code))]
+pub fn check_children_tags<Ms>(el: &El<Ms>, allowed: &[Tag]) -> Vec<ValidationWarning> {
+    el.children
+        .iter()
+        .enumerate()
+        .filter_map(|(index, child)| match child {
+            Node::Element(child) if !allowed.contains(&child.tag) => {
+                Some(ValidationWarning::DisallowedChild {
+                    path: vec![index],
+                    tag: child.tag.clone(),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn validate_el_recursive<Ms>(
+    el: &El<Ms>,
+    path: &mut NodePath,
+    ids: &mut HashMap<String, Vec<NodePath>>,
+    warnings: &mut Vec<ValidationWarning>,
+) {
+    // Only HTML elements are checked - SVG and custom elements have their own attributes.
+    let is_html = el.namespace.is_none() && !el.is_custom();
+    let tag_name = el.tag.name();
+
+    for (attr, val) in &el.attrs.vals {
+        if *attr == At::Id {
+            if let AtValue::Some(id) = val {
+                ids.entry(id.clone()).or_default().push(path.clone());
+            }
+        }
+        if !is_html {
+            continue;
+        }
+        let allowed_tags = RESTRICTED_ATTRS
+            .iter()
+            .find(|(name, _)| *name == attr.as_str())
+            .map(|(_, tags)| tags);
+        if let Some(allowed_tags) = allowed_tags {
+            if !allowed_tags.contains(&tag_name.as_ref()) {
+                warnings.push(ValidationWarning::InvalidAttr {
+                    path: path.clone(),
+                    tag: el.tag.clone(),
+                    attr: attr.clone(),
+                });
+            }
+        }
+    }
+
+    let mut duplicate_triggers = Vec::<Ev>::new();
+    for (index, listener) in el.listeners.iter().enumerate() {
+        let trigger = listener.trigger;
+        let is_duplicate = el.listeners[..index]
+            .iter()
+            .any(|previous| previous.trigger == trigger);
+        if is_duplicate && !duplicate_triggers.contains(&trigger) {
+            duplicate_triggers.push(trigger);
+            warnings.push(ValidationWarning::DuplicateListener {
+                path: path.clone(),
+                trigger,
+            });
+        }
+    }
+
+    if is_html
+        && VOID_ELEMENTS.contains(&tag_name.as_ref())
+        && el.children.iter().any(|child| !child.is_empty())
+    {
+        warnings.push(ValidationWarning::ChildrenInVoidElement {
+            path: path.clone(),
+            tag: el.tag.clone(),
+        });
+    }
+
+    validate_children(&el.children, path, ids, warnings);
+}
+
+fn validate_children<Ms>(
+    children: &[Node<Ms>],
+    path: &mut NodePath,
+    ids: &mut HashMap<String, Vec<NodePath>>,
+    warnings: &mut Vec<ValidationWarning>,
+) {
+    for (index, child) in children.iter().enumerate() {
+        path.push(index);
+        match child {
+            Node::Element(child) => validate_el_recursive(child, path, ids, warnings),
+            Node::Portal { children, .. } => validate_children(children, path, ids, warnings),
+            Node::Text(_) | Node::Empty => (),
+        }
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::browser::dom::event_handler::simple_ev;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn warnings() {
+        let mut link = El::empty(Tag::Div);
+        link.add_attr("href", "/").add_attr("id", "a");
+        let mut image = El::empty(Tag::Img);
+        image
+            .add_attr("src", "logo.png")
+            .add_attr("id", "a")
+            .add_text("logo");
+        let mut input = El::empty(Tag::Input);
+        input
+            .add_attr("value", "x")
+            .add_child(Node::Empty)
+            .add_listener(simple_ev(Ev::Input, ()))
+            .add_listener(simple_ev(Ev::Input, ()))
+            .add_listener(simple_ev(Ev::Change, ()))
+            .add_listener(simple_ev(Ev::Input, ()));
+        let mut el: El<()> = El::empty(Tag::Section);
+        el.add_child(Node::Element(link))
+            .add_child(Node::Element(image))
+            .add_child(Node::Element(input));
+
+        assert_eq!(
+            validate_el(&el),
+            vec![
+                ValidationWarning::InvalidAttr {
+                    path: vec![0],
+                    tag: Tag::Div,
+                    attr: At::Href,
+                },
+                ValidationWarning::ChildrenInVoidElement {
+                    path: vec![1],
+                    tag: Tag::Img,
+                },
+                ValidationWarning::DuplicateListener {
+                    path: vec![2],
+                    trigger: Ev::Input,
+                },
+                ValidationWarning::DuplicateId {
+                    id: "a".into(),
+                    paths: vec![vec![0], vec![1]],
+                },
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn children_tags() {
+        let mut tabs: El<()> = El::empty(Tag::Custom("x-tabs".into()));
+        tabs.add_child(Node::Element(El::empty(Tag::Custom("x-tab".into()))))
+            .add_text(" ")
+            .add_child(Node::Element(El::empty(Tag::Div)))
+            .add_child(Node::Empty)
+            .add_child(Node::Element(El::empty(Tag::Custom("x-tab".into()))));
+
+        assert_eq!(
+            check_children_tags(&tabs, &[Tag::Custom("x-tab".into())]),
+            vec![ValidationWarning::DisallowedChild {
+                path: vec![2],
+                tag: Tag::Div,
+            }]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use super::St;
+
 // ------------- CSSValue -------------
 
 /// CSS property value.
@@ -25,6 +27,223 @@ impl<T: ToString> From<T> for CSSValue {
     }
 }
 
+impl CSSValue {
+    /// Create a `CSSValue` from a raw string; eg from a `style` attribute of imported HTML.
+    /// Whitespace is normalized, so the same values written differently are equal
+    /// (`"1px  solid   red"` => `"1px solid red"`, `"rgb( 0 ,0,0 )"` => `"rgb(0, 0, 0)"`).
+    /// Values of custom properties (`--my-color`) are only trimmed.
+    /// An empty value is `CSSValue::Ignored`.
+    pub fn parse(property: &St, raw: &str) -> CSSValue {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            CSSValue::Ignored
+        } else if property.as_str().starts_with("--") {
+            CSSValue::Some(raw.to_string())
+        } else {
+            CSSValue::Some(normalize_whitespace(raw))
+        }
+    }
+
+    /// An `rgb(r, g, b)` color. Components are clamped to `0..=255` and rounded;
+    /// eg `CSSValue::rgb(300., 127.6, -5.)` => `rgb(255, 128, 0)`.
+    pub fn rgb(r: f64, g: f64, b: f64) -> CSSValue {
+        CSSValue::Some(format!(
+            "rgb({}, {}, {})",
+            color_channel(r),
+            color_channel(g),
+            color_channel(b)
+        ))
+    }
+
+    /// An `rgba(r, g, b, a)` color. Color components are clamped like in `CSSValue::rgb`,
+    /// the alpha is clamped to `0..=1`.
+    pub fn rgba(r: f64, g: f64, b: f64, a: f64) -> CSSValue {
+        CSSValue::Some(format!(
+            "rgba({}, {}, {}, {})",
+            color_channel(r),
+            color_channel(g),
+            color_channel(b),
+            clamp(a, 1.)
+        ))
+    }
+
+    /// An `hsl(h, s%, l%)` color. The hue is an angle in degrees, so it's wrapped
+    /// into `0..360` (`-30.` => `330`); saturation and lightness are clamped to `0..=100`.
+    pub fn hsl(h: f64, s: f64, l: f64) -> CSSValue {
+        let h = if h.is_finite() {
+            h.rem_euclid(360.)
+        } else {
+            0.
+        };
+        CSSValue::Some(format!(
+            "hsl({}, {}%, {}%)",
+            h,
+            clamp(s, 100.),
+            clamp(l, 100.)
+        ))
+    }
+
+    /// A reference to the custom property `--{token}`; eg `CSSValue::var("primary")` => `var(--primary)`.
+    pub fn var(token: &str) -> CSSValue {
+        CSSValue::Some(format!("var(--{})", token))
+    }
+
+    /// Split the value into its components; eg a shorthand `"1px solid #FFF"` into
+    /// a dimension, a keyword and a color. `CSSValue::Ignored` has no parts.
+    pub fn parts(&self) -> Vec<CSSValuePart> {
+        match self {
+            CSSValue::Ignored => Vec::new(),
+            CSSValue::Some(value) => split_top_level(&normalize_whitespace(value))
+                .into_iter()
+                .map(CSSValuePart::from_token)
+                .collect(),
+        }
+    }
+}
+
+/// Clamp the value to `0..=max`; `NaN` is `0`.
+fn clamp(value: f64, max: f64) -> f64 {
+    if value.is_nan() {
+        0.
+    } else {
+        value.max(0.).min(max)
+    }
+}
+
+fn color_channel(value: f64) -> u8 {
+    clamp(value, 255.).round() as u8
+}
+
+/// A component of a CSS value; see `CSSValue::parts`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CSSValuePart {
+    /// A number with a unit; eg `12px`, `50%` or `0.3s`. The unit is lowercase.
+    Dimension { value: f64, unit: String },
+    /// A number without a unit; eg `1.5` in `line-height: 1.5`.
+    Number(f64),
+    /// A hex color (lowercase) or a color function; eg `#fff` or `rgb(0, 0, 0)`.
+    Color(String),
+    /// A lowercase identifier; eg `solid`, `auto`, or a named color like `red`.
+    Keyword(String),
+    /// Anything else; eg a string, `url(..)` or `calc(..)`.
+    Raw(String),
+}
+
+impl CSSValuePart {
+    fn from_token(token: &str) -> Self {
+        let lowercase = token.to_lowercase();
+
+        let hex = lowercase.trim_start_matches('#');
+        if lowercase.starts_with('#')
+            && [3, 4, 6, 8].contains(&hex.len())
+            && hex.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return CSSValuePart::Color(lowercase);
+        }
+        if ["rgb(", "rgba(", "hsl(", "hsla("]
+            .iter()
+            .any(|function| lowercase.starts_with(function))
+        {
+            return CSSValuePart::Color(token.to_string());
+        }
+
+        let number_end = token
+            .char_indices()
+            .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && (c == '-' || c == '+'))))
+            .map_or(token.len(), |(i, _)| i);
+        if let Ok(value) = token[..number_end].parse::<f64>() {
+            let unit = &lowercase[number_end..];
+            if unit.is_empty() {
+                return CSSValuePart::Number(value);
+            }
+            if unit == "%" || unit.chars().all(|c| c.is_ascii_alphabetic()) {
+                return CSSValuePart::Dimension {
+                    value,
+                    unit: unit.to_string(),
+                };
+            }
+        }
+
+        if token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            CSSValuePart::Keyword(lowercase)
+        } else {
+            CSSValuePart::Raw(token.to_string())
+        }
+    }
+}
+
+/// Collapse whitespace outside of strings; there is no space after `(`, before `)`
+/// and before `,`, and there is exactly one space after `,`.
+fn normalize_whitespace(raw: &str) -> String {
+    let mut result = String::with_capacity(raw.len());
+    let mut quote = None;
+    let mut pending_space = false;
+
+    for c in raw.trim().chars() {
+        if let Some(quote_char) = quote {
+            result.push(c);
+            if c == quote_char {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            c if c.is_whitespace() => pending_space = true,
+            ')' => {
+                pending_space = false;
+                result.push(c);
+            }
+            ',' => {
+                result.push(c);
+                pending_space = true;
+            }
+            _ => {
+                if pending_space && !result.ends_with('(') {
+                    result.push(' ');
+                }
+                pending_space = false;
+                if c == '"' || c == '\'' {
+                    quote = Some(c);
+                }
+                result.push(c);
+            }
+        }
+    }
+    result
+}
+
+/// Split the value on whitespace and commas, except inside of strings and parentheses.
+fn split_top_level(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0_usize;
+    let mut quote = None;
+    let mut start = 0;
+
+    for (i, c) in value.char_indices() {
+        match (quote, c) {
+            (Some(quote_char), c) if c == quote_char => quote = None,
+            (Some(_), _) => (),
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, c) if depth == 0 && (c.is_whitespace() || c == ',') => {
+                if start < i {
+                    parts.push(&value[start..i]);
+                }
+                start = i + c.len_utf8();
+            }
+            _ => (),
+        }
+    }
+    if start < value.len() {
+        parts.push(&value[start..]);
+    }
+    parts
+}
+
 // ----------- ToCSSValue impls ------------
 
 // impl ToCSSValue for CSSValue
@@ -115,3 +334,459 @@ impl AsAtValue for bool {
         }
     }
 }
+
+// ------------- PopoverMode -------------
+
+/// Value of the attribute `popover`.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/popover)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PopoverMode {
+    /// The popover can be closed by clicking outside of it or by pressing Esc,
+    /// and showing it closes other `auto` popovers.
+    Auto,
+    /// The popover has to be closed explicitly, eg by its toggle button.
+    Manual,
+}
+
+impl PopoverMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            PopoverMode::Auto => "auto",
+            PopoverMode::Manual => "manual",
+        }
+    }
+}
+
+// ------------- DialogState -------------
+
+/// Whether and how a `dialog` created by `El::dialog` is shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DialogState {
+    Closed,
+    /// Shown by `show()`; the rest of the page stays interactive.
+    Open,
+    /// Shown by `showModal()`; the rest of the page is inert and Esc closes the dialog.
+    Modal,
+}
+
+impl DialogState {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DialogState::Closed => "closed",
+            DialogState::Open => "open",
+            DialogState::Modal => "modal",
+        }
+    }
+}
+
+// ------------- Decoding -------------
+
+/// A hint for decoding an image; see `El::decoding`.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/HTMLImageElement/decoding)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decoding {
+    /// Decode the image synchronously with other content.
+    Sync,
+    /// Decode the image asynchronously, so other content can be rendered sooner.
+    Async,
+    /// Let the browser decide.
+    Auto,
+}
+
+impl Decoding {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Decoding::Sync => "sync",
+            Decoding::Async => "async",
+            Decoding::Auto => "auto",
+        }
+    }
+}
+
+// ------------- Dir -------------
+
+/// Value of the attribute `dir`; the direction of the element's text.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/dir)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dir {
+    /// Left to right; eg English.
+    Ltr,
+    /// Right to left; eg Arabic or Hebrew.
+    Rtl,
+    /// Let the browser infer the direction from the first strongly directional character
+    /// of the content; eg for user-generated text, whose language isn't known.
+    Auto,
+}
+
+impl Dir {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Dir::Ltr => "ltr",
+            Dir::Rtl => "rtl",
+            Dir::Auto => "auto",
+        }
+    }
+}
+
+// ------------- Language tags -------------
+
+/// Check the syntax of a [BCP 47](https://tools.ietf.org/html/bcp47) language tag
+/// (eg `en`, `pt-BR` or `zh-Hant-TW`); the subtags aren't checked against the registry.
+/// An empty tag (the language is unknown) is valid too.
+pub(crate) fn is_language_tag(tag: &str) -> bool {
+    if tag.is_empty() {
+        return true;
+    }
+    let mut subtags = tag.split('-');
+    let primary = subtags.next().unwrap_or_default();
+    let primary_is_valid = match primary.len() {
+        // A private use (`x-..`) or grandfathered (`i-..`) tag.
+        1 => primary.eq_ignore_ascii_case("x") || primary.eq_ignore_ascii_case("i"),
+        2..=8 => primary.chars().all(|c| c.is_ascii_alphabetic()),
+        _ => false,
+    };
+    primary_is_valid
+        && subtags.all(|subtag| {
+            (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+// ------------- Form controls -------------
+
+/// Value of the attributes `method` and `formmethod`; how the form is submitted.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/form#attr-method)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormMethod {
+    /// The form data are appended to the `action` URL.
+    Get,
+    /// The form data are sent in the request body.
+    Post,
+    /// Close the `dialog` containing the form instead of submitting it.
+    Dialog,
+}
+
+impl FormMethod {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FormMethod::Get => "get",
+            FormMethod::Post => "post",
+            FormMethod::Dialog => "dialog",
+        }
+    }
+}
+
+/// Value of the attribute `inputmode`; the virtual keyboard shown for the field.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/inputmode)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputMode {
+    /// No virtual keyboard; eg for fields with their own input UI.
+    None,
+    Text,
+    Decimal,
+    Numeric,
+    Tel,
+    Search,
+    Email,
+    Url,
+}
+
+impl InputMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            InputMode::None => "none",
+            InputMode::Text => "text",
+            InputMode::Decimal => "decimal",
+            InputMode::Numeric => "numeric",
+            InputMode::Tel => "tel",
+            InputMode::Search => "search",
+            InputMode::Email => "email",
+            InputMode::Url => "url",
+        }
+    }
+}
+
+/// Value of the attribute `enterkeyhint`; the label of the virtual keyboard's enter key.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/enterkeyhint)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnterKeyHint {
+    Enter,
+    Done,
+    Go,
+    Next,
+    Previous,
+    Search,
+    Send,
+}
+
+impl EnterKeyHint {
+    pub fn as_str(&self) -> &str {
+        match self {
+            EnterKeyHint::Enter => "enter",
+            EnterKeyHint::Done => "done",
+            EnterKeyHint::Go => "go",
+            EnterKeyHint::Next => "next",
+            EnterKeyHint::Previous => "previous",
+            EnterKeyHint::Search => "search",
+            EnterKeyHint::Send => "send",
+        }
+    }
+}
+
+/// Value of the attribute `autocapitalize`.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/autocapitalize)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoCapitalize {
+    Off,
+    Sentences,
+    Words,
+    Characters,
+}
+
+impl AutoCapitalize {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AutoCapitalize::Off => "off",
+            AutoCapitalize::Sentences => "sentences",
+            AutoCapitalize::Words => "words",
+            AutoCapitalize::Characters => "characters",
+        }
+    }
+}
+
+/// Common values of the attribute `autocomplete`; the kind of data the browser may fill in.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/HTML/Attributes/autocomplete)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Autocomplete {
+    Off,
+    On,
+    Name,
+    GivenName,
+    FamilyName,
+    Email,
+    Username,
+    NewPassword,
+    CurrentPassword,
+    OneTimeCode,
+    Organization,
+    StreetAddress,
+    AddressLevel1,
+    AddressLevel2,
+    PostalCode,
+    Country,
+    Tel,
+    Url,
+    Bday,
+    CcName,
+    CcNumber,
+    CcExp,
+    CcCsc,
+}
+
+impl Autocomplete {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Autocomplete::Off => "off",
+            Autocomplete::On => "on",
+            Autocomplete::Name => "name",
+            Autocomplete::GivenName => "given-name",
+            Autocomplete::FamilyName => "family-name",
+            Autocomplete::Email => "email",
+            Autocomplete::Username => "username",
+            Autocomplete::NewPassword => "new-password",
+            Autocomplete::CurrentPassword => "current-password",
+            Autocomplete::OneTimeCode => "one-time-code",
+            Autocomplete::Organization => "organization",
+            Autocomplete::StreetAddress => "street-address",
+            Autocomplete::AddressLevel1 => "address-level1",
+            Autocomplete::AddressLevel2 => "address-level2",
+            Autocomplete::PostalCode => "postal-code",
+            Autocomplete::Country => "country",
+            Autocomplete::Tel => "tel",
+            Autocomplete::Url => "url",
+            Autocomplete::Bday => "bday",
+            Autocomplete::CcName => "cc-name",
+            Autocomplete::CcNumber => "cc-number",
+            Autocomplete::CcExp => "cc-exp",
+            Autocomplete::CcCsc => "cc-csc",
+        }
+    }
+}
+
+// ------------- ARIA -------------
+
+/// Value of the attribute `aria-live`; how screen readers announce the region's updates.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA/ARIA_Live_Regions)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Politeness {
+    /// Updates aren't announced.
+    Off,
+    /// Updates are announced when the user is idle; eg status messages.
+    Polite,
+    /// Updates are announced immediately; eg errors.
+    Assertive,
+}
+
+impl Politeness {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Politeness::Off => "off",
+            Politeness::Polite => "polite",
+            Politeness::Assertive => "assertive",
+        }
+    }
+}
+
+/// Value of the attribute `role`; landmarks that structure a page and common live regions.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA/Roles)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// The site-wide header.
+    Banner,
+    Navigation,
+    /// The primary content of the page; there should be only one.
+    Main,
+    /// The site-wide footer; eg copyrights and links to privacy statements.
+    ContentInfo,
+    /// Content supporting the main content; eg related articles.
+    Complementary,
+    /// A significant section with an accessible name (eg `aria-label`).
+    Region,
+    Search,
+    /// A form with an accessible name; forms without it aren't landmarks.
+    Form,
+    /// An important message announced immediately; see `El::live_region`.
+    Alert,
+    /// An advisory message announced when the user is idle; see `El::live_region`.
+    Status,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Role::Banner => "banner",
+            Role::Navigation => "navigation",
+            Role::Main => "main",
+            Role::ContentInfo => "contentinfo",
+            Role::Complementary => "complementary",
+            Role::Region => "region",
+            Role::Search => "search",
+            Role::Form => "form",
+            Role::Alert => "alert",
+            Role::Status => "status",
+        }
+    }
+}
+
+// ------------- Easing -------------
+
+/// Timing function of CSS transitions and animations.
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/CSS/easing-function)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    Ease,
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    CubicBezier(f64, f64, f64, f64),
+}
+
+impl std::fmt::Display for Easing {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Easing::Ease => write!(f, "ease"),
+            Easing::Linear => write!(f, "linear"),
+            Easing::EaseIn => write!(f, "ease-in"),
+            Easing::EaseOut => write!(f, "ease-out"),
+            Easing::EaseInOut => write!(f, "ease-in-out"),
+            Easing::CubicBezier(x1, y1, x2, y2) => {
+                write!(f, "cubic-bezier({}, {}, {}, {})", x1, y1, x2, y2)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn language_tags() {
+        for tag in &["", "en", "pt-BR", "zh-Hant-TW", "de-CH-1901", "x-klingon"] {
+            assert!(is_language_tag(tag), "{}", tag);
+        }
+        for tag in &["e", "en_US", "en-", "-en", "en-verylongsubtag", "cs-CZ!"] {
+            assert!(!is_language_tag(tag), "{}", tag);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn colors() {
+        assert_eq!(
+            CSSValue::rgb(300., 127.6, -5.),
+            CSSValue::Some("rgb(255, 128, 0)".into())
+        );
+        assert_eq!(
+            CSSValue::rgba(0., 0., 0., 1.5),
+            CSSValue::Some("rgba(0, 0, 0, 1)".into())
+        );
+        assert_eq!(
+            CSSValue::rgba(10., 20., 30., 0.25),
+            CSSValue::Some("rgba(10, 20, 30, 0.25)".into())
+        );
+        assert_eq!(
+            CSSValue::hsl(-30., 120., 50.5),
+            CSSValue::Some("hsl(330, 100%, 50.5%)".into())
+        );
+        assert_eq!(
+            CSSValue::hsl(720., f64::NAN, -1.),
+            CSSValue::Some("hsl(0, 0%, 0%)".into())
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn parse_normalizes_whitespace() {
+        assert_eq!(
+            CSSValue::parse(&St::Border, "  1px   solid\tred "),
+            CSSValue::Some("1px solid red".into())
+        );
+        assert_eq!(
+            CSSValue::parse(&St::Color, "rgb( 0 ,0,  0 )"),
+            CSSValue::Some("rgb(0, 0, 0)".into())
+        );
+        assert_eq!(
+            CSSValue::parse(&St::FontFamily, "'Open  Sans' ,serif"),
+            CSSValue::Some("'Open  Sans', serif".into())
+        );
+        assert_eq!(CSSValue::parse(&St::Color, "  "), CSSValue::Ignored);
+    }
+
+    #[wasm_bindgen_test]
+    fn parts_of_shorthand() {
+        assert_eq!(
+            CSSValue::parse(&St::Border, "1.5PX solid #FFF").parts(),
+            vec![
+                CSSValuePart::Dimension {
+                    value: 1.5,
+                    unit: "px".into()
+                },
+                CSSValuePart::Keyword("solid".into()),
+                CSSValuePart::Color("#fff".into()),
+            ]
+        );
+        assert_eq!(
+            CSSValue::from("calc(100% - 2em) 50% rgba(0, 0, 0, 0.5) 2").parts(),
+            vec![
+                CSSValuePart::Raw("calc(100% - 2em)".into()),
+                CSSValuePart::Dimension {
+                    value: 50.,
+                    unit: "%".into()
+                },
+                CSSValuePart::Color("rgba(0, 0, 0, 0.5)".into()),
+                CSSValuePart::Number(2.),
+            ]
+        );
+        assert!(CSSValue::Ignored.parts().is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{El, Node};
+use super::{El, Node, Text};
 
 pub trait View<Ms: 'static> {
     fn els(self) -> Vec<Node<Ms>>;
@@ -27,3 +27,16 @@ impl<Ms: 'static> View<Ms> for Vec<Node<Ms>> {
         self
     }
 }
+
+/// A bare text root; eg a localized string rendered standalone.
+impl<Ms: 'static> View<Ms> for Text {
+    fn els(self) -> Vec<Node<Ms>> {
+        vec![Node::Text(self)]
+    }
+}
+
+impl<Ms: 'static> View<Ms> for String {
+    fn els(self) -> Vec<Node<Ms>> {
+        vec![Node::new_text(self)]
+    }
+}
@@ -0,0 +1,118 @@
+//! This module contains `virtual_window` - rendering of only the visible part of a large list
+//! (list virtualization), with spacers in place of the other items.
+
+use super::{El, Node, St, Tag};
+use std::ops::Range;
+
+/// Keys of the spacers, so they're kept when the window slides.
+const TOP_SPACER_KEY: &str = "seed-window-top";
+const BOTTOM_SPACER_KEY: &str = "seed-window-bottom";
+
+/// Render the items of the `visible_range` (eg from `visible_range`) of the list with `total`
+/// items, each `item_height` pixels high; eg
+/// `div![virtual_window(model.rows.len(), range, 24., |index| row(&model.rows[index]))]`.
+///
+/// The items get their indices as keys (see `El::key`), so their DOM nodes are reused when
+/// the window slides, and `div` spacers above and below them keep the list's scroll height.
+/// The range is clamped to the list. The items can't be `tr`s - the spacers would be invalid
+/// children of their `tbody`.
+pub fn virtual_window<Ms>(
+    total: usize,
+    visible_range: Range<usize>,
+    item_height: f64,
+    render: impl Fn(usize) -> El<Ms>,
+) -> Vec<Node<Ms>> {
+    let end = visible_range.end.min(total);
+    let start = visible_range.start.min(end);
+
+    let mut nodes = Vec::with_capacity(end - start + 2);
+    nodes.push(spacer(TOP_SPACER_KEY, start as f64 * item_height));
+    nodes.extend((start..end).map(|index| {
+        let mut el = render(index);
+        el.key(index);
+        Node::Element(el)
+    }));
+    nodes.push(spacer(
+        BOTTOM_SPACER_KEY,
+        (total - end) as f64 * item_height,
+    ));
+    nodes
+}
+
+/// The items of the list visible in its scrolled container (plus `overscan` items on both
+/// sides, so they're ready before they're scrolled into view); see `virtual_window`.
+pub fn visible_range(
+    scroll_top: f64,
+    viewport_height: f64,
+    item_height: f64,
+    total: usize,
+    overscan: usize,
+) -> Range<usize> {
+    if item_height <= 0. {
+        return 0..0;
+    }
+    let first = (scroll_top.max(0.) / item_height).floor() as usize;
+    let last = ((scroll_top.max(0.) + viewport_height) / item_height).ceil() as usize;
+    let start = first.saturating_sub(overscan).min(total);
+    let end = last.saturating_add(overscan).min(total);
+    start..end
+}
+
+fn spacer<Ms>(key: &str, height: f64) -> Node<Ms> {
+    let mut el = El::empty(Tag::Div);
+    el.key(key)
+        .add_attr("aria-hidden", "true")
+        .add_style(St::Height, format!("{}px", height));
+    Node::Element(el)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::virtual_dom::CSSValue;
+    use wasm_bindgen_test::*;
+
+    fn height(node: &Node<()>) -> Option<CSSValue> {
+        match node {
+            Node::Element(el) => el.style.vals.get(&St::Height).cloned(),
+            _ => None,
+        }
+    }
+
+    fn key(node: &Node<()>) -> Option<String> {
+        match node {
+            Node::Element(el) => el.key.clone(),
+            _ => None,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn window() {
+        let nodes = virtual_window(100, 10..13, 20., |_| El::empty(Tag::Li));
+
+        assert_eq!(
+            nodes.iter().map(key).collect::<Vec<_>>(),
+            vec![
+                Some(TOP_SPACER_KEY.into()),
+                Some("10".into()),
+                Some("11".into()),
+                Some("12".into()),
+                Some(BOTTOM_SPACER_KEY.into()),
+            ]
+        );
+        assert_eq!(height(&nodes[0]), Some(CSSValue::Some("200px".into())));
+        assert_eq!(height(&nodes[4]), Some(CSSValue::Some("1740px".into())));
+
+        let clamped = virtual_window(5, 3..10, 20., |_| El::empty(Tag::Li));
+        assert_eq!(clamped.len(), 4);
+        assert_eq!(height(&clamped[3]), Some(CSSValue::Some("0px".into())));
+    }
+
+    #[wasm_bindgen_test]
+    fn visible_ranges() {
+        assert_eq!(visible_range(0., 100., 20., 1000, 0), 0..5);
+        assert_eq!(visible_range(210., 100., 20., 1000, 2), 8..18);
+        assert_eq!(visible_range(19_950., 100., 20., 1000, 2), 995..1000);
+        assert_eq!(visible_range(0., 100., 0., 1000, 2), 0..0);
+    }
+}